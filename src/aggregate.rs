@@ -0,0 +1,970 @@
+// ============================================================
+// Aggregate: 跨文件聚合 (类型体外成员归并、头文件配对、类型分部归并)
+// ============================================================
+
+use crate::detector::Language;
+use crate::symbol::{FileMap, Location, Signature, Symbol, SymbolKind};
+use std::collections::{HashMap, HashSet};
+use std::path::Path;
+
+/// 容器索引: (作用域, 限定名 `ns::Foo`) → 所在文件下标
+type ContainerIndex = HashMap<(String, String), Vec<usize>>;
+
+/// 将带 owner 的符号移到同一作用域内所属容器 (类型/命名空间) 的 children 下
+/// - owner 依次按外层命名空间前缀由内向外解析，再按唯一后缀匹配
+/// - 同文件的容器优先，其次按文件顺序取第一个
+/// - 容器内已有同名声明时，只在声明上记录定义位置
+/// - 跨文件移动时在符号上记录原文件路径
+/// - 找不到容器的符号保持原位
+pub fn attach_owned_members(maps: &mut [FileMap]) {
+    // 第一步: 容器索引
+    let mut containers: ContainerIndex = HashMap::new();
+    for (i, map) in maps.iter().enumerate() {
+        let Some(scope) = owner_scope(map) else {
+            continue;
+        };
+        index_containers(&map.symbols, &mut Vec::new(), &mut |qualified| {
+            containers
+                .entry((scope.clone(), qualified))
+                .or_default()
+                .push(i);
+        });
+    }
+
+    // 第二步: 摘出可归并的成员 (任意深度)
+    let mut moves: Vec<(usize, String, Symbol)> = Vec::new();
+    for (i, map) in maps.iter_mut().enumerate() {
+        let Some(scope) = owner_scope(map) else {
+            continue;
+        };
+        let resolve = |prefix: &[String], owner: &str| {
+            let qualified = resolve_owner(&containers, &scope, prefix, owner)?;
+            let files = &containers[&(scope.clone(), qualified.clone())];
+            let target = *files.iter().find(|&&f| f == i).unwrap_or(&files[0]);
+            Some((target, qualified))
+        };
+        let mut taken = Vec::new();
+        detach_members(&mut map.symbols, &mut Vec::new(), &resolve, &mut taken);
+
+        for (target, qualified, mut sym) in taken {
+            if target != i {
+                sym.path = Some(map.path.clone());
+            }
+            moves.push((target, qualified, sym));
+        }
+    }
+
+    // 第三步: 挂到目标容器下
+    for (target, qualified, sym) in moves {
+        let path: Vec<&str> = qualified.split("::").collect();
+        let home = maps[target].path.clone();
+        if let Some(parent) = find_container(&mut maps[target].symbols, &path) {
+            insert_member(parent, sym, &home);
+        }
+    }
+}
+
+/// 递归收集容器的限定名
+fn index_containers(symbols: &[Symbol], prefix: &mut Vec<String>, add: &mut impl FnMut(String)) {
+    for sym in symbols.iter().filter(|s| is_container(s.kind)) {
+        prefix.push(sym.name.clone());
+        add(prefix.join("::"));
+        index_containers(&sym.children, prefix, add);
+        prefix.pop();
+    }
+}
+
+/// 递归摘出 owner 可解析的符号，prefix 为外层命名空间/类型名
+fn detach_members(
+    symbols: &mut Vec<Symbol>,
+    prefix: &mut Vec<String>,
+    resolve: &impl Fn(&[String], &str) -> Option<(usize, String)>,
+    taken: &mut Vec<(usize, String, Symbol)>,
+) {
+    for mut sym in std::mem::take(symbols) {
+        let target = sym
+            .owner
+            .as_deref()
+            .and_then(|owner| resolve(prefix, owner));
+        match target {
+            Some((target, qualified)) => taken.push((target, qualified, sym)),
+            None => {
+                if is_container(sym.kind) {
+                    prefix.push(sym.name.clone());
+                    detach_members(&mut sym.children, prefix, resolve, taken);
+                    prefix.pop();
+                }
+                symbols.push(sym);
+            }
+        }
+    }
+}
+
+/// 由内向外尝试 `prefix::owner`，最后按唯一后缀 `*::owner` 匹配
+fn resolve_owner(
+    containers: &ContainerIndex,
+    scope: &str,
+    prefix: &[String],
+    owner: &str,
+) -> Option<String> {
+    let exists =
+        |qualified: &str| containers.contains_key(&(scope.to_string(), qualified.to_string()));
+
+    for depth in (0..=prefix.len()).rev() {
+        let mut parts = prefix[..depth].to_vec();
+        parts.push(owner.to_string());
+        let qualified = parts.join("::");
+        if exists(&qualified) {
+            return Some(qualified);
+        }
+    }
+
+    let suffix = format!("::{}", owner);
+    let mut matches = containers
+        .keys()
+        .filter(|(s, q)| s == scope && q.ends_with(&suffix))
+        .map(|(_, q)| q);
+    match (matches.next(), matches.next()) {
+        (Some(qualified), None) => Some(qualified.clone()),
+        _ => None,
+    }
+}
+
+/// 按限定名逐级查找容器
+fn find_container<'a>(symbols: &'a mut [Symbol], path: &[&str]) -> Option<&'a mut Symbol> {
+    let (first, rest) = path.split_first()?;
+    let sym = symbols
+        .iter_mut()
+        .find(|s| is_container(s.kind) && s.name == *first)?;
+    if rest.is_empty() {
+        Some(sym)
+    } else {
+        find_container(&mut sym.children, rest)
+    }
+}
+
+/// 有可配对的声明 (见 declares) 时合并到声明上，否则追加为子符号
+fn insert_member(parent: &mut Symbol, mut sym: Symbol, home: &str) {
+    if parent.kind == SymbolKind::Namespace && sym.kind == SymbolKind::Method {
+        sym.kind = SymbolKind::Function;
+    }
+
+    let declaration = parent.children.iter_mut().find(|c| declares(c, &sym));
+    match declaration {
+        Some(declaration) => {
+            declaration.definition = Some(Location {
+                path: sym.path.take().unwrap_or_else(|| home.to_string()),
+                range: sym.range,
+            });
+        }
+        None => parent.children.push(sym),
+    }
+}
+
+/// 头文件 / 实现文件扩展名 (C、C++、Objective-C)
+const HEADER_EXTENSIONS: &[&str] = &["h", "hh", "hpp", "hxx"];
+const SOURCE_EXTENSIONS: &[&str] = &["c", "cc", "cpp", "cxx", "m", "mm"];
+
+/// 将实现文件中的定义合并到同名头文件的声明上
+/// - `foo.c` / `foo.m` / `foo.cpp` 与同目录的 `foo.h` 配对，同目录没有时取全局唯一的同名头文件
+/// - 声明记录定义位置，实现文件中不再重复列出
+/// - 头文件中没有声明的定义 (如 static 函数) 留在实现文件
+pub fn pair_headers(maps: &mut [FileMap]) {
+    for (header, source) in header_pairs(maps) {
+        let (header, source) = if header < source {
+            let (left, right) = maps.split_at_mut(source);
+            (&mut left[header], &mut right[0])
+        } else {
+            let (left, right) = maps.split_at_mut(header);
+            (&mut right[0], &mut left[source])
+        };
+        let definitions = std::mem::take(&mut source.symbols);
+        source.symbols = merge_definitions(&mut header.symbols, definitions, &source.path);
+    }
+}
+
+/// (头文件下标, 实现文件下标)
+fn header_pairs(maps: &[FileMap]) -> Vec<(usize, usize)> {
+    let is_c_family = |map: &FileMap| {
+        [Language::C, Language::Cpp, Language::ObjC]
+            .iter()
+            .any(|lang| map.language == lang.as_str())
+    };
+    let split = |map: &FileMap, extensions: &[&str]| {
+        let path = Path::new(&map.path);
+        let ext = path.extension()?.to_str()?;
+        if !extensions.contains(&ext) || !is_c_family(map) {
+            return None;
+        }
+        Some((path.parent()?.to_path_buf(), path.file_stem()?.to_owned()))
+    };
+
+    let headers: Vec<_> = maps
+        .iter()
+        .enumerate()
+        .filter_map(|(i, map)| Some((i, split(map, HEADER_EXTENSIONS)?)))
+        .collect();
+
+    let mut pairs = Vec::new();
+    for (i, map) in maps.iter().enumerate() {
+        let Some((dir, stem)) = split(map, SOURCE_EXTENSIONS) else {
+            continue;
+        };
+        let same_stem: Vec<_> = headers.iter().filter(|(_, (_, s))| *s == stem).collect();
+        let header =
+            same_stem
+                .iter()
+                .find(|(_, (d, _))| *d == dir)
+                .or(match same_stem.as_slice() {
+                    [only] => Some(only),
+                    _ => None,
+                });
+        if let Some((h, _)) = header {
+            pairs.push((*h, i));
+        }
+    }
+    pairs
+}
+
+/// 递归合并: 容器按名称配对后合并子符号，可调用符号记录定义位置，返回未配对的定义
+fn merge_definitions(
+    declarations: &mut [Symbol],
+    definitions: Vec<Symbol>,
+    path: &str,
+) -> Vec<Symbol> {
+    let mut rest = Vec::new();
+    for mut def in definitions {
+        let declaration = declarations
+            .iter_mut()
+            .find(|decl| same_container(decl, &def) || declares(decl, &def));
+        let Some(declaration) = declaration else {
+            rest.push(def);
+            continue;
+        };
+
+        let location = Location {
+            path: path.to_string(),
+            range: def.range.clone(),
+        };
+        if is_container(def.kind) || def.kind == SymbolKind::Extension {
+            if def.kind != SymbolKind::Namespace && declaration.definition.is_none() {
+                declaration.definition = Some(location);
+            }
+            let children = std::mem::take(&mut def.children);
+            def.children = merge_definitions(&mut declaration.children, children, path);
+            if !def.children.is_empty() {
+                rest.push(def);
+            }
+        } else {
+            declaration.definition = Some(location);
+        }
+    }
+    rest
+}
+
+/// 同名且同为容器，或同为同名扩展 (ObjC 类别名相同)
+fn same_container(decl: &Symbol, def: &Symbol) -> bool {
+    decl.name == def.name
+        && ((is_container(decl.kind) && is_container(def.kind))
+            || (decl.kind == SymbolKind::Extension
+                && def.kind == SymbolKind::Extension
+                && decl.detail == def.detail))
+}
+
+/// 定义可记录到该声明上: 同名可调用、声明没有函数体且尚无定义位置、参数类型相同 (区分重载)、
+/// C++ 显式特化的模板实参相同 (特化不作为泛型声明的定义)
+fn declares(decl: &Symbol, def: &Symbol) -> bool {
+    decl.name == def.name
+        && is_callable(decl.kind)
+        && is_callable(def.kind)
+        && !decl.has_body
+        && decl.definition.is_none()
+        && same_parameters(decl, def)
+        && template_args(decl) == template_args(def)
+}
+
+/// C++ 显式特化的模板实参 (extractor 记在 detail 开头)
+fn template_args(sym: &Symbol) -> Option<&str> {
+    sym.detail
+        .as_deref()
+        .filter(|detail| detail.starts_with('<'))
+}
+
+/// 参数类型列表相同 (忽略空白)；任一方没有签名时不比较
+fn same_parameters(decl: &Symbol, def: &Symbol) -> bool {
+    let (Some(decl), Some(def)) = (&decl.signature, &def.signature) else {
+        return true;
+    };
+    let types = |signature: &Signature| {
+        signature
+            .parameters
+            .iter()
+            .map(|p| p.type_name.as_deref().unwrap_or("").replace(' ', ""))
+            .collect::<Vec<_>>()
+    };
+    types(decl) == types(def)
+}
+
+// ------------------------------------------------------------
+// 类型分部归并 (Swift extension、ObjC category、C# partial class)
+// ------------------------------------------------------------
+
+/// 将同一类型分散的各部分挂到主声明下，呈现为一个逻辑类型
+/// - 主声明: 按文件顺序第一个同名 (含外层命名空间) 的非 extension 类型
+/// - 其余部分 (extension、category、C# 的其余 partial 声明) 整体作为主声明的子符号，
+///   保留各自的成员，跨文件时记录原文件路径
+/// - C# 只归并带 `partial` 修饰符的类型，同名的非 partial 类型各自保留
+/// - ObjC 的 @implementation 不嵌套: 主声明与其方法声明记录定义位置，没有声明的方法追加为成员；
+///   category 的 @implementation 同样记录到同名同分类的 @interface 上
+/// - 找不到主声明的 extension (如扩展标准库类型) 保持原位
+pub fn merge_type_parts(maps: &mut [FileMap]) {
+    // 第一步: 主声明索引 (语言, 限定名) → 文件下标
+    let mut primaries: HashMap<(String, String), usize> = HashMap::new();
+    for (i, map) in maps.iter().enumerate() {
+        if !has_type_parts(map) {
+            continue;
+        }
+        index_types(&map.symbols, &mut Vec::new(), &mut |qualified, sym| {
+            if is_type_part(&map.language, sym) {
+                primaries
+                    .entry((map.language.clone(), qualified))
+                    .or_insert(i);
+            }
+        });
+    }
+
+    // 第二步: 摘出其余部分 (主声明所在文件中第一个同名类型为主声明)
+    let mut claimed = HashSet::new();
+    let mut moves: Vec<(usize, String, Symbol)> = Vec::new();
+    for (i, map) in maps.iter_mut().enumerate() {
+        if !has_type_parts(map) {
+            continue;
+        }
+        let mut taken = Vec::new();
+        let language = map.language.clone();
+        let mut classify = |qualified: &str, sym: &Symbol| {
+            if !is_type_part(&language, sym) {
+                return None;
+            }
+            let key = (language.clone(), qualified.to_string());
+            let primary = *primaries.get(&key)?;
+            if sym.kind != SymbolKind::Extension && primary == i && claimed.insert(key) {
+                return None;
+            }
+            Some(primary)
+        };
+        detach_parts(&mut map.symbols, &mut Vec::new(), &mut classify, &mut taken);
+
+        for (target, qualified, mut part) in taken {
+            if target != i {
+                part.path = Some(map.path.clone());
+            }
+            moves.push((target, qualified, part));
+        }
+    }
+
+    // 第三步: 挂到主声明下
+    for (target, qualified, part) in moves {
+        let objc = maps[target].language == Language::ObjC.as_str();
+        let home = part
+            .path
+            .clone()
+            .unwrap_or_else(|| maps[target].path.clone());
+        let path: Vec<&str> = qualified.split("::").collect();
+        let Some(primary) = find_container(&mut maps[target].symbols, &path) else {
+            continue;
+        };
+        if objc && part.kind != SymbolKind::Extension {
+            link_implementation(primary, part, home);
+        } else if objc {
+            attach_category(&mut primary.children, part, home);
+        } else {
+            primary.children.push(part);
+        }
+    }
+
+    // 第四步: 找不到主声明的 ObjC category (如扩展 NSString) 在文件内配对
+    for map in maps.iter_mut() {
+        if map.language != Language::ObjC.as_str() {
+            continue;
+        }
+        for sym in std::mem::take(&mut map.symbols) {
+            if sym.kind == SymbolKind::Extension {
+                attach_category(&mut map.symbols, sym, map.path.clone());
+            } else {
+                map.symbols.push(sym);
+            }
+        }
+    }
+}
+
+/// ObjC category: 同名同分类的 @implementation 记录到已有的 @interface 上，否则追加
+fn attach_category(siblings: &mut Vec<Symbol>, part: Symbol, home: String) {
+    match siblings
+        .iter_mut()
+        .find(|decl| decl.kind == SymbolKind::Extension && same_container(decl, &part))
+    {
+        Some(decl) => link_implementation(decl, part, home),
+        None => siblings.push(part),
+    }
+}
+
+/// ObjC @implementation: 主声明记录定义位置，方法合并到同名声明上，其余成员追加
+fn link_implementation(primary: &mut Symbol, mut part: Symbol, home: String) {
+    if primary.definition.is_none() {
+        primary.definition = Some(Location {
+            path: home.clone(),
+            range: part.range.clone(),
+        });
+    }
+    let members = std::mem::take(&mut part.children);
+    for mut member in merge_definitions(&mut primary.children, members, &home) {
+        member.path = part.path.clone();
+        primary.children.push(member);
+    }
+}
+
+/// C# 只有 partial 类型可分部声明
+fn is_type_part(language: &str, sym: &Symbol) -> bool {
+    language != Language::CSharp.as_str()
+        || sym
+            .detail
+            .as_deref()
+            .is_some_and(|detail| detail.split_whitespace().any(|word| word == "partial"))
+}
+
+fn has_type_parts(map: &FileMap) -> bool {
+    [Language::Swift, Language::ObjC, Language::CSharp]
+        .iter()
+        .any(|lang| map.language == lang.as_str())
+}
+
+/// 递归收集类型的限定名 (extension 不作为主声明)
+fn index_types(
+    symbols: &[Symbol],
+    prefix: &mut Vec<String>,
+    add: &mut impl FnMut(String, &Symbol),
+) {
+    for sym in symbols.iter().filter(|s| is_container(s.kind)) {
+        prefix.push(sym.name.clone());
+        if is_type_like(sym.kind) {
+            add(prefix.join("::"), sym);
+        }
+        index_types(&sym.children, prefix, add);
+        prefix.pop();
+    }
+}
+
+/// 递归摘出类型分部，classify 返回 Some(主声明文件下标) 表示该符号是需要归并的分部
+fn detach_parts(
+    symbols: &mut Vec<Symbol>,
+    prefix: &mut Vec<String>,
+    classify: &mut impl FnMut(&str, &Symbol) -> Option<usize>,
+    taken: &mut Vec<(usize, String, Symbol)>,
+) {
+    for mut sym in std::mem::take(symbols) {
+        let is_part = is_type_like(sym.kind) || sym.kind == SymbolKind::Extension;
+        prefix.push(sym.name.clone());
+        let qualified = prefix.join("::");
+        let target = if is_part {
+            classify(&qualified, &sym)
+        } else {
+            None
+        };
+
+        match target {
+            Some(target) => taken.push((target, qualified, sym)),
+            None => {
+                if is_container(sym.kind) {
+                    detach_parts(&mut sym.children, prefix, classify, taken);
+                }
+                symbols.push(sym);
+            }
+        }
+        prefix.pop();
+    }
+}
+
+fn is_callable(kind: SymbolKind) -> bool {
+    matches!(
+        kind,
+        SymbolKind::Function | SymbolKind::Method | SymbolKind::Constructor
+    )
+}
+
+/// 成员归并的作用域，None 表示该语言不参与归并
+fn owner_scope(map: &FileMap) -> Option<String> {
+    if map.language == Language::Go.as_str() {
+        // Go: 同一目录即同一 package
+        let dir = Path::new(&map.path).parent().unwrap_or(Path::new(""));
+        Some(dir.to_string_lossy().to_string())
+    } else if map.language == Language::Cpp.as_str() {
+        // C++: 限定名全局唯一，头文件与实现文件可在不同目录
+        Some(String::new())
+    } else {
+        None
+    }
+}
+
+fn is_container(kind: SymbolKind) -> bool {
+    is_type_like(kind) || kind == SymbolKind::Namespace
+}
+
+fn is_type_like(kind: SymbolKind) -> bool {
+    matches!(
+        kind,
+        SymbolKind::Class
+            | SymbolKind::Struct
+            | SymbolKind::Interface
+            | SymbolKind::Type
+            | SymbolKind::Enum
+            | SymbolKind::Record
+    )
+}
+
+// ============================================================
+// 单元测试
+// ============================================================
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::symbol::Parameter;
+
+    #[test]
+    fn test_attach_in_same_file() {
+        let mut maps = vec![FileMap::new("user.go", "go").with_symbols(vec![
+            Symbol::at("User", SymbolKind::Struct, 1),
+            Symbol::at("Name", SymbolKind::Method, 5).with_owner("User"),
+            Symbol::at("main", SymbolKind::Function, 9),
+        ])];
+
+        attach_owned_members(&mut maps);
+
+        let names: Vec<_> = maps[0].symbols.iter().map(|s| s.name.as_str()).collect();
+        assert_eq!(names, vec!["User", "main"]);
+        assert_eq!(maps[0].symbols[0].children[0].name, "Name");
+        assert_eq!(maps[0].symbols[0].children[0].path, None);
+    }
+
+    #[test]
+    fn test_attach_across_files_of_package() {
+        let user = Symbol::at("User", SymbolKind::Struct, 1);
+        let save = Symbol::at("Save", SymbolKind::Method, 3).with_owner("User");
+        let load = Symbol::at("Load", SymbolKind::Method, 3).with_owner("User");
+        let mut maps = vec![
+            FileMap::new("pkg/a.go", "go").with_symbols(vec![user]),
+            FileMap::new("pkg/b.go", "go").with_symbols(vec![save]),
+            FileMap::new("other/c.go", "go").with_symbols(vec![load]),
+        ];
+
+        attach_owned_members(&mut maps);
+
+        let user = &maps[0].symbols[0];
+        assert_eq!(user.children.len(), 1);
+        assert_eq!(user.children[0].name, "Save");
+        assert_eq!(user.children[0].path.as_deref(), Some("pkg/b.go"));
+        assert!(maps[1].symbols.is_empty());
+        // 不同 package 的同名类型不归并
+        assert_eq!(maps[2].symbols[0].name, "Load");
+    }
+
+    #[test]
+    fn test_unknown_owner_stays_top_level() {
+        let string = Symbol::at("String", SymbolKind::Method, 1).with_owner("External");
+        let mut maps = vec![FileMap::new("a.go", "go").with_symbols(vec![string])];
+
+        attach_owned_members(&mut maps);
+        assert_eq!(maps[0].symbols[0].name, "String");
+    }
+
+    #[test]
+    fn test_other_languages_untouched() {
+        let mut maps = vec![FileMap::new("a.rs", "rust").with_symbols(vec![
+            Symbol::at("User", SymbolKind::Struct, 1),
+            Symbol::at("name", SymbolKind::Method, 5).with_owner("User"),
+        ])];
+
+        attach_owned_members(&mut maps);
+        assert_eq!(maps[0].symbols.len(), 2);
+    }
+
+    fn namespace(name: &str, children: Vec<Symbol>) -> Symbol {
+        let mut ns = Symbol::at(name, SymbolKind::Namespace, 1);
+        ns.children = children;
+        ns
+    }
+
+    #[test]
+    fn test_pair_c_header() {
+        let mut maps = vec![
+            FileMap::new("src/math.c", "c").with_symbols(vec![
+                Symbol::at("add", SymbolKind::Function, 3),
+                Symbol::at("helper", SymbolKind::Function, 7),
+            ]),
+            FileMap::new("src/math.h", "c").with_symbols(vec![
+                Symbol::at("add", SymbolKind::Function, 1),
+                Symbol::at("sub", SymbolKind::Function, 2),
+            ]),
+        ];
+
+        pair_headers(&mut maps);
+
+        let def = maps[1].symbols[0].definition.as_ref().unwrap();
+        assert_eq!(def.path, "src/math.c");
+        assert_eq!(def.range.start.line, 3);
+        assert!(maps[1].symbols[1].definition.is_none());
+        let rest: Vec<_> = maps[0].symbols.iter().map(|s| s.name.as_str()).collect();
+        assert_eq!(rest, vec!["helper"]);
+    }
+
+    #[test]
+    fn test_pair_objc_class_members() {
+        let mut interface = Symbol::at("User", SymbolKind::Class, 3);
+        interface.children = vec![Symbol::at("save", SymbolKind::Method, 4)];
+        let mut implementation = Symbol::at("User", SymbolKind::Class, 3);
+        implementation.children = vec![
+            Symbol::at("save", SymbolKind::Method, 4),
+            Symbol::at("reset", SymbolKind::Method, 8),
+        ];
+        let mut maps = vec![
+            FileMap::new("User.h", "objc").with_symbols(vec![interface]),
+            FileMap::new("User.m", "objc").with_symbols(vec![implementation]),
+        ];
+
+        pair_headers(&mut maps);
+
+        let user = &maps[0].symbols[0];
+        assert_eq!(user.definition.as_ref().unwrap().path, "User.m");
+        assert!(user.children[0].definition.is_some());
+        // 私有方法留在实现文件
+        assert_eq!(maps[1].symbols[0].children.len(), 1);
+        assert_eq!(maps[1].symbols[0].children[0].name, "reset");
+    }
+
+    #[test]
+    fn test_pair_prefers_same_directory() {
+        let f = |path, line| {
+            FileMap::new(path, "c").with_symbols(vec![Symbol::at("f", SymbolKind::Function, line)])
+        };
+        let mut maps = vec![
+            f("a/util.h", 1),
+            f("b/util.h", 1),
+            f("b/util.c", 2),
+            f("c/util.c", 2),
+        ];
+
+        pair_headers(&mut maps);
+
+        assert!(maps[0].symbols[0].definition.is_none());
+        assert!(maps[1].symbols[0].definition.is_some());
+        // 多个同名头文件且不在同目录时不配对
+        assert_eq!(maps[3].symbols.len(), 1);
+    }
+
+    #[test]
+    fn test_swift_extension_merged_across_files() {
+        let mut user = Symbol::at("User", SymbolKind::Class, 1);
+        user.children = vec![Symbol::at("name", SymbolKind::Property, 2)];
+        let mut ext = Symbol::at("User", SymbolKind::Extension, 1);
+        ext.children = vec![Symbol::at("display", SymbolKind::Function, 2)];
+        let string = Symbol::at("String", SymbolKind::Extension, 1);
+        let mut maps = vec![
+            FileMap::new("User+Display.swift", "swift").with_symbols(vec![ext]),
+            FileMap::new("User.swift", "swift").with_symbols(vec![user]),
+            FileMap::new("String+Util.swift", "swift").with_symbols(vec![string]),
+        ];
+
+        merge_type_parts(&mut maps);
+
+        assert!(maps[0].symbols.is_empty());
+        let user = &maps[1].symbols[0];
+        assert_eq!(user.children.len(), 2);
+        assert_eq!(user.children[1].kind, SymbolKind::Extension);
+        assert_eq!(user.children[1].path.as_deref(), Some("User+Display.swift"));
+        assert_eq!(user.children[1].children[0].name, "display");
+        // 没有主声明的 extension 保持原位
+        assert_eq!(maps[2].symbols.len(), 1);
+    }
+
+    #[test]
+    fn test_csharp_partial_class_merged_within_namespace() {
+        let partial = |line| {
+            let mut user = Symbol::at("User", SymbolKind::Class, line);
+            user.detail = Some("static partial".to_string());
+            user
+        };
+        let mut maps = vec![
+            FileMap::new("User.cs", "csharp").with_symbols(vec![namespace(
+                "App",
+                vec![partial(3), Symbol::at("Config", SymbolKind::Class, 8)],
+            )]),
+            FileMap::new("User.Generated.cs", "csharp").with_symbols(vec![
+                namespace(
+                    "App",
+                    vec![partial(5), Symbol::at("Config", SymbolKind::Class, 9)],
+                ),
+                namespace("Other", vec![partial(9)]),
+            ]),
+        ];
+
+        merge_type_parts(&mut maps);
+
+        let user = &maps[0].symbols[0].children[0];
+        assert_eq!(user.children.len(), 1);
+        assert_eq!(user.children[0].path.as_deref(), Some("User.Generated.cs"));
+        // 非 partial 的同名类型不归并
+        assert_eq!(maps[1].symbols[0].children.len(), 1);
+        assert_eq!(maps[1].symbols[0].children[0].name, "Config");
+        assert!(maps[0].symbols[0].children[1].children.is_empty());
+        // 不同命名空间的同名类型不归并
+        assert_eq!(maps[1].symbols[1].children.len(), 1);
+    }
+
+    #[test]
+    fn test_objc_implementation_linked_to_interface() {
+        let mut interface = Symbol::at("User", SymbolKind::Class, 1);
+        interface.children = vec![
+            Symbol::at("name", SymbolKind::Property, 2),
+            Symbol::at("save", SymbolKind::Method, 3),
+        ];
+        let mut implementation = Symbol::at("User", SymbolKind::Class, 6);
+        implementation.children = vec![
+            Symbol::at("save", SymbolKind::Method, 7),
+            Symbol::at("reset", SymbolKind::Method, 9),
+        ];
+        let mut category = Symbol::at("User", SymbolKind::Extension, 12);
+        category.children = vec![Symbol::at("show", SymbolKind::Method, 13)];
+        let mut maps = vec![FileMap::new("User.m", "objc").with_symbols(vec![
+            interface,
+            implementation,
+            category,
+        ])];
+
+        merge_type_parts(&mut maps);
+
+        assert_eq!(maps[0].symbols.len(), 1);
+        let user = &maps[0].symbols[0];
+        assert_eq!(user.definition.as_ref().unwrap().range.start.line, 6);
+        // 方法不重复列出，声明记录定义位置；没有声明的方法追加
+        let names: Vec<&str> = user.children.iter().map(|c| c.name.as_str()).collect();
+        assert_eq!(names, ["name", "save", "reset", "User"]);
+        let save = user.children[1].definition.as_ref().unwrap();
+        assert_eq!((save.path.as_str(), save.range.start.line), ("User.m", 7));
+        assert!(user.children[2].path.is_none());
+        // category 仍作为分部挂在主声明下
+        assert_eq!(user.children[3].kind, SymbolKind::Extension);
+    }
+
+    #[test]
+    fn test_objc_category_implementation_in_same_file() {
+        let category = |line| {
+            let mut category = Symbol::at("Foo", SymbolKind::Extension, line);
+            category.detail = Some("Net".to_string());
+            category.children = vec![Symbol::at("fetch", SymbolKind::Method, line + 1)];
+            category
+        };
+        let mut other = category(20);
+        other.detail = Some("Cache".to_string());
+        let mut external = category(30);
+        external.name = "NSString".to_string();
+        let mut external_impl = category(40);
+        external_impl.name = "NSString".to_string();
+        let mut maps = vec![FileMap::new("Foo.m", "objc").with_symbols(vec![
+            Symbol::at("Foo", SymbolKind::Class, 1),
+            category(5),
+            category(13),
+            other,
+            external,
+            external_impl,
+        ])];
+
+        merge_type_parts(&mut maps);
+
+        let symbols = &maps[0].symbols;
+        assert_eq!(symbols.len(), 2);
+        // 同名同分类的 @implementation 记录到 @interface 上，不同分类各自保留
+        let net = &symbols[0].children[0];
+        assert_eq!(symbols[0].children.len(), 2);
+        assert_eq!(net.definition.as_ref().unwrap().range.start.line, 13);
+        assert_eq!(net.children.len(), 1);
+        let fetch = net.children[0].definition.as_ref().unwrap();
+        assert_eq!(fetch.range.start.line, 14);
+        assert_eq!(symbols[0].children[1].detail.as_deref(), Some("Cache"));
+        // 找不到主声明的 category 在文件内配对
+        assert_eq!(symbols[1].name, "NSString");
+        assert_eq!(symbols[1].definition.as_ref().unwrap().range.start.line, 40);
+    }
+
+    #[test]
+    fn test_pair_objc_category() {
+        let mut decl = Symbol::at("User", SymbolKind::Extension, 1);
+        decl.detail = Some("Display".to_string());
+        decl.children = vec![Symbol::at("show", SymbolKind::Method, 2)];
+        let mut def = Symbol::at("User", SymbolKind::Extension, 3);
+        def.detail = Some("Display".to_string());
+        def.children = vec![Symbol::at("show", SymbolKind::Method, 4)];
+        let mut maps = vec![
+            FileMap::new("User+Display.h", "objc").with_symbols(vec![decl]),
+            FileMap::new("User+Display.m", "objc").with_symbols(vec![def]),
+        ];
+
+        pair_headers(&mut maps);
+
+        let category = &maps[0].symbols[0];
+        assert_eq!(category.definition.as_ref().unwrap().range.start.line, 3);
+        assert_eq!(
+            category.children[0]
+                .definition
+                .as_ref()
+                .unwrap()
+                .range
+                .start
+                .line,
+            4
+        );
+        assert!(maps[1].symbols.is_empty());
+    }
+
+    #[test]
+    fn test_cpp_definition_linked_to_declaration() {
+        let mut user = Symbol::at("User", SymbolKind::Class, 2);
+        user.children = vec![Symbol::at("save", SymbolKind::Method, 3)];
+        let mut maps = vec![
+            FileMap::new("user.hpp", "cpp").with_symbols(vec![namespace("app", vec![user])]),
+            FileMap::new("user.cpp", "cpp").with_symbols(vec![
+                Symbol::at("save", SymbolKind::Method, 5).with_owner("app::User"),
+                Symbol::at("load", SymbolKind::Method, 9).with_owner("app::User"),
+            ]),
+        ];
+
+        attach_owned_members(&mut maps);
+
+        let user = &maps[0].symbols[0].children[0];
+        assert_eq!(user.children.len(), 2);
+        let def = user.children[0].definition.as_ref().unwrap();
+        assert_eq!(def.path, "user.cpp");
+        assert_eq!(def.range.start.line, 5);
+        // 无声明的定义作为新成员追加
+        assert_eq!(user.children[1].name, "load");
+        assert_eq!(user.children[1].path.as_deref(), Some("user.cpp"));
+        assert!(maps[1].symbols.is_empty());
+    }
+
+    #[test]
+    fn test_cpp_definitions_matched_by_parameters() {
+        let method = |line, types: &[&str], has_body, owner: Option<&str>| {
+            let mut sym = Symbol::at("add", SymbolKind::Method, line);
+            sym.owner = owner.map(str::to_string);
+            sym.has_body = has_body;
+            sym.signature = Some(Signature {
+                parameters: types
+                    .iter()
+                    .map(|t| Parameter {
+                        name: "x".to_string(),
+                        type_name: Some(t.to_string()),
+                        ..Parameter::default()
+                    })
+                    .collect(),
+                ..Signature::default()
+            });
+            sym
+        };
+        let mut repo = Symbol::at("Repo", SymbolKind::Class, 1);
+        repo.children = vec![
+            method(2, &["int"], false, None),
+            method(3, &["const std::string &"], false, None),
+            method(4, &["double"], true, None),
+        ];
+        let mut specialization = method(20, &["double"], true, Some("Repo"));
+        specialization.detail = Some("<int>".to_string());
+        let mut maps = vec![FileMap::new("repo.cpp", "cpp").with_symbols(vec![
+            repo,
+            method(10, &["const std::string&"], true, Some("Repo")),
+            method(15, &["int"], true, Some("Repo")),
+            specialization,
+        ])];
+
+        attach_owned_members(&mut maps);
+
+        let repo = &maps[0].symbols[0];
+        let defined = |i: usize| {
+            repo.children[i]
+                .definition
+                .as_ref()
+                .map(|d| d.range.start.line)
+        };
+        // 重载按参数类型配对，不按出现顺序
+        assert_eq!(defined(0), Some(15));
+        assert_eq!(defined(1), Some(10));
+        // 带函数体的声明已是定义，特化作为独立成员保留
+        assert_eq!(defined(2), None);
+        assert_eq!(repo.children.len(), 4);
+        assert_eq!(repo.children[3].detail.as_deref(), Some("<int>"));
+    }
+
+    #[test]
+    fn test_cpp_specialization_not_definition_of_template() {
+        let specialization = |line, args: &str| {
+            let mut sym = Symbol::at("as", SymbolKind::Method, line);
+            sym.detail = Some(args.to_string());
+            sym
+        };
+        let mut foo = Symbol::at("Foo", SymbolKind::Class, 1);
+        foo.children = vec![
+            Symbol::at("as", SymbolKind::Method, 2),
+            specialization(3, "<float>"),
+        ];
+        let mut maps = vec![FileMap::new("foo.cpp", "cpp").with_symbols(vec![
+            foo,
+            specialization(10, "<int>").with_owner("Foo"),
+            specialization(12, "<float>").with_owner("Foo"),
+        ])];
+
+        attach_owned_members(&mut maps);
+
+        let foo = &maps[0].symbols[0];
+        let defined: Vec<_> = foo
+            .children
+            .iter()
+            .map(|c| c.definition.as_ref().map(|d| d.range.start.line))
+            .collect();
+        // 泛型声明不记录特化的位置，同一特化的声明与定义配对
+        assert_eq!(defined, vec![None, Some(12), None]);
+        assert_eq!(foo.children[2].detail.as_deref(), Some("<int>"));
+    }
+
+    #[test]
+    fn test_cpp_owner_resolved_inside_namespace() {
+        let mut maps = vec![FileMap::new("a.cpp", "cpp").with_symbols(vec![
+            namespace(
+                "app",
+                vec![
+                    Symbol::at("User", SymbolKind::Class, 2),
+                    Symbol::at("save", SymbolKind::Method, 5).with_owner("User"),
+                ],
+            ),
+            Symbol::at("helper", SymbolKind::Method, 9).with_owner("app"),
+        ])];
+
+        attach_owned_members(&mut maps);
+
+        let app = &maps[0].symbols[0];
+        assert_eq!(app.children.len(), 2);
+        assert_eq!(app.children[0].children[0].name, "save");
+        // 归并到命名空间下的方法视为函数
+        assert_eq!(app.children[1].name, "helper");
+        assert_eq!(app.children[1].kind, SymbolKind::Function);
+        assert_eq!(maps[0].symbols.len(), 1);
+    }
+
+    #[test]
+    fn test_cpp_owner_suffix_match() {
+        let mut maps = vec![FileMap::new("a.cpp", "cpp").with_symbols(vec![
+            namespace("app", vec![Symbol::at("User", SymbolKind::Class, 2)]),
+            Symbol::at("save", SymbolKind::Method, 5).with_owner("User"),
+        ])];
+
+        attach_owned_members(&mut maps);
+        assert_eq!(maps[0].symbols[0].children[0].children[0].name, "save");
+    }
+}
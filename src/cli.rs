@@ -2,10 +2,13 @@
 // CLI: 命令行参数定义
 // ============================================================
 
-use clap::{Parser, ValueEnum};
+use crate::detector::Language;
+use clap::{Parser, Subcommand, ValueEnum};
+use serde::Deserialize;
 use std::path::PathBuf;
 
-#[derive(Debug, Clone, Copy, Default, ValueEnum)]
+#[derive(Debug, Clone, Copy, Default, ValueEnum, Deserialize)]
+#[serde(rename_all = "lowercase")]
 pub enum OutputFormat {
     #[default]
     Markdown,
@@ -17,13 +20,89 @@ pub enum OutputFormat {
 #[command(about = "AI-friendly source code index generator")]
 #[command(version)]
 pub struct Cli {
-    /// Input file or directory
+    #[command(subcommand)]
+    pub command: Option<Command>,
+
+    /// Input files or directories, each rendered under its own root label when more than one
+    /// (`-` reads a single source from stdin)
     #[arg(default_value = ".")]
-    pub input: PathBuf,
+    pub input: Vec<PathBuf>,
+
+    /// Index the files listed in FILE (`-` for stdin) instead of scanning, one per line or
+    /// NUL-separated (`git ls-files -z`, `fd -0`); paths are relative to the current directory
+    #[arg(long, value_name = "FILE", conflicts_with = "input")]
+    pub files_from: Option<PathBuf>,
+
+    /// Language of the source read from stdin (detected from --path-hint when omitted)
+    #[arg(long, value_name = "LANG", value_parser = parse_language, global = true)]
+    pub lang: Option<Language>,
+
+    /// Path shown for the source read from stdin (also used for detection and config globs)
+    #[arg(long, value_name = "PATH", global = true)]
+    pub path_hint: Option<PathBuf>,
+
+    /// Output format [default: markdown]
+    #[arg(long, short, value_enum, global = true)]
+    pub format: Option<OutputFormat>,
+
+    /// Only index files matching this glob (relative to input, repeatable)
+    #[arg(long, value_name = "GLOB", global = true)]
+    pub include: Vec<String>,
+
+    /// Skip files matching this glob (relative to input, repeatable)
+    #[arg(long, value_name = "GLOB", global = true)]
+    pub exclude: Vec<String>,
+
+    /// Truncate the output to about this many tokens (~4 characters each), ending with a marker line
+    #[arg(long, value_name = "TOKENS")]
+    pub budget: Option<usize>,
+
+    /// Only show symbols carrying this attribute / annotation / decorator
+    /// (e.g. `test` matches #[test], @Test and #[tokio::test]; repeatable)
+    #[arg(long, value_name = "NAME")]
+    pub with_attr: Vec<String>,
+
+    /// Hide test files and test code (test functions, describe/it blocks, #[cfg(test)] modules)
+    #[arg(long, conflicts_with = "tests_only")]
+    pub no_tests: bool,
+
+    /// Only show tests (with their enclosing containers)
+    #[arg(long)]
+    pub tests_only: bool,
+
+    /// Directory of <lang>.scm files replacing built-in queries
+    /// (first line "; extends" appends to the built-in query instead)
+    #[arg(long, value_name = "DIR", global = true)]
+    pub queries_dir: Option<PathBuf>,
+
+    /// Ignore .codemap.toml files
+    #[arg(long, global = true)]
+    pub no_config: bool,
 
-    /// Output format
-    #[arg(long, short, value_enum, default_value = "markdown")]
-    pub format: OutputFormat,
+    /// Index generated, vendored, minified and oversized files too
+    #[arg(long, global = true)]
+    pub no_skip: bool,
+
+    /// Skip files larger than this (bytes, or with a K / M suffix; 0 = no limit) [default: 1M]
+    #[arg(long, value_name = "SIZE", value_parser = parse_size, global = true)]
+    pub max_file_size: Option<u64>,
+
+    /// Fail when any file has syntax errors or cannot be read
+    #[arg(long, global = true)]
+    pub strict: bool,
+}
+
+#[derive(Subcommand, Debug)]
+pub enum Command {
+    /// List ancestors and descendants of a type across the scanned files
+    Hierarchy {
+        /// Type name (generic arguments and namespace are ignored)
+        name: String,
+
+        /// Input files or directories (`-` reads a single source from stdin)
+        #[arg(default_value = ".")]
+        input: Vec<PathBuf>,
+    },
 }
 
 impl Cli {
@@ -32,6 +111,37 @@ impl Cli {
     }
 }
 
+/// 文件大小: 字节数，或带 K / M 后缀 (1024 进制)
+pub fn parse_size(text: &str) -> Result<u64, String> {
+    let text = text.trim();
+    let (digits, unit) = match text.char_indices().find(|(_, c)| !c.is_ascii_digit()) {
+        Some((i, _)) => text.split_at(i),
+        None => (text, ""),
+    };
+    let multiplier = match unit.to_ascii_uppercase().as_str() {
+        "" | "B" => 1,
+        "K" | "KB" | "KIB" => 1024,
+        "M" | "MB" | "MIB" => 1024 * 1024,
+        _ => return Err(format!("invalid size unit: {}", unit)),
+    };
+    digits
+        .parse::<u64>()
+        .map(|n| n * multiplier)
+        .map_err(|_| format!("invalid size: {}", text))
+}
+
+/// 语言名: `python`、`cpp` 等，也接受 `C++`、`Objective-C` 等别名
+pub fn parse_language(text: &str) -> Result<Language, String> {
+    Language::from_name(text).ok_or_else(|| {
+        let names: Vec<&str> = Language::ALL.iter().map(|lang| lang.as_str()).collect();
+        format!(
+            "unknown language: {} (expected one of {})",
+            text,
+            names.join(", ")
+        )
+    })
+}
+
 // ============================================================
 // 单元测试
 // ============================================================
@@ -41,19 +151,120 @@ mod tests {
 
     #[test]
     fn test_cli_default_input() {
-        let cli = Cli {
-            input: PathBuf::from("."),
-            format: OutputFormat::Markdown,
-        };
-        assert_eq!(cli.input, PathBuf::from("."));
+        let cli = Cli::parse_from(["agent-codemap"]);
+        assert_eq!(cli.input, vec![PathBuf::from(".")]);
+        assert!(cli.format.is_none());
     }
 
     #[test]
     fn test_cli_json_format() {
-        let cli = Cli {
-            input: PathBuf::from("src"),
-            format: OutputFormat::Json,
-        };
-        assert!(matches!(cli.format, OutputFormat::Json));
+        let cli = Cli::parse_from(["agent-codemap", "src", "-f", "json"]);
+        assert_eq!(cli.input, vec![PathBuf::from("src")]);
+        assert!(matches!(cli.format, Some(OutputFormat::Json)));
+    }
+
+    #[test]
+    fn test_cli_repeated_globs() {
+        let cli = Cli::parse_from([
+            "agent-codemap",
+            "--include",
+            "src/**",
+            "--include",
+            "lib/**",
+            "--exclude",
+            "**/*_test.go",
+        ]);
+        assert_eq!(cli.include, vec!["src/**", "lib/**"]);
+        assert_eq!(cli.exclude, vec!["**/*_test.go"]);
+    }
+
+    #[test]
+    fn test_cli_hierarchy_subcommand() {
+        let cli = Cli::parse_from(["agent-codemap", "hierarchy", "User", "src", "-f", "json"]);
+        match cli.command {
+            Some(Command::Hierarchy { name, input }) => {
+                assert_eq!(name, "User");
+                assert_eq!(input, vec![PathBuf::from("src")]);
+            }
+            None => panic!("expected hierarchy subcommand"),
+        }
+        assert!(matches!(cli.format, Some(OutputFormat::Json)));
+        assert!(Cli::parse_from(["agent-codemap", "src"]).command.is_none());
+    }
+
+    #[test]
+    fn test_cli_budget() {
+        assert!(Cli::parse_from(["agent-codemap"]).budget.is_none());
+        let cli = Cli::parse_from(["agent-codemap", "src", "--budget", "4000"]);
+        assert_eq!(cli.budget, Some(4000));
+        assert!(Cli::try_parse_from(["agent-codemap", "--budget", "lots"]).is_err());
+    }
+
+    #[test]
+    fn test_cli_with_attr() {
+        let cli = Cli::parse_from([
+            "agent-codemap",
+            "--with-attr",
+            "test",
+            "--with-attr",
+            "Bean",
+        ]);
+        assert_eq!(cli.with_attr, vec!["test", "Bean"]);
+    }
+
+    #[test]
+    fn test_cli_test_filters() {
+        let cli = Cli::parse_from(["agent-codemap", "--no-tests"]);
+        assert!(cli.no_tests && !cli.tests_only);
+        assert!(Cli::try_parse_from(["agent-codemap", "--no-tests", "--tests-only"]).is_err());
+    }
+
+    #[test]
+    fn test_parse_size() {
+        assert_eq!(parse_size("512"), Ok(512));
+        assert_eq!(parse_size("64K"), Ok(64 * 1024));
+        assert_eq!(parse_size("2mb"), Ok(2 * 1024 * 1024));
+        assert!(parse_size("2G").is_err());
+        assert!(parse_size("big").is_err());
+
+        let cli = Cli::parse_from(["agent-codemap", "--max-file-size", "1K", "--no-skip"]);
+        assert_eq!(cli.max_file_size, Some(1024));
+        assert!(cli.no_skip);
+    }
+
+    #[test]
+    fn test_cli_stdin_options() {
+        let cli = Cli::parse_from([
+            "agent-codemap",
+            "-",
+            "--lang",
+            "C++",
+            "--path-hint",
+            "src/foo.cc",
+        ]);
+        assert_eq!(cli.input, vec![PathBuf::from("-")]);
+        assert_eq!(cli.lang, Some(Language::Cpp));
+        assert_eq!(cli.path_hint, Some(PathBuf::from("src/foo.cc")));
+        assert!(Cli::try_parse_from(["agent-codemap", "-", "--lang", "cobol"]).is_err());
+    }
+
+    #[test]
+    fn test_cli_multiple_inputs() {
+        let cli = Cli::parse_from(["agent-codemap", "backend", "web/src"]);
+        assert_eq!(
+            cli.input,
+            vec![PathBuf::from("backend"), PathBuf::from("web/src")]
+        );
+
+        let cli = Cli::parse_from(["agent-codemap", "--files-from", "-"]);
+        assert_eq!(cli.files_from, Some(PathBuf::from("-")));
+        assert!(Cli::try_parse_from(["agent-codemap", "src", "--files-from", "list.txt"]).is_err());
+    }
+
+    #[test]
+    fn test_cli_strict_is_global() {
+        assert!(!Cli::parse_from(["agent-codemap"]).strict);
+        let cli = Cli::parse_from(["agent-codemap", "hierarchy", "User", "--strict"]);
+        assert!(cli.strict);
     }
 }
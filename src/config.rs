@@ -0,0 +1,280 @@
+// ============================================================
+// Config: 项目配置文件 (.codemap.toml)
+// ============================================================
+
+use crate::cli::OutputFormat;
+use crate::detector::Language;
+use crate::symbol::SymbolKind;
+use anyhow::{bail, Context, Result};
+use encoding_rs::Encoding;
+use globset::Glob;
+use serde::Deserialize;
+use std::collections::{BTreeMap, HashMap};
+use std::path::{Path, PathBuf};
+
+/// 配置文件名
+pub const CONFIG_FILE: &str = ".codemap.toml";
+
+/// .codemap.toml 内容，所有字段可选
+///
+/// ```toml
+/// format = "json"
+/// queries_dir = "codemap-queries"   # 相对配置文件所在目录
+/// include = ["src/**"]
+/// exclude = ["**/generated/**"]
+/// max_file_size = "2M"              # 扫描目录时跳过更大的文件，0 为不限
+/// budget = 8000                     # 输出的 token 预算，超出时截断
+///
+/// [languages]        # 扩展名 → 语言
+/// h = "cpp"
+///
+/// [encodings]        # glob (相对输入目录) → 编码，用于无 BOM 的非 UTF-8 文件
+/// "legacy/**/*.c" = "shift_jis"
+///
+/// [kinds.python]     # 按语言开关符号类型
+/// variable = false
+///
+/// [dsl.ruby]         # DSL 方法 → 符号类型 (追加/覆盖内置 catalog)
+/// after_transition = "callback"
+/// ```
+#[derive(Debug, Default, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct Config {
+    pub format: Option<OutputFormat>,
+    pub queries_dir: Option<PathBuf>,
+    pub include: Vec<String>,
+    pub exclude: Vec<String>,
+    #[serde(deserialize_with = "deserialize_size")]
+    pub max_file_size: Option<u64>,
+    pub budget: Option<usize>,
+    pub languages: HashMap<String, Language>,
+    pub encodings: BTreeMap<String, String>,
+    pub kinds: HashMap<Language, HashMap<SymbolKind, bool>>,
+    pub dsl: HashMap<Language, HashMap<String, SymbolKind>>,
+}
+
+impl Config {
+    /// 从输入路径向上查找并加载配置，找不到时返回默认配置
+    pub fn load(input: &Path) -> Result<Self> {
+        match discover(input) {
+            Some(path) => Self::from_file(&path),
+            None => Ok(Self::default()),
+        }
+    }
+
+    pub fn from_file(path: &Path) -> Result<Self> {
+        let text = std::fs::read_to_string(path)
+            .with_context(|| format!("Failed to read config: {}", path.display()))?;
+        let mut config: Self =
+            toml::from_str(&text).with_context(|| format!("Invalid config: {}", path.display()))?;
+
+        config
+            .validate()
+            .with_context(|| format!("Invalid config: {}", path.display()))?;
+
+        // 相对路径以配置文件所在目录为基准
+        if let (Some(dir), Some(base)) = (config.queries_dir.as_mut(), path.parent()) {
+            if dir.is_relative() {
+                *dir = base.join(&*dir);
+            }
+        }
+
+        Ok(config)
+    }
+
+    /// 扩展名覆盖 (键可写作 "h" 或 ".h")
+    pub fn language_for(&self, path: &Path) -> Option<Language> {
+        let ext = path.extension()?.to_str()?;
+        self.languages
+            .iter()
+            .find(|(key, _)| key.trim_start_matches('.') == ext)
+            .map(|(_, lang)| *lang)
+    }
+
+    /// 检查 [encodings] 的 glob 与编码名
+    fn validate(&self) -> Result<()> {
+        for (pattern, label) in &self.encodings {
+            Glob::new(pattern).with_context(|| format!("Invalid glob: {}", pattern))?;
+            if Encoding::for_label(label.as_bytes()).is_none() {
+                bail!("Unknown encoding for {}: {}", pattern, label);
+            }
+        }
+        Ok(())
+    }
+
+    /// 按 [encodings] 为文件 (相对输入目录的路径) 指定编码，多个匹配时取字典序第一个
+    pub fn encoding_for(&self, rel: &Path) -> Option<&'static Encoding> {
+        self.encodings.iter().find_map(|(pattern, label)| {
+            let matcher = Glob::new(pattern).ok()?.compile_matcher();
+            if matcher.is_match(rel) {
+                Encoding::for_label(label.as_bytes())
+            } else {
+                None
+            }
+        })
+    }
+
+    /// 符号类型是否启用 (未配置时默认启用)
+    pub fn kind_enabled(&self, lang: Language, kind: SymbolKind) -> bool {
+        self.kinds
+            .get(&lang)
+            .and_then(|kinds| kinds.get(&kind))
+            .copied()
+            .unwrap_or(true)
+    }
+}
+
+/// 文件大小可写作字节数或带 K / M 后缀的字符串
+fn deserialize_size<'de, D: serde::Deserializer<'de>>(
+    deserializer: D,
+) -> Result<Option<u64>, D::Error> {
+    #[derive(Deserialize)]
+    #[serde(untagged)]
+    enum Size {
+        Bytes(u64),
+        Text(String),
+    }
+    match Size::deserialize(deserializer)? {
+        Size::Bytes(n) => Ok(Some(n)),
+        Size::Text(text) => crate::cli::parse_size(&text)
+            .map(Some)
+            .map_err(serde::de::Error::custom),
+    }
+}
+
+/// 从输入目录 (文件则取其父目录) 逐级向上查找配置文件
+pub fn discover(input: &Path) -> Option<PathBuf> {
+    let input_abs = input.canonicalize().ok()?;
+    let start = if input_abs.is_file() {
+        input_abs.parent()?.to_path_buf()
+    } else {
+        input_abs
+    };
+
+    start
+        .ancestors()
+        .map(|dir| dir.join(CONFIG_FILE))
+        .find(|candidate| candidate.is_file())
+}
+
+// ============================================================
+// 单元测试
+// ============================================================
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::fs;
+    use tempfile::TempDir;
+
+    #[test]
+    fn test_parse_full_config() {
+        let config: Config = toml::from_str(
+            r#"
+            format = "json"
+            budget = 8000
+            include = ["src/**"]
+            exclude = ["**/gen/**"]
+
+            [languages]
+            h = "cpp"
+
+            [kinds.python]
+            variable = false
+
+            [dsl.ruby]
+            acts_as_list = "scope"
+            "#,
+        )
+        .unwrap();
+
+        assert!(matches!(config.format, Some(OutputFormat::Json)));
+        assert_eq!(config.budget, Some(8000));
+        assert_eq!(config.include, vec!["src/**"]);
+        assert_eq!(config.exclude, vec!["**/gen/**"]);
+        assert_eq!(config.language_for(Path::new("a.h")), Some(Language::Cpp));
+        assert!(!config.kind_enabled(Language::Python, SymbolKind::Variable));
+        assert!(config.kind_enabled(Language::Python, SymbolKind::Class));
+        assert!(config.kind_enabled(Language::Rust, SymbolKind::Variable));
+        assert_eq!(
+            config.dsl[&Language::Ruby]["acts_as_list"],
+            SymbolKind::Scope
+        );
+    }
+
+    #[test]
+    fn test_max_file_size() {
+        let config: Config = toml::from_str("max_file_size = \"2M\"").unwrap();
+        assert_eq!(config.max_file_size, Some(2 * 1024 * 1024));
+        let config: Config = toml::from_str("max_file_size = 4096").unwrap();
+        assert_eq!(config.max_file_size, Some(4096));
+        assert_eq!(Config::default().max_file_size, None);
+        assert!(toml::from_str::<Config>("max_file_size = \"huge\"").is_err());
+    }
+
+    #[test]
+    fn test_dotted_extension_key() {
+        let config: Config = toml::from_str("[languages]\n\".inc\" = \"c\"\n").unwrap();
+        assert_eq!(config.language_for(Path::new("x.inc")), Some(Language::C));
+        assert_eq!(config.language_for(Path::new("x.c")), None);
+    }
+
+    #[test]
+    fn test_unknown_key_rejected() {
+        assert!(toml::from_str::<Config>("formt = \"json\"").is_err());
+        assert!(toml::from_str::<Config>("[languages]\nh = \"cobol\"").is_err());
+    }
+
+    #[test]
+    fn test_encoding_globs() {
+        let config: Config =
+            toml::from_str("[encodings]\n\"legacy/**\" = \"shift_jis\"\n\"*.java\" = \"latin1\"\n")
+                .unwrap();
+        assert!(config.validate().is_ok());
+        assert_eq!(
+            config.encoding_for(Path::new("legacy/io/a.c")),
+            Some(encoding_rs::SHIFT_JIS)
+        );
+        assert_eq!(
+            config.encoding_for(Path::new("src/A.java")),
+            Some(encoding_rs::WINDOWS_1252)
+        );
+        assert_eq!(config.encoding_for(Path::new("src/a.c")), None);
+
+        let tmp = TempDir::new().unwrap();
+        let path = tmp.path().join(CONFIG_FILE);
+        fs::write(&path, "[encodings]\n\"*.c\" = \"klingon\"\n").unwrap();
+        let err = Config::from_file(&path).unwrap_err();
+        assert!(format!("{:#}", err).contains("Unknown encoding for *.c: klingon"));
+    }
+
+    #[test]
+    fn test_queries_dir_relative_to_config() {
+        let tmp = TempDir::new().unwrap();
+        let path = tmp.path().join(CONFIG_FILE);
+        fs::write(&path, "queries_dir = \"queries\"\n").unwrap();
+
+        let config = Config::from_file(&path).unwrap();
+        assert_eq!(config.queries_dir, Some(tmp.path().join("queries")));
+    }
+
+    #[test]
+    fn test_discover_walks_up() {
+        let tmp = TempDir::new().unwrap();
+        let nested = tmp.path().join("a/b");
+        fs::create_dir_all(&nested).unwrap();
+        fs::write(tmp.path().join(CONFIG_FILE), "").unwrap();
+
+        let found = discover(&nested).unwrap();
+        assert_eq!(found, tmp.path().canonicalize().unwrap().join(CONFIG_FILE));
+    }
+
+    #[test]
+    fn test_discover_from_file_input() {
+        let tmp = TempDir::new().unwrap();
+        let file = tmp.path().join("main.rs");
+        fs::write(&file, "").unwrap();
+        fs::write(tmp.path().join(CONFIG_FILE), "").unwrap();
+
+        assert!(discover(&file).is_some());
+    }
+}
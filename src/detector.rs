@@ -2,10 +2,167 @@
 // Detector: 语言检测
 // ============================================================
 
+use crate::gitattributes::Attributes;
+use serde::Deserialize;
 use std::path::Path;
 
+/// 判断二进制 (含 NUL) 时检查的开头字节数
+const HEAD_BYTES: usize = 8 * 1024;
+
+/// Vim modeline 所在的开头 / 末尾行数
+const MODELINE_LINES: usize = 5;
+
+/// 内容判断检查的非空行数
+const SNIFF_LINES: usize = 100;
+
+/// 内容判断须命中的不同特征数
+const SNIFF_MIN_MARKERS: usize = 2;
+
+/// 无扩展名文件的内容特征，按顺序判断 (模式语法见 marker_matches)
+const SNIFF_MARKERS: &[(Language, &[&str])] = &[
+    (
+        Language::ObjC,
+        &["@interface *", "@implementation *", "@end", "#import *"],
+    ),
+    (
+        Language::C,
+        &[
+            "#include *",
+            "#define *",
+            "#ifndef *",
+            "typedef *",
+            "int main(*",
+        ],
+    ),
+    (
+        Language::Go,
+        &["package *", "func *", "import (", "import \"*"],
+    ),
+    (
+        Language::Java,
+        &[
+            "package *;",
+            "import *;",
+            "public class *",
+            "public interface *",
+        ],
+    ),
+    (
+        Language::CSharp,
+        &[
+            "using System*;",
+            "namespace *",
+            "public class *",
+            "internal class *",
+        ],
+    ),
+    (
+        Language::Rust,
+        &["fn *", "pub fn *", "use *;", "#![*", "impl *", "mod *;"],
+    ),
+    (
+        Language::Swift,
+        &[
+            "import Foundation",
+            "import SwiftUI",
+            "import UIKit",
+            "func *{",
+            "struct *{",
+        ],
+    ),
+    (
+        Language::Kotlin,
+        &["package *", "fun *", "val *", "data class *", "object *"],
+    ),
+    (
+        Language::Python,
+        &[
+            "def *:",
+            "class *:",
+            "from * import *",
+            "import *",
+            "if __name__ == *:",
+        ],
+    ),
+    (
+        Language::Ruby,
+        &[
+            "require *",
+            "require_relative *",
+            "def *",
+            "module *",
+            "end",
+        ],
+    ),
+    (
+        Language::JavaScript,
+        &[
+            "module.exports*",
+            "const * = require(*",
+            "'use strict'*",
+            "function *",
+        ],
+    ),
+];
+
+/// 常见的文档类无扩展名文件，不做内容判断
+const DOC_NAMES: &[&str] = &[
+    "README",
+    "INSTALL",
+    "LICENSE",
+    "LICENCE",
+    "COPYING",
+    "NOTICE",
+    "NOTES",
+    "CHANGELOG",
+    "CHANGES",
+    "HISTORY",
+    "NEWS",
+    "AUTHORS",
+    "CONTRIBUTORS",
+    "CONTRIBUTING",
+    "MAINTAINERS",
+    "CODEOWNERS",
+    "THANKS",
+    "TODO",
+    "VERSION",
+    "PATENTS",
+];
+
+/// 常见的无扩展名文件；None 为已识别但没有语法支持的文件 (不索引)
+const FILENAMES: &[(&str, Option<Language>)] = &[
+    ("Rakefile", Some(Language::Ruby)),
+    ("Gemfile", Some(Language::Ruby)),
+    ("Podfile", Some(Language::Ruby)),
+    ("Vagrantfile", Some(Language::Ruby)),
+    ("Guardfile", Some(Language::Ruby)),
+    ("Fastfile", Some(Language::Ruby)),
+    ("Appfile", Some(Language::Ruby)),
+    ("Brewfile", Some(Language::Ruby)),
+    ("Capfile", Some(Language::Ruby)),
+    ("Berksfile", Some(Language::Ruby)),
+    ("Dangerfile", Some(Language::Ruby)),
+    ("Thorfile", Some(Language::Ruby)),
+    // Bazel / Buck / SCons / Tilt: Starlark 与 Python 语法兼容
+    ("BUILD", Some(Language::Python)),
+    ("BUCK", Some(Language::Python)),
+    ("WORKSPACE", Some(Language::Python)),
+    ("SConstruct", Some(Language::Python)),
+    ("SConscript", Some(Language::Python)),
+    ("Tiltfile", Some(Language::Python)),
+    ("Jakefile", Some(Language::JavaScript)),
+    ("Dockerfile", None),
+    ("Containerfile", None),
+    ("Makefile", None),
+    ("GNUmakefile", None),
+    ("CMakeLists.txt", None),
+    ("Justfile", None),
+    ("Procfile", None),
+];
+
 /// 支持的语言
-#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Deserialize)]
+#[serde(rename_all = "lowercase")]
 pub enum Language {
     Python,
     TypeScript,
@@ -26,6 +183,25 @@ pub enum Language {
 }
 
 impl Language {
+    pub const ALL: &'static [Language] = &[
+        Self::Python,
+        Self::TypeScript,
+        Self::Tsx,
+        Self::JavaScript,
+        Self::Jsx,
+        Self::Go,
+        Self::Rust,
+        Self::Java,
+        Self::C,
+        Self::Cpp,
+        Self::Ruby,
+        Self::Markdown,
+        Self::Swift,
+        Self::ObjC,
+        Self::Kotlin,
+        Self::CSharp,
+    ];
+
     pub fn as_str(&self) -> &'static str {
         match self {
             Self::Python => "python",
@@ -46,14 +222,43 @@ impl Language {
             Self::CSharp => "csharp",
         }
     }
+
+    /// 按语言名解析: Linguist 名 (`C++`、`Objective-C`、`C#`)、Emacs mode、Vim filetype 与常见别名，不区分大小写
+    pub fn from_name(name: &str) -> Option<Language> {
+        let name = name.trim().to_ascii_lowercase();
+        let name = name.strip_suffix("-mode").unwrap_or(&name);
+        match name {
+            "python" | "python3" | "py" | "starlark" | "bazel" | "bzl" => Some(Self::Python),
+            "typescript" | "ts" => Some(Self::TypeScript),
+            "tsx" | "typescriptreact" | "typescript-tsx" => Some(Self::Tsx),
+            "javascript" | "js" | "js2" | "node" | "nodejs" | "ecmascript" => {
+                Some(Self::JavaScript)
+            }
+            "jsx" | "javascriptreact" | "rjsx" => Some(Self::Jsx),
+            "go" | "golang" => Some(Self::Go),
+            "rust" | "rs" => Some(Self::Rust),
+            "java" => Some(Self::Java),
+            "c" => Some(Self::C),
+            "c++" | "cpp" | "cxx" => Some(Self::Cpp),
+            "ruby" | "rb" | "jruby" => Some(Self::Ruby),
+            "markdown" | "md" | "gfm" => Some(Self::Markdown),
+            "swift" => Some(Self::Swift),
+            "objective-c" | "objective-c++" | "objc" | "objcpp" | "objectivec" | "obj-c" => {
+                Some(Self::ObjC)
+            }
+            "kotlin" | "kt" => Some(Self::Kotlin),
+            "c#" | "csharp" | "cs" => Some(Self::CSharp),
+            _ => None,
+        }
+    }
 }
 
 /// 根据文件路径检测语言
 pub fn detect(path: &Path) -> Option<Language> {
     let ext = path.extension()?.to_str()?;
     match ext {
-        "py" | "pyi" => Some(Language::Python),
-        "ts" => Some(Language::TypeScript),
+        "py" | "pyi" | "pyw" | "bzl" | "bazel" | "star" => Some(Language::Python),
+        "ts" | "mts" | "cts" => Some(Language::TypeScript),
         "tsx" => Some(Language::Tsx),
         "js" | "mjs" | "cjs" => Some(Language::JavaScript),
         "jsx" => Some(Language::Jsx),
@@ -62,7 +267,7 @@ pub fn detect(path: &Path) -> Option<Language> {
         "java" => Some(Language::Java),
         "c" | "h" => Some(Language::C),
         "cpp" | "cc" | "cxx" | "hpp" | "hxx" => Some(Language::Cpp),
-        "rb" => Some(Language::Ruby),
+        "rb" | "rake" | "gemspec" | "podspec" | "ru" | "jbuilder" | "thor" => Some(Language::Ruby),
         "md" | "markdown" => Some(Language::Markdown),
         "swift" => Some(Language::Swift),
         "m" | "mm" => Some(Language::ObjC),
@@ -72,6 +277,297 @@ pub fn detect(path: &Path) -> Option<Language> {
     }
 }
 
+/// 只看路径能得到的判断，决定文件是否需要读取
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum PathMatch {
+    /// 已确定 (`[languages]` 配置、linguist-language)，不再看内容
+    Fixed(Language),
+    /// 文件名或扩展名给出的语言，内容中的 modeline 可覆盖，`.h` 再按内容区分
+    Likely(Language),
+    /// 无扩展名的文件，按 modeline、shebang 与内容特征判断
+    Unknown,
+}
+
+/// 按路径检测 (rel 为相对输入目录的路径)，不读取文件；返回 None 时不索引
+/// (不支持的语言、未知扩展名、Dockerfile 等已知但不支持的文件名、README 等文档)
+pub fn detect_path(path: &Path, rel: &Path, attributes: &Attributes) -> Option<PathMatch> {
+    // 显式指定不支持的语言时不索引
+    if let Some(name) = attributes.language(rel) {
+        return Language::from_name(name).map(PathMatch::Fixed);
+    }
+    detect_name(path)
+}
+
+/// 按路径与已读入的源码检测 (如 stdin 的缓冲区，路径不必存在)
+pub fn detect_source(path: &Path, text: &str) -> Option<Language> {
+    detect_content(path, detect_name(path)?, text)
+}
+
+/// 结合路径判断与源码确定语言，优先级:
+/// linguist-language > Emacs / Vim modeline > 常见文件名 > 扩展名 > shebang > 内容特征；
+/// `.h` 头文件按内容与同名兄弟文件区分 C / C++ / Objective-C
+pub fn detect_content(path: &Path, by_path: PathMatch, text: &str) -> Option<Language> {
+    let lang = match by_path {
+        PathMatch::Fixed(lang) => return Some(lang),
+        PathMatch::Likely(lang) => Some(lang),
+        PathMatch::Unknown => None,
+    };
+
+    // 二进制文件只按文件名与扩展名判断
+    if text.as_bytes().iter().take(HEAD_BYTES).any(|&b| b == 0) {
+        return lang;
+    }
+    if let Some(lang) = modeline(text) {
+        return Some(lang);
+    }
+    match lang {
+        Some(_) if path.extension().is_some_and(|ext| ext == "h") => {
+            Some(classify_header(path, text))
+        }
+        Some(lang) => Some(lang),
+        None => shebang(text).or_else(|| sniff(text)),
+    }
+}
+
+/// 文件名与扩展名；无扩展名且非文档类的文件 (如 `bin/deploy`) 留待内容判断
+fn detect_name(path: &Path) -> Option<PathMatch> {
+    match detect_filename(path) {
+        Some(Some(lang)) => return Some(PathMatch::Likely(lang)),
+        Some(None) => return None,
+        None => {}
+    }
+    if let Some(lang) = detect(path) {
+        return Some(PathMatch::Likely(lang));
+    }
+    (path.extension().is_none() && !is_doc_name(path)).then_some(PathMatch::Unknown)
+}
+
+/// 常见文件名: Some(None) 为已识别但不支持 (Dockerfile、Makefile 等)
+fn detect_filename(path: &Path) -> Option<Option<Language>> {
+    let name = path.file_name()?.to_str()?;
+    if name.starts_with("Dockerfile") || name.to_ascii_lowercase().ends_with(".dockerfile") {
+        return Some(None);
+    }
+    FILENAMES
+        .iter()
+        .find(|(known, _)| *known == name)
+        .map(|(_, lang)| *lang)
+}
+
+/// Emacs modeline (第一行，有 shebang 时第二行) 或 Vim modeline (开头 / 末尾 5 行)；
+/// 指定的语言不支持时忽略
+fn modeline(text: &str) -> Option<Language> {
+    let emacs = text.lines().take(2).find_map(emacs_mode);
+    let vim = text
+        .lines()
+        .take(MODELINE_LINES)
+        .chain(text.lines().rev().take(MODELINE_LINES))
+        .find_map(vim_filetype);
+    emacs
+        .and_then(Language::from_name)
+        .or_else(|| vim.and_then(Language::from_name))
+}
+
+/// `-*- C++ -*-` 或 `-*- mode: python; coding: utf-8 -*-`
+fn emacs_mode(line: &str) -> Option<&str> {
+    let start = line.find("-*-")? + 3;
+    let rest = &line[start..];
+    let body = rest[..rest.find("-*-")?].trim();
+    if !body.contains(':') {
+        return Some(body);
+    }
+    body.split(';').find_map(|part| {
+        let (key, value) = part.split_once(':')?;
+        key.trim()
+            .eq_ignore_ascii_case("mode")
+            .then(|| value.trim())
+    })
+}
+
+/// `vim: set ft=cpp :`、`vi:filetype=python`、`ex: syntax=ruby`
+fn vim_filetype(line: &str) -> Option<&str> {
+    ["vim:", "vi:", "ex:"].iter().find_map(|marker| {
+        let at = line.find(marker)?;
+        // 标记须在行首或空白之后 (排除 `index:` 等)
+        if line[..at]
+            .chars()
+            .next_back()
+            .is_some_and(|c| !c.is_whitespace())
+        {
+            return None;
+        }
+        line[at + marker.len()..]
+            .split(|c: char| c.is_whitespace() || c == ':')
+            .find_map(|option| {
+                let (key, value) = option.split_once('=')?;
+                matches!(key, "ft" | "filetype" | "syntax" | "syn").then_some(value)
+            })
+    })
+}
+
+/// `#!/usr/bin/python3`、`#!/usr/bin/env -S node --flag` 等解释器
+fn shebang(text: &str) -> Option<Language> {
+    let line = text.lines().next()?.strip_prefix("#!")?;
+    let mut words = line.split_whitespace();
+    let mut program = words.next()?.rsplit('/').next()?;
+    if program == "env" {
+        // 跳过 env 的选项与变量赋值
+        program = words.find(|w| !w.starts_with('-') && !w.contains('='))?;
+        program = program.rsplit('/').next()?;
+    }
+    // 去掉版本号: python3.11、ruby2.7
+    match program.trim_end_matches(|c: char| c.is_ascii_digit() || c == '.') {
+        "python" | "pypy" => Some(Language::Python),
+        "ruby" | "jruby" | "macruby" => Some(Language::Ruby),
+        "node" | "nodejs" | "bun" => Some(Language::JavaScript),
+        "deno" | "ts-node" | "tsx" => Some(Language::TypeScript),
+        "swift" => Some(Language::Swift),
+        "kotlin" | "kscript" => Some(Language::Kotlin),
+        "rust-script" | "cargo" => Some(Language::Rust),
+        "gorun" => Some(Language::Go),
+        "dotnet-script" => Some(Language::CSharp),
+        _ => None,
+    }
+}
+
+/// 无扩展名文件的内容特征 (开头 100 个不缩进的非空行)：须命中同一语言的至少两个不同特征，
+/// 按表中顺序取第一个满足的语言；C 再按内容区分 C++
+fn sniff(content: &str) -> Option<Language> {
+    let lines: Vec<&str> = content
+        .lines()
+        .filter(|line| !line.starts_with(char::is_whitespace))
+        .map(str::trim_end)
+        .filter(|line| !line.is_empty())
+        .take(SNIFF_LINES)
+        .collect();
+
+    let (lang, _) = SNIFF_MARKERS.iter().find(|(_, markers)| {
+        let hits = markers
+            .iter()
+            .filter(|marker| lines.iter().any(|line| marker_matches(line, marker)))
+            .count();
+        hits >= SNIFF_MIN_MARKERS
+    })?;
+    if *lang == Language::C {
+        return Some(header_language_by_content(content).unwrap_or(Language::C));
+    }
+    Some(*lang)
+}
+
+/// 特征模式: 不含 `*` 时整行相等，否则为 `前缀*中间*后缀`，各段按顺序出现
+fn marker_matches(line: &str, marker: &str) -> bool {
+    let mut parts = marker.split('*');
+    let first = parts.next().unwrap_or_default();
+    let Some(mut rest) = line.strip_prefix(first) else {
+        return false;
+    };
+    let parts: Vec<&str> = parts.collect();
+    let Some((last, middle)) = parts.split_last() else {
+        return rest.is_empty();
+    };
+    for part in middle {
+        match rest.find(part) {
+            Some(at) => rest = &rest[at + part.len()..],
+            None => return false,
+        }
+    }
+    rest.ends_with(last)
+}
+
+/// 文档类文件名 (不区分大小写)，不做内容判断
+fn is_doc_name(path: &Path) -> bool {
+    path.file_name()
+        .and_then(|name| name.to_str())
+        .is_some_and(|name| DOC_NAMES.iter().any(|doc| doc.eq_ignore_ascii_case(name)))
+}
+
+/// `.h` 分类: 先看内容特征，再看同名实现文件，默认 C
+pub fn classify_header(path: &Path, content: &str) -> Language {
+    if let Some(lang) = header_language_by_content(content) {
+        return lang;
+    }
+
+    const SIBLINGS: &[(&str, Language)] = &[
+        ("m", Language::ObjC),
+        ("mm", Language::ObjC),
+        ("cpp", Language::Cpp),
+        ("cc", Language::Cpp),
+        ("cxx", Language::Cpp),
+        ("c", Language::C),
+    ];
+    SIBLINGS
+        .iter()
+        .find(|(ext, _)| path.with_extension(ext).is_file())
+        .map_or(Language::C, |(_, lang)| *lang)
+}
+
+/// 内容特征 (注释行忽略)，无法判断时返回 None
+fn header_language_by_content(content: &str) -> Option<Language> {
+    const OBJC: &[&str] = &["@interface", "@protocol", "@class", "@end", "#import"];
+    const CPP: &[&str] = &[
+        "class ",
+        "namespace ",
+        "template<",
+        "template <",
+        "public:",
+        "private:",
+        "protected:",
+        "using ",
+    ];
+
+    let lines = content.lines().map(str::trim).filter(|line| {
+        !(line.starts_with("//") || line.starts_with('*') || line.starts_with("/*"))
+    });
+
+    let mut cpp = false;
+    for line in lines {
+        if OBJC.iter().any(|marker| line.starts_with(marker)) {
+            return Some(Language::ObjC);
+        }
+        if CPP.iter().any(|marker| line.starts_with(marker)) || has_scope_operator(line) {
+            cpp = true;
+        }
+    }
+    cpp.then_some(Language::Cpp)
+}
+
+/// 行内是否有作用域运算符 (`std::string`、`Foo<T>::type`)，
+/// 忽略字符串、字符字面量与 C23 `[[gnu::...]]` 属性中的 `::`
+fn has_scope_operator(line: &str) -> bool {
+    let bytes = line.as_bytes();
+    let mut code = Vec::with_capacity(bytes.len());
+    let mut i = 0;
+    while i < bytes.len() {
+        match bytes[i] {
+            quote @ (b'"' | b'\'') => {
+                // 跳到配对的引号 (跳过转义字符)
+                i += 1;
+                while i < bytes.len() && bytes[i] != quote {
+                    if bytes[i] == b'\\' {
+                        i += 1;
+                    }
+                    i += 1;
+                }
+                code.push(b' ');
+            }
+            b'[' if bytes.get(i + 1) == Some(&b'[') => {
+                i = line[i..].find("]]").map_or(bytes.len(), |end| i + end + 1);
+                code.push(b' ');
+            }
+            b => code.push(b),
+        }
+        i += 1;
+    }
+
+    let ident = |b: u8| b.is_ascii_alphanumeric() || b == b'_';
+    code.windows(4).any(|w| {
+        (ident(w[0]) || w[0] == b'>')
+            && w[1] == b':'
+            && w[2] == b':'
+            && (ident(w[3]) || w[3] == b'~')
+    })
+}
+
 // ============================================================
 // 单元测试
 // ============================================================
@@ -83,11 +579,15 @@ mod tests {
     fn test_detect_python() {
         assert_eq!(detect(Path::new("test.py")), Some(Language::Python));
         assert_eq!(detect(Path::new("test.pyi")), Some(Language::Python));
+        assert_eq!(detect(Path::new("defs.bzl")), Some(Language::Python));
+        assert_eq!(detect(Path::new("BUILD.bazel")), Some(Language::Python));
     }
 
     #[test]
     fn test_detect_typescript() {
         assert_eq!(detect(Path::new("test.ts")), Some(Language::TypeScript));
+        assert_eq!(detect(Path::new("index.d.ts")), Some(Language::TypeScript));
+        assert_eq!(detect(Path::new("test.mts")), Some(Language::TypeScript));
         assert_eq!(detect(Path::new("test.tsx")), Some(Language::Tsx));
     }
 
@@ -129,9 +629,65 @@ mod tests {
         assert_eq!(detect(Path::new("test.hxx")), Some(Language::Cpp));
     }
 
+    #[test]
+    fn test_classify_header_by_content() {
+        let path = Path::new("/nonexistent/foo.h");
+        assert_eq!(
+            classify_header(
+                path,
+                "#import <Foundation/Foundation.h>\n@interface Foo : NSObject\n@end\n"
+            ),
+            Language::ObjC
+        );
+        assert_eq!(
+            classify_header(path, "namespace app {\nclass Foo {\npublic:\n};\n}\n"),
+            Language::Cpp
+        );
+        assert_eq!(
+            classify_header(path, "// class Foo is not here\n#ifdef __cplusplus\nextern \"C\" {\n#endif\nint add(int a, int b);\n"),
+            Language::C
+        );
+        // 属性与字符串中的 `::` 不是作用域运算符
+        assert_eq!(
+            classify_header(
+                path,
+                "[[gnu::always_inline]] static inline int f(void);\n#define SEP \"::\"\nchar c = ':';\n"
+            ),
+            Language::C
+        );
+        assert_eq!(
+            classify_header(path, "std::string name(void);\n"),
+            Language::Cpp
+        );
+        assert_eq!(
+            classify_header(path, "typedef Foo<int>::type T;\n"),
+            Language::Cpp
+        );
+    }
+
+    #[test]
+    fn test_classify_header_by_sibling() {
+        let tmp = tempfile::TempDir::new().unwrap();
+        let header = tmp.path().join("util.h");
+        std::fs::write(tmp.path().join("util.mm"), "").unwrap();
+        assert_eq!(
+            classify_header(&header, "int add(int a, int b);\n"),
+            Language::ObjC
+        );
+
+        let header = tmp.path().join("other.h");
+        std::fs::write(tmp.path().join("other.cc"), "").unwrap();
+        assert_eq!(
+            classify_header(&header, "int add(int a, int b);\n"),
+            Language::Cpp
+        );
+    }
+
     #[test]
     fn test_detect_ruby() {
         assert_eq!(detect(Path::new("test.rb")), Some(Language::Ruby));
+        assert_eq!(detect(Path::new("tasks.rake")), Some(Language::Ruby));
+        assert_eq!(detect(Path::new("app.gemspec")), Some(Language::Ruby));
     }
 
     #[test]
@@ -154,7 +710,10 @@ mod tests {
     #[test]
     fn test_detect_kotlin() {
         assert_eq!(detect(Path::new("test.kt")), Some(Language::Kotlin));
-        assert_eq!(detect(Path::new("build.gradle.kts")), Some(Language::Kotlin));
+        assert_eq!(
+            detect(Path::new("build.gradle.kts")),
+            Some(Language::Kotlin)
+        );
     }
 
     #[test]
@@ -168,6 +727,165 @@ mod tests {
         assert_eq!(detect(Path::new("test")), None);
     }
 
+    #[test]
+    fn test_language_from_name() {
+        assert_eq!(Language::from_name("C++"), Some(Language::Cpp));
+        assert_eq!(Language::from_name("Objective-C"), Some(Language::ObjC));
+        assert_eq!(Language::from_name("C#"), Some(Language::CSharp));
+        assert_eq!(Language::from_name("Starlark"), Some(Language::Python));
+        assert_eq!(Language::from_name("python-mode"), Some(Language::Python));
+        assert_eq!(Language::from_name("typescriptreact"), Some(Language::Tsx));
+        assert_eq!(Language::from_name("Shell"), None);
+        for lang in Language::ALL {
+            assert_eq!(Language::from_name(lang.as_str()), Some(*lang));
+        }
+    }
+
+    #[test]
+    fn test_modeline() {
+        assert_eq!(modeline("// -*- C++ -*-\n"), Some(Language::Cpp));
+        assert_eq!(
+            modeline("#!/bin/sh\n# -*- mode: ruby; coding: utf-8 -*-\n"),
+            Some(Language::Ruby)
+        );
+        // 只有编码的 Emacs 行不指定语言
+        assert_eq!(modeline("# -*- coding: utf-8 -*-\n"), None);
+        assert_eq!(
+            modeline("int x;\n/* vim: set ft=cpp : */\n"),
+            Some(Language::Cpp)
+        );
+        // Vim modeline 可在末尾
+        let long = format!("{}# vim:filetype=python\n", "x = 1\n".repeat(10));
+        assert_eq!(modeline(&long), Some(Language::Python));
+        assert_eq!(modeline("index: ft=cpp\n"), None);
+        assert_eq!(modeline("# vim: ft=sh\n"), None);
+    }
+
+    #[test]
+    fn test_shebang() {
+        assert_eq!(shebang("#!/usr/bin/env python3\n"), Some(Language::Python));
+        assert_eq!(shebang("#!/usr/bin/python2.7 -u\n"), Some(Language::Python));
+        assert_eq!(
+            shebang("#!/usr/bin/env -S node --no-warnings\n"),
+            Some(Language::JavaScript)
+        );
+        assert_eq!(shebang("#!/usr/local/bin/ruby\n"), Some(Language::Ruby));
+        assert_eq!(
+            shebang("#!/usr/bin/env deno run\n"),
+            Some(Language::TypeScript)
+        );
+        assert_eq!(shebang("#!/bin/bash\n"), None);
+        assert_eq!(shebang("print(1)\n"), None);
+    }
+
+    #[test]
+    fn test_sniff() {
+        assert_eq!(
+            sniff("package main\n\nimport \"fmt\"\n\nfunc main() {}\n"),
+            Some(Language::Go)
+        );
+        assert_eq!(
+            sniff("#include <vector>\n#define N 3\nstd::vector<int> v;\n"),
+            Some(Language::Cpp)
+        );
+        assert_eq!(
+            sniff("#include <stdio.h>\nint main(void) {\n}\n"),
+            Some(Language::C)
+        );
+        assert_eq!(
+            sniff("import os\n\ndef main():\n    pass\n"),
+            Some(Language::Python)
+        );
+        assert_eq!(
+            sniff("require 'json'\n\ndef run\n  puts JSON.dump({})\nend\n"),
+            Some(Language::Ruby)
+        );
+        assert_eq!(
+            sniff("'use strict';\nconst fs = require('fs');\n"),
+            Some(Language::JavaScript)
+        );
+        assert_eq!(sniff("MIT License\n\nPermission is hereby granted\n"), None);
+        // 单个特征、缩进的示例代码不足以判断
+        assert_eq!(
+            sniff("Install the package, then:\n\n    from mylib import thing\n    import os\n"),
+            None
+        );
+        assert_eq!(
+            sniff("Remember to add\n#include <stdio.h>\nin main.c. See the docs.\n"),
+            None
+        );
+        assert_eq!(sniff("def greet\n"), None);
+    }
+
+    #[test]
+    fn test_detect_path() {
+        let tmp = tempfile::TempDir::new().unwrap();
+        let root = tmp.path();
+        std::fs::write(
+            root.join(".gitattributes"),
+            "*.inc linguist-language=C++\n*.tmpl linguist-language=Shell\n",
+        )
+        .unwrap();
+        let files = [
+            ("deploy", "#!/usr/bin/env ruby\nputs 1\n"),
+            ("Rakefile", "task :default\n"),
+            ("BUILD", "cc_library(name = \"x\")\n"),
+            ("util.inc", "int x;\n"),
+            ("app.py.tmpl", "def f():\n    pass\n"),
+            ("legacy.h", "// -*- C++ -*-\nint x;\n"),
+            ("script", "import os\n\ndef main():\n    pass\n"),
+            ("notes", "Some text.\n"),
+            ("image", "\0\0def f():\n"),
+        ];
+        for (name, content) in files {
+            std::fs::write(root.join(name), content).unwrap();
+        }
+        let attributes = Attributes::load(root).unwrap();
+        let detect = |name: &str| {
+            let path = root.join(name);
+            let by_path = detect_path(&path, Path::new(name), &attributes)?;
+            detect_content(&path, by_path, &std::fs::read_to_string(&path).unwrap())
+        };
+        let by_path = |name: &str| detect_path(&root.join(name), Path::new(name), &attributes);
+
+        // 只按路径决定是否读取: 未知扩展名、不支持的文件名与文档不读
+        assert_eq!(by_path("util.inc"), Some(PathMatch::Fixed(Language::Cpp)));
+        assert_eq!(by_path("Rakefile"), Some(PathMatch::Likely(Language::Ruby)));
+        assert_eq!(by_path("deploy"), Some(PathMatch::Unknown));
+        assert_eq!(by_path("data.json"), None);
+        assert_eq!(by_path("Dockerfile"), None);
+        assert_eq!(by_path("INSTALL"), None);
+
+        assert_eq!(detect("deploy"), Some(Language::Ruby));
+        assert_eq!(detect("Rakefile"), Some(Language::Ruby));
+        assert_eq!(detect("BUILD"), Some(Language::Python));
+        // linguist-language 优先；指定不支持的语言时不索引
+        assert_eq!(detect("util.inc"), Some(Language::Cpp));
+        assert_eq!(detect("app.py.tmpl"), None);
+        // modeline 优先于 `.h` 的内容与兄弟文件判断
+        assert_eq!(detect("legacy.h"), Some(Language::Cpp));
+        assert_eq!(detect("script"), Some(Language::Python));
+        assert_eq!(detect("notes"), None);
+        assert_eq!(detect("image"), None);
+    }
+
+    #[test]
+    fn test_detect_source() {
+        assert_eq!(
+            detect_source(Path::new("/unsaved/foo.py"), "x = 1\n"),
+            Some(Language::Python)
+        );
+        assert_eq!(
+            detect_source(Path::new("-"), "#!/usr/bin/env node\nmain();\n"),
+            Some(Language::JavaScript)
+        );
+        assert_eq!(
+            detect_source(Path::new("/unsaved/foo.h"), "class Foo {};\n"),
+            Some(Language::Cpp)
+        );
+        assert_eq!(detect_source(Path::new("notes.txt"), "hello\n"), None);
+    }
+
     #[test]
     fn test_language_as_str() {
         assert_eq!(Language::Python.as_str(), "python");
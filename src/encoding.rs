@@ -0,0 +1,219 @@
+// ============================================================
+// Encoding: 源码编码识别与解码
+// ============================================================
+
+use crate::symbol::{FileMap, Position, Range, Symbol};
+use chardetng::EncodingDetector;
+use encoding_rs::{Encoding, UTF_16BE, UTF_16LE, UTF_8};
+
+/// UTF-16 无 BOM 判断时检查的字节数
+const SNIFF_BYTES: usize = 4096;
+
+/// 解码后的源码 (Tree-sitter 解析 text，位置再映射回原始字节)
+pub struct Decoded {
+    pub text: String,
+    pub encoding: &'static Encoding,
+    /// 原始字节开头的 BOM 长度
+    pub bom: usize,
+    /// text 中各行的起始字节
+    line_starts: Vec<usize>,
+}
+
+/// 解码源码，优先级: BOM > 配置指定的编码 > 无 BOM 的 UTF-16 > 合法 UTF-8 > chardetng 猜测
+/// (Latin-1 / Windows-1252、Shift_JIS、GBK、EUC-KR 等)
+pub fn decode(bytes: &[u8], configured: Option<&'static Encoding>) -> Decoded {
+    let (encoding, bom) = match Encoding::for_bom(bytes) {
+        Some((encoding, bom)) => (encoding, bom),
+        None => (configured.unwrap_or_else(|| sniff(bytes)), 0),
+    };
+    let body = &bytes[bom..];
+    let text = if encoding == UTF_8 {
+        String::from_utf8_lossy(body).into_owned()
+    } else {
+        encoding.decode_without_bom_handling(body).0.into_owned()
+    };
+
+    let line_starts = if encoding == UTF_8 && bom == 0 {
+        Vec::new()
+    } else {
+        std::iter::once(0)
+            .chain(text.match_indices('\n').map(|(i, _)| i + 1))
+            .collect()
+    };
+
+    Decoded {
+        text,
+        encoding,
+        bom,
+        line_starts,
+    }
+}
+
+/// 无 BOM、未配置时猜测编码
+fn sniff(bytes: &[u8]) -> &'static Encoding {
+    // ASCII 为主的 UTF-16 同时也是合法 UTF-8 (含大量 NUL)，先判断
+    if let Some(encoding) = sniff_utf16(bytes) {
+        return encoding;
+    }
+    if std::str::from_utf8(bytes).is_ok() {
+        return UTF_8;
+    }
+    let mut detector = EncodingDetector::new();
+    detector.feed(bytes, true);
+    detector.guess(None, true)
+}
+
+/// 源码以 ASCII 为主，UTF-16 时每两个字节中有一个为 0: 偶数位多为 0 是 BE，奇数位多为 0 是 LE
+fn sniff_utf16(bytes: &[u8]) -> Option<&'static Encoding> {
+    let sample = &bytes[..bytes.len().min(SNIFF_BYTES) & !1];
+    if sample.is_empty() {
+        return None;
+    }
+    let pairs = sample.len() / 2;
+    let even = sample.iter().step_by(2).filter(|b| **b == 0).count();
+    let odd = sample
+        .iter()
+        .skip(1)
+        .step_by(2)
+        .filter(|b| **b == 0)
+        .count();
+    if odd * 10 >= pairs * 3 && even * 10 < pairs {
+        Some(UTF_16LE)
+    } else if even * 10 >= pairs * 3 && odd * 10 < pairs {
+        Some(UTF_16BE)
+    } else {
+        None
+    }
+}
+
+impl Decoded {
+    /// 非 UTF-8 或带 BOM 时的编码名 (如 `Shift_JIS`、`UTF-16LE`)
+    pub fn label(&self) -> Option<String> {
+        if self.line_starts.is_empty() {
+            None
+        } else {
+            Some(self.encoding.name().to_string())
+        }
+    }
+
+    /// 记录编码，并把提取结果中的列号 (text 中的 UTF-8 字节偏移) 换算为原始字节偏移；
+    /// 行号不变 (解码不改变换行)
+    pub fn restore_positions(&self, map: &mut FileMap) {
+        map.encoding = self.label();
+        if self.line_starts.is_empty() {
+            return;
+        }
+        self.restore_symbols(&mut map.symbols);
+        for diagnostic in &mut map.diagnostics {
+            diagnostic.column = self.original_column(diagnostic.line, diagnostic.column);
+        }
+    }
+
+    fn restore_symbols(&self, symbols: &mut [Symbol]) {
+        for sym in symbols {
+            self.restore_range(&mut sym.range);
+            for range in &mut sym.overloads {
+                self.restore_range(range);
+            }
+            self.restore_symbols(&mut sym.children);
+        }
+    }
+
+    fn restore_range(&self, range: &mut Range) {
+        for pos in [&mut range.start, &mut range.end] {
+            let Position { line, column } = *pos;
+            pos.column = self.original_column(line, column);
+        }
+    }
+
+    /// text 中第 line 行 (从 1 开始) 的 UTF-8 字节列换算为原始编码下的字节列
+    pub fn original_column(&self, line: usize, column: usize) -> usize {
+        let Some(&start) = self.line_starts.get(line.saturating_sub(1)) else {
+            return column;
+        };
+        let mut end = (start + column).min(self.text.len());
+        while !self.text.is_char_boundary(end) {
+            end -= 1;
+        }
+        let prefix = &self.text[start..end];
+        let width = if self.encoding == UTF_16LE || self.encoding == UTF_16BE {
+            prefix.encode_utf16().count() * 2
+        } else if self.encoding == UTF_8 {
+            prefix.len()
+        } else {
+            self.encoding.encode(prefix).0.len()
+        };
+        // BOM 只在第一行之前
+        if line == 1 {
+            width + self.bom
+        } else {
+            width
+        }
+    }
+}
+
+// ============================================================
+// 单元测试
+// ============================================================
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use encoding_rs::{SHIFT_JIS, WINDOWS_1252};
+
+    fn utf16le(text: &str, bom: bool) -> Vec<u8> {
+        let mut bytes = if bom { vec![0xFF, 0xFE] } else { Vec::new() };
+        bytes.extend(text.encode_utf16().flat_map(|unit| unit.to_le_bytes()));
+        bytes
+    }
+
+    #[test]
+    fn test_decode_utf8() {
+        let decoded = decode("fn main() {}\n".as_bytes(), None);
+        assert_eq!(decoded.encoding, UTF_8);
+        assert_eq!(decoded.label(), None);
+        assert_eq!(decoded.original_column(1, 3), 3);
+
+        let decoded = decode(b"\xEF\xBB\xBFfn main() {}\n", None);
+        assert_eq!(decoded.text, "fn main() {}\n");
+        assert_eq!(decoded.label(), Some("UTF-8".to_string()));
+        // 第一行的列号计入 BOM
+        assert_eq!(decoded.original_column(1, 3), 6);
+    }
+
+    #[test]
+    fn test_decode_utf16() {
+        let source = "class A {}\nint b;\n";
+        for bom in [true, false] {
+            let decoded = decode(&utf16le(source, bom), None);
+            assert_eq!(decoded.encoding, UTF_16LE);
+            assert_eq!(decoded.text, source);
+            assert_eq!(decoded.original_column(2, 4), 8);
+        }
+        assert_eq!(
+            decode(&utf16le(source, true), None).original_column(1, 6),
+            14
+        );
+    }
+
+    #[test]
+    fn test_decode_legacy_encodings() {
+        let latin1 = b"/* Gr\xFC\xDFe */ int caf\xE9 = 1;\n";
+        let decoded = decode(latin1, None);
+        assert_eq!(decoded.encoding, WINDOWS_1252);
+        assert_eq!(decoded.text, "/* Grüße */ int café = 1;\n");
+        // `int` 在 UTF-8 中位于第 14 字节，原文件中位于第 12 字节
+        assert_eq!(decoded.original_column(1, 14), 12);
+
+        let (sjis, _, _) =
+            SHIFT_JIS.encode("// 日本語のコメントです。ユーザー情報を管理します。\nint x;\n");
+        let decoded = decode(&sjis, None);
+        assert_eq!(decoded.encoding, SHIFT_JIS);
+        assert!(decoded.text.starts_with("// 日本語"));
+
+        // 配置指定的编码优先于猜测，BOM 优先于配置
+        let decoded = decode(b"caf\xE9", Some(SHIFT_JIS));
+        assert_eq!(decoded.encoding, SHIFT_JIS);
+        let decoded = decode(b"\xEF\xBB\xBFcafe", Some(SHIFT_JIS));
+        assert_eq!(decoded.encoding, UTF_8);
+    }
+}
@@ -0,0 +1,616 @@
+// ============================================================
+// EntryPoint: 入口点、CLI 命令与 HTTP 路由识别
+// ============================================================
+
+use crate::detector::Language;
+use crate::symbol::{EntryKind, EntryPoint, FileMap, Symbol, SymbolKind};
+
+/// HTTP 方法 (路由装饰器 / 注解的方法名部分)
+const HTTP_METHODS: &[&str] = &["get", "post", "put", "delete", "patch", "head", "options"];
+
+/// 含类型成员的容器 (路由 handler 记为 `Type.member`)
+const OWNER_KINDS: &[SymbolKind] = &[
+    SymbolKind::Class,
+    SymbolKind::Struct,
+    SymbolKind::Module,
+    SymbolKind::Impl,
+    SymbolKind::Record,
+    SymbolKind::Extension,
+];
+
+/// 在查询得到的入口点 (Python `__main__`、Express、Go、cobra、Rails) 之外，
+/// 按符号名与注解补充入口点，结果按行号排序
+/// - main: Go / Rust / C / C++ / Objective-C / Kotlin 顶层 `main`，Java / C# 静态 `main` / `Main`，Swift `@main` 类型
+/// - 路由: Flask / FastAPI 装饰器、Spring `@GetMapping` 等注解、ASP.NET `[HttpGet]` / `[Route]`
+/// - 命令: click / typer `@x.command()` / `@x.group()`，clap `derive(Parser)` 结构体与 `derive(Subcommand)` 枚举成员
+/// - Ruby 路由 DSL 只在 `routes.rb` 或 `routes/` 目录下生效
+pub fn discover(map: &mut FileMap, lang: Language) {
+    if lang == Language::Ruby && !is_routes_file(&map.path) {
+        map.entry_points.clear();
+    }
+    let mut found = Vec::new();
+    walk(&map.symbols, lang, None, &mut found);
+    for entry in found {
+        if !map
+            .entry_points
+            .iter()
+            .any(|e| e.kind == entry.kind && e.name == entry.name && e.line == entry.line)
+        {
+            map.entry_points.push(entry);
+        }
+    }
+    map.entry_points.sort_by_key(|e| e.line);
+}
+
+/// Rails 路由文件: `routes.rb` 或 `routes/` 目录下的文件
+fn is_routes_file(path: &str) -> bool {
+    let mut components = path.rsplit(['/', '\\']);
+    let file = components.next().unwrap_or("");
+    file == "routes.rb" || components.any(|dir| dir == "routes")
+}
+
+fn walk(symbols: &[Symbol], lang: Language, owner: Option<&Symbol>, out: &mut Vec<EntryPoint>) {
+    for sym in symbols {
+        let line = sym.range.start.line;
+        let handler = match owner {
+            Some(owner) => format!("{}.{}", owner.name, sym.name),
+            None => sym.name.clone(),
+        };
+
+        if is_main(sym, lang, owner.is_some()) {
+            out.push(EntryPoint {
+                kind: EntryKind::Main,
+                name: sym.name.clone(),
+                handler: owner.map(|_| handler.clone()),
+                line,
+            });
+        }
+        if matches!(sym.kind, SymbolKind::Function | SymbolKind::Method) {
+            let prefix = owner.map(|o| class_prefix(o, lang)).unwrap_or_default();
+            for (method, path) in routes(sym, lang, &prefix) {
+                let name = match method {
+                    Some(method) => format!("{} {}", method, path),
+                    None => path,
+                };
+                out.push(EntryPoint {
+                    kind: EntryKind::Route,
+                    name,
+                    handler: Some(handler.clone()),
+                    line,
+                });
+            }
+            if let Some(name) = python_command(sym, lang) {
+                out.push(EntryPoint {
+                    kind: EntryKind::Command,
+                    name,
+                    handler: Some(handler.clone()),
+                    line,
+                });
+            }
+        }
+        if lang == Language::Rust {
+            clap_commands(sym, out);
+        }
+
+        let owner = if OWNER_KINDS.contains(&sym.kind) {
+            Some(sym)
+        } else {
+            owner
+        };
+        walk(&sym.children, lang, owner, out);
+    }
+}
+
+// ------------------------------------------------------------
+// main
+// ------------------------------------------------------------
+
+fn is_main(sym: &Symbol, lang: Language, member: bool) -> bool {
+    if sym.has_attribute("main") && sym.kind != SymbolKind::Function {
+        // Swift `@main struct App`；Rust `#[tokio::main]` 落到下面的 fn main
+        return lang == Language::Swift;
+    }
+    if !matches!(sym.kind, SymbolKind::Function | SymbolKind::Method) {
+        return false;
+    }
+    let is_static = sym.signature.as_ref().is_some_and(|s| s.is_static);
+    match lang {
+        Language::Go
+        | Language::Rust
+        | Language::C
+        | Language::Cpp
+        | Language::ObjC
+        | Language::Kotlin => !member && sym.name == "main",
+        Language::Java => is_static && sym.name == "main",
+        Language::CSharp => is_static && sym.name == "Main",
+        _ => false,
+    }
+}
+
+// ------------------------------------------------------------
+// 路由
+// ------------------------------------------------------------
+
+/// 类级路由前缀: Spring `@RequestMapping("/api")`、ASP.NET `[Route("api/[controller]")]`
+fn class_prefix(owner: &Symbol, lang: Language) -> String {
+    match lang {
+        Language::Java | Language::Kotlin => owner
+            .attributes
+            .iter()
+            .find(|attr| attribute_name(attr) == "RequestMapping")
+            .and_then(|attr| string_arg(attr, "value").or_else(|| string_arg(attr, "path")))
+            .unwrap_or_default(),
+        Language::CSharp => {
+            let controller = owner.name.strip_suffix("Controller").unwrap_or(&owner.name);
+            owner
+                .attributes
+                .iter()
+                .find(|attr| attribute_name(attr) == "Route")
+                .and_then(|attr| string_arg(attr, "template"))
+                .map(|route| route.replace("[controller]", controller))
+                .unwrap_or_default()
+        }
+        _ => String::new(),
+    }
+}
+
+/// 函数 / 方法上的路由: (HTTP 方法, 路径)
+fn routes(sym: &Symbol, lang: Language, prefix: &str) -> Vec<(Option<String>, String)> {
+    let mut found = Vec::new();
+    for attr in &sym.attributes {
+        let name = attribute_name(attr);
+        let last = name.rsplit(['.', ':']).next().unwrap_or(name);
+        match lang {
+            // Flask `@app.route("/x", methods=[...])`、FastAPI `@router.get("/x")`
+            Language::Python if name.contains('.') => {
+                let Some(path) = string_arg(attr, "path") else {
+                    continue;
+                };
+                if last == "route" || last == "api_route" {
+                    // 未指定 methods 时 Flask 只接受 GET
+                    let mut methods = list_arg(attr, "methods");
+                    if methods.is_empty() {
+                        methods.push("GET".to_string());
+                    }
+                    for method in methods {
+                        found.push((Some(method.to_ascii_uppercase()), path.clone()));
+                    }
+                } else if HTTP_METHODS.contains(&last) {
+                    found.push((Some(last.to_ascii_uppercase()), path));
+                }
+            }
+            // Spring `@GetMapping("/x")`、`@RequestMapping(value = "/x", method = RequestMethod.POST)`
+            Language::Java | Language::Kotlin => {
+                let Some(verb) = last.strip_suffix("Mapping") else {
+                    continue;
+                };
+                let path = string_arg(attr, "value")
+                    .or_else(|| string_arg(attr, "path"))
+                    .unwrap_or_default();
+                let method = if verb == "Request" {
+                    keyword_value(attr, "method")
+                        .map(|m| m.rsplit('.').next().unwrap_or(&m).to_string())
+                } else if HTTP_METHODS.iter().any(|m| m.eq_ignore_ascii_case(verb)) {
+                    Some(verb.to_ascii_uppercase())
+                } else {
+                    continue;
+                };
+                found.push((method, join_path(prefix, &path)));
+            }
+            // ASP.NET `[HttpGet("{id}")]`、`[Route("x")]`；`/` 或 `~/` 开头的模板忽略类前缀
+            Language::CSharp => {
+                let verb = match last.strip_prefix("Http") {
+                    Some(verb) if HTTP_METHODS.iter().any(|m| m.eq_ignore_ascii_case(verb)) => {
+                        Some(verb.to_ascii_uppercase())
+                    }
+                    _ if last == "Route" => None,
+                    _ => continue,
+                };
+                // 方法上同时有 [Route] 与 [HttpGet] 时只记录带方法的一条
+                if verb.is_none()
+                    && sym
+                        .attributes
+                        .iter()
+                        .any(|a| attribute_name(a).starts_with("Http"))
+                {
+                    continue;
+                }
+                let template = string_arg(attr, "template")
+                    .unwrap_or_default()
+                    .replace("[action]", &sym.name);
+                let path = match template.strip_prefix('~').unwrap_or(&template) {
+                    absolute if absolute.starts_with('/') => absolute.to_string(),
+                    relative => join_path(prefix, relative),
+                };
+                found.push((verb, path));
+            }
+            _ => {}
+        }
+    }
+    found
+}
+
+/// 拼接路由前缀与路径，中间只保留一个 `/`
+fn join_path(prefix: &str, path: &str) -> String {
+    let head = prefix.trim_end_matches('/');
+    let rest = path.trim_start_matches('/');
+    match (head.is_empty(), rest.is_empty()) {
+        (true, true) => "/".to_string(),
+        (true, false) if prefix.starts_with('/') || path.starts_with('/') => format!("/{}", rest),
+        (true, false) => rest.to_string(),
+        (false, true) => head.to_string(),
+        (false, false) => format!("{}/{}", head, rest),
+    }
+}
+
+// ------------------------------------------------------------
+// 命令
+// ------------------------------------------------------------
+
+/// click / typer: `@click.command()`、`@cli.command("name")`、`@app.command(name="x")`、`@cli.group()`
+/// 未指定名称时与 click 一致，取函数名并将 `_` 换成 `-`
+fn python_command(sym: &Symbol, lang: Language) -> Option<String> {
+    if lang != Language::Python {
+        return None;
+    }
+    let attr = sym.attributes.iter().find(|attr| {
+        let name = attribute_name(attr);
+        name.contains('.') && (name.ends_with(".command") || name.ends_with(".group"))
+    })?;
+    Some(string_arg(attr, "name").unwrap_or_else(|| sym.name.replace('_', "-")))
+}
+
+/// clap derive: `derive(Parser)` 结构体为顶层命令，`derive(Subcommand)` 枚举成员为子命令
+/// 名称取 `#[command(name = "...")]`，否则结构体名 / 成员名的 kebab-case
+fn clap_commands(sym: &Symbol, out: &mut Vec<EntryPoint>) {
+    let derives = |name: &str| {
+        sym.attributes.iter().any(|attr| {
+            attribute_name(attr) == "derive"
+                && arguments(attr)
+                    .split(',')
+                    .any(|d| d.trim().rsplit(':').next() == Some(name))
+        })
+    };
+    if derives("Parser") && matches!(sym.kind, SymbolKind::Struct | SymbolKind::Enum) {
+        out.push(EntryPoint {
+            kind: EntryKind::Command,
+            name: command_name(sym).unwrap_or_else(|| sym.name.clone()),
+            handler: Some(sym.name.clone()),
+            line: sym.range.start.line,
+        });
+    }
+    if derives("Subcommand") && sym.kind == SymbolKind::Enum {
+        for variant in sym
+            .children
+            .iter()
+            .filter(|c| c.kind == SymbolKind::EnumMember)
+        {
+            out.push(EntryPoint {
+                kind: EntryKind::Command,
+                name: command_name(variant).unwrap_or_else(|| kebab_case(&variant.name)),
+                handler: Some(format!("{}.{}", sym.name, variant.name)),
+                line: variant.range.start.line,
+            });
+        }
+    }
+}
+
+fn command_name(sym: &Symbol) -> Option<String> {
+    sym.attributes
+        .iter()
+        .filter(|attr| matches!(attribute_name(attr), "command" | "clap"))
+        .find_map(|attr| keyword_value(attr, "name"))
+        .map(|name| name.trim_matches('"').to_string())
+}
+
+fn kebab_case(name: &str) -> String {
+    let mut out = String::new();
+    for (i, c) in name.chars().enumerate() {
+        if c.is_uppercase() && i > 0 {
+            out.push('-');
+        }
+        out.extend(c.to_lowercase());
+    }
+    out
+}
+
+// ------------------------------------------------------------
+// 注解文本解析 (clean_attribute 之后的 `name(args)`)
+// ------------------------------------------------------------
+
+/// 注解名: 参数之前的部分
+fn attribute_name(attr: &str) -> &str {
+    attr.split('(').next().unwrap_or(attr).trim()
+}
+
+/// 括号内的参数文本
+fn arguments(attr: &str) -> &str {
+    match (attr.find('('), attr.rfind(')')) {
+        (Some(start), Some(end)) if start < end => &attr[start + 1..end],
+        _ => "",
+    }
+}
+
+/// 按顶层逗号拆分参数 (忽略字符串、括号内的逗号)
+fn split_args(args: &str) -> Vec<&str> {
+    let mut parts = Vec::new();
+    let mut depth = 0i32;
+    let mut quote = None;
+    let mut start = 0;
+    for (i, c) in args.char_indices() {
+        match (quote, c) {
+            (Some(q), c) if c == q => quote = None,
+            (Some(_), _) => {}
+            (None, '"' | '\'') => quote = Some(c),
+            (None, '(' | '[' | '{') => depth += 1,
+            (None, ')' | ']' | '}') => depth -= 1,
+            (None, ',') if depth == 0 => {
+                parts.push(args[start..i].trim());
+                start = i + 1;
+            }
+            _ => {}
+        }
+    }
+    parts.push(args[start..].trim());
+    parts.retain(|p| !p.is_empty());
+    parts
+}
+
+/// 关键字参数 `key = value` / `key: value` 的值文本
+fn keyword_value(attr: &str, key: &str) -> Option<String> {
+    split_args(arguments(attr)).into_iter().find_map(|arg| {
+        let (k, v) = arg.split_once(['=', ':'])?;
+        (k.trim() == key).then(|| v.trim().to_string())
+    })
+}
+
+/// 字符串参数: 关键字 `key` 的值，否则首个位置参数的字符串；
+/// 数组形式 (Kotlin `["/x"]`、Java `{"/x"}`) 取首个元素
+fn string_arg(attr: &str, key: &str) -> Option<String> {
+    let value = keyword_value(attr, key).or_else(|| {
+        split_args(arguments(attr))
+            .into_iter()
+            .find(|arg| arg.starts_with(['"', '\'', '[', '{']))
+            .map(str::to_string)
+    })?;
+    let first = split_args(value.trim_matches(['[', ']', '{', '}']))
+        .into_iter()
+        .next()?
+        .to_string();
+    unquote(&first)
+}
+
+/// 列表参数中的字符串: `methods=["GET", "POST"]`
+fn list_arg(attr: &str, key: &str) -> Vec<String> {
+    keyword_value(attr, key)
+        .map(|value| {
+            split_args(value.trim_matches(['[', ']', '(', ')', '{', '}']))
+                .into_iter()
+                .filter_map(unquote)
+                .collect()
+        })
+        .unwrap_or_default()
+}
+
+fn unquote(text: &str) -> Option<String> {
+    let text = text.trim();
+    let quote = text.chars().next().filter(|c| matches!(c, '"' | '\''))?;
+    Some(
+        text.trim_start_matches(quote)
+            .trim_end_matches(quote)
+            .to_string(),
+    )
+}
+
+// ============================================================
+// 单元测试
+// ============================================================
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::symbol::Signature;
+
+    fn discovered(
+        path: &str,
+        lang: Language,
+        symbols: Vec<Symbol>,
+    ) -> Vec<(EntryKind, String, Option<String>)> {
+        let mut map = FileMap::new(path, lang.as_str()).with_symbols(symbols);
+        discover(&mut map, lang);
+        map.entry_points
+            .into_iter()
+            .map(|e| (e.kind, e.name, e.handler))
+            .collect()
+    }
+
+    fn entry(
+        kind: EntryKind,
+        name: &str,
+        handler: Option<&str>,
+    ) -> (EntryKind, String, Option<String>) {
+        (kind, name.to_string(), handler.map(str::to_string))
+    }
+
+    #[test]
+    fn test_attribute_args() {
+        let attr = r#"app.route("/users/<id>", methods=["GET", "POST"])"#;
+        assert_eq!(attribute_name(attr), "app.route");
+        assert_eq!(string_arg(attr, "path"), Some("/users/<id>".to_string()));
+        assert_eq!(list_arg(attr, "methods"), vec!["GET", "POST"]);
+
+        let attr = "RequestMapping(value = \"/x\", method = RequestMethod.POST)";
+        assert_eq!(string_arg(attr, "value"), Some("/x".to_string()));
+        assert_eq!(
+            keyword_value(attr, "method"),
+            Some("RequestMethod.POST".to_string())
+        );
+        assert_eq!(string_arg("HttpPost", "template"), None);
+    }
+
+    #[test]
+    fn test_join_path() {
+        assert_eq!(join_path("/api", "/users"), "/api/users");
+        assert_eq!(join_path("/api/", "users"), "/api/users");
+        assert_eq!(join_path("", "/users"), "/users");
+        assert_eq!(join_path("api/Users", ""), "api/Users");
+        assert_eq!(join_path("", ""), "/");
+        assert_eq!(kebab_case("InitDb"), "init-db");
+    }
+
+    #[test]
+    fn test_main_functions() {
+        let mut java_main = Symbol::at("main", SymbolKind::Method, 3);
+        java_main.signature = Some(Signature {
+            is_static: true,
+            ..Signature::default()
+        });
+        let mut class = Symbol::at("App", SymbolKind::Class, 1);
+        class.children = vec![java_main, Symbol::at("helper", SymbolKind::Method, 5)];
+        assert_eq!(
+            discovered("App.java", Language::Java, vec![class]),
+            vec![entry(EntryKind::Main, "main", Some("App.main"))]
+        );
+
+        assert_eq!(
+            discovered(
+                "main.go",
+                Language::Go,
+                vec![Symbol::at("main", SymbolKind::Function, 1)]
+            ),
+            vec![entry(EntryKind::Main, "main", None)]
+        );
+        assert_eq!(
+            discovered(
+                "App.swift",
+                Language::Swift,
+                vec![Symbol::at("App", SymbolKind::Struct, 1).with_attributes(&["main"])]
+            ),
+            vec![entry(EntryKind::Main, "App", None)]
+        );
+        // 非静态 Java main 不是入口
+        assert!(discovered(
+            "A.java",
+            Language::Java,
+            vec![Symbol::at("main", SymbolKind::Method, 1)]
+        )
+        .is_empty());
+    }
+
+    #[test]
+    fn test_python_routes_and_commands() {
+        let symbols = vec![
+            Symbol::at("user", SymbolKind::Function, 2)
+                .with_attributes(&[r#"app.route("/users/<id>", methods=["GET", "POST"])"#]),
+            Symbol::at("items", SymbolKind::Function, 5).with_attributes(&["router.get('/items')"]),
+            Symbol::at("serve_cmd", SymbolKind::Function, 8)
+                .with_attributes(&[r#"click.command(name="serve")"#]),
+            Symbol::at("init_db", SymbolKind::Function, 11).with_attributes(&["cli.command()"]),
+            Symbol::at("cached", SymbolKind::Function, 14)
+                .with_attributes(&["functools.cache", "get"]),
+        ];
+        assert_eq!(
+            discovered("app.py", Language::Python, symbols),
+            vec![
+                entry(EntryKind::Route, "GET /users/<id>", Some("user")),
+                entry(EntryKind::Route, "POST /users/<id>", Some("user")),
+                entry(EntryKind::Route, "GET /items", Some("items")),
+                entry(EntryKind::Command, "serve", Some("serve_cmd")),
+                entry(EntryKind::Command, "init-db", Some("init_db")),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_controller_routes() {
+        let mut spring = Symbol::at("UserController", SymbolKind::Class, 1)
+            .with_attributes(&["RestController", r#"RequestMapping("/api")"#]);
+        spring.children = vec![
+            Symbol::at("get", SymbolKind::Method, 3)
+                .with_attributes(&[r#"GetMapping("/users/{id}")"#]),
+            Symbol::at("x", SymbolKind::Method, 5)
+                .with_attributes(&[r#"RequestMapping(value = "/x", method = RequestMethod.POST)"#]),
+            Symbol::at("all", SymbolKind::Method, 7).with_attributes(&["DeleteMapping"]),
+        ];
+        assert_eq!(
+            discovered("UserController.java", Language::Java, vec![spring]),
+            vec![
+                entry(
+                    EntryKind::Route,
+                    "GET /api/users/{id}",
+                    Some("UserController.get")
+                ),
+                entry(EntryKind::Route, "POST /api/x", Some("UserController.x")),
+                entry(EntryKind::Route, "DELETE /api", Some("UserController.all")),
+            ]
+        );
+
+        let mut aspnet = Symbol::at("UsersController", SymbolKind::Class, 1)
+            .with_attributes(&["ApiController", r#"Route("api/[controller]")"#]);
+        aspnet.children = vec![
+            Symbol::at("Get", SymbolKind::Method, 3).with_attributes(&[r#"HttpGet("{id}")"#]),
+            Symbol::at("Create", SymbolKind::Method, 5).with_attributes(&["HttpPost"]),
+            Symbol::at("Health", SymbolKind::Method, 7).with_attributes(&[r#"HttpGet("/health")"#]),
+        ];
+        assert_eq!(
+            discovered("UsersController.cs", Language::CSharp, vec![aspnet]),
+            vec![
+                entry(
+                    EntryKind::Route,
+                    "GET api/Users/{id}",
+                    Some("UsersController.Get")
+                ),
+                entry(
+                    EntryKind::Route,
+                    "POST api/Users",
+                    Some("UsersController.Create")
+                ),
+                entry(
+                    EntryKind::Route,
+                    "GET /health",
+                    Some("UsersController.Health")
+                ),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_clap_commands() {
+        let mut commands = Symbol::at("Commands", SymbolKind::Enum, 4)
+            .with_attributes(&["derive(Subcommand, Debug)"]);
+        commands.children = vec![
+            Symbol::at("Serve", SymbolKind::EnumMember, 5),
+            Symbol::at("InitDb", SymbolKind::EnumMember, 7)
+                .with_attributes(&[r#"command(name = "db-init")"#]),
+        ];
+        let symbols = vec![
+            Symbol::at("Cli", SymbolKind::Struct, 1)
+                .with_attributes(&["derive(Parser)", r#"command(name = "tool")"#]),
+            commands,
+        ];
+        assert_eq!(
+            discovered("main.rs", Language::Rust, symbols),
+            vec![
+                entry(EntryKind::Command, "tool", Some("Cli")),
+                entry(EntryKind::Command, "serve", Some("Commands.Serve")),
+                entry(EntryKind::Command, "db-init", Some("Commands.InitDb")),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_ruby_routes_only_in_routes_files() {
+        assert!(is_routes_file("config/routes.rb"));
+        assert!(is_routes_file("config/routes/admin.rb"));
+        assert!(!is_routes_file("app/models/user.rb"));
+
+        let mut map = FileMap::new("app/controllers/users_controller.rb", "ruby");
+        map.entry_points.push(EntryPoint {
+            kind: EntryKind::Route,
+            name: "GET /users".to_string(),
+            handler: None,
+            line: 1,
+        });
+        discover(&mut map, Language::Ruby);
+        assert!(map.entry_points.is_empty());
+    }
+}
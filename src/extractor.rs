@@ -3,28 +3,35 @@
 // ============================================================
 
 use crate::detector::Language;
-use crate::symbol::{FileMap, Position, Range, Symbol, SymbolKind};
+use crate::query::{self, CaptureRole, Queries};
+use crate::symbol::{
+    Diagnostic, DiagnosticKind, EntryKind, EntryPoint, FileMap, Parameter, Position, Range,
+    Signature, Symbol, TypeParam,
+};
 use anyhow::{Context, Result};
-use std::collections::HashSet;
-use std::path::Path;
+use std::collections::{HashMap, HashSet};
 use streaming_iterator::StreamingIterator;
-use tree_sitter::{Parser, Query, QueryCursor};
+use tree_sitter::{Node, Parser, QueryCursor, Tree};
+
+/// 每个文件最多记录的语法错误位置 (parse_errors 仍为总数)
+const MAX_DIAGNOSTICS: usize = 20;
 
 // ------------------------------------------------------------
 // 公开接口
 // ------------------------------------------------------------
 
-/// 从文件提取符号
-pub fn extract(path: &Path, lang: &Language) -> Result<FileMap> {
-    let code = std::fs::read_to_string(path)
-        .with_context(|| format!("Failed to read file: {}", path.display()))?;
-
-    let symbols = extract_symbols(&code, lang)?;
+/// 从源码提取符号，path 为输出中显示的路径
+pub fn extract(code: &str, path: &str, lang: &Language, queries: &Queries) -> Result<FileMap> {
+    let tree = parse(code, lang)?;
+    let (symbols, entry_points) = extract_tree(code, &tree, lang, queries)?;
+    let (parse_errors, diagnostics) = collect_diagnostics(tree.root_node(), code);
 
     Ok(FileMap {
-        path: path.to_string_lossy().to_string(),
-        language: lang.as_str().to_string(),
         symbols,
+        entry_points,
+        parse_errors,
+        diagnostics,
+        ..FileMap::new(path, lang.as_str())
     })
 }
 
@@ -34,89 +41,728 @@ pub fn extract(path: &Path, lang: &Language) -> Result<FileMap> {
 
 /// 扁平符号 (带原始范围信息用于嵌套计算)
 struct FlatSymbol {
-    name: String,
-    kind: SymbolKind,
+    symbol: Symbol,
     start_byte: usize,
     end_byte: usize,
-    range: Range,
+    /// 匹配的模式序号，重复匹配时靠后 (更具体) 的模式优先
+    pattern: usize,
+    /// 重载签名 (@overload)
+    overload: bool,
+    /// 泛型参数及其起始字节 (多个匹配合并后按源码顺序排列)
+    type_params: Vec<(usize, TypeParam)>,
+    /// 函数参数及其起始字节 (同上)
+    params: Vec<(usize, Parameter)>,
 }
 
-fn extract_symbols(code: &str, lang: &Language) -> Result<Vec<Symbol>> {
+/// 没有 @definition.<kind> 的匹配中的泛型参数 / 参数 / 返回类型，
+/// 归入以 `@symbol` 节点为符号节点或以 `@parameters` 节点为参数列表的符号
+struct Fragment {
+    owners: Vec<usize>,
+    type_params: Vec<(usize, TypeParam)>,
+    params: Vec<(usize, Parameter)>,
+    returns: Option<String>,
+}
+
+fn parse(code: &str, lang: &Language) -> Result<Tree> {
     let mut parser = Parser::new();
-    let ts_lang = get_language(lang);
+    let ts_lang = query::get_language(lang);
     parser.set_language(&ts_lang)?;
 
-    let tree = parser
-        .parse(code, None)
-        .context("Tree-sitter parse failed")?;
+    parser.parse(code, None).context("Tree-sitter parse failed")
+}
+
+/// 语法错误: ERROR 节点 (不再深入其子树) 与 MISSING 节点，按源码顺序
+/// 返回总数与前 MAX_DIAGNOSTICS 个位置
+fn collect_diagnostics(root: Node, code: &str) -> (usize, Vec<Diagnostic>) {
+    let mut count = 0;
+    let mut diagnostics = Vec::new();
+    let mut stack = vec![root];
+    while let Some(node) = stack.pop() {
+        let kind = if node.is_error() {
+            DiagnosticKind::Error
+        } else if node.is_missing() {
+            DiagnosticKind::Missing
+        } else {
+            if node.has_error() {
+                let mut cursor = node.walk();
+                let children: Vec<Node> = node.children(&mut cursor).collect();
+                stack.extend(children.into_iter().rev());
+            }
+            continue;
+        };
+
+        count += 1;
+        if diagnostics.len() < MAX_DIAGNOSTICS {
+            let text = match kind {
+                DiagnosticKind::Error => {
+                    let text = node.utf8_text(code.as_bytes()).unwrap_or("");
+                    let first = text.lines().next().unwrap_or("").trim();
+                    first.chars().take(40).collect()
+                }
+                DiagnosticKind::Missing => node.kind().to_string(),
+            };
+            diagnostics.push(Diagnostic {
+                kind,
+                line: node.start_position().row + 1,
+                column: node.start_position().column,
+                text,
+            });
+        }
+    }
+    (count, diagnostics)
+}
 
-    let query_src = get_query_source(lang);
-    let query = Query::new(&ts_lang, query_src)?;
+/// 提取符号树与查询中的入口点 (`@entry.<kind>`)
+fn extract_tree(
+    code: &str,
+    tree: &Tree,
+    lang: &Language,
+    queries: &Queries,
+) -> Result<(Vec<Symbol>, Vec<EntryPoint>)> {
+    let query = queries.compile(lang)?;
+    let roles: Vec<CaptureRole> = query
+        .capture_names()
+        .iter()
+        .map(|name| query::capture_role(name).unwrap_or(CaptureRole::Helper))
+        .collect();
     let mut cursor = QueryCursor::new();
 
-    // 第一步: 收集扁平符号列表
-    let mut flat_symbols = Vec::new();
-    let mut seen = HashSet::new();
+    // 第一步: 收集扁平符号列表 (同名同行的重复匹配按模式顺序合并)
+    let mut flat_symbols: Vec<FlatSymbol> = Vec::new();
+    let mut seen: HashMap<(String, usize), usize> = HashMap::new();
+    let mut exports: HashSet<String> = HashSet::new();
+    let mut entries: Vec<EntryPoint> = Vec::new();
+    let mut fragments: Vec<Fragment> = Vec::new();
+    let mut owners: HashMap<usize, usize> = HashMap::new();
     let mut matches = cursor.matches(&query, tree.root_node(), code.as_bytes());
-    let name_idx = query.capture_index_for_name("name");
 
     while let Some(m) = matches.next() {
         let mut name_text = String::new();
-        let mut kind = SymbolKind::Function;
-        let mut start_byte = 0usize;
-        let mut end_byte = 0usize;
-        let mut range = None;
+        let mut name_span: Option<(usize, usize)> = None;
+        let mut definition = None;
+        let mut fragment_owner = None;
+        let mut detail: Vec<&str> = Vec::new();
+        let mut doc: Vec<&str> = Vec::new();
+        let mut visibility: Vec<&str> = Vec::new();
+        let mut type_params: Vec<(usize, TypeParam)> = Vec::new();
+        let mut bounds: Vec<String> = Vec::new();
+        let mut params: Vec<(usize, Parameter)> = Vec::new();
+        let mut parameters = None;
+        let mut param_label = None;
+        let mut param_type = None;
+        let mut param_type_start = 0;
+        let mut param_default = None;
+        let mut variadic = false;
+        let mut has_signature = false;
+        let mut returns = None;
+        let mut returns_span: Option<(usize, usize)> = None;
+        let mut modifiers: Vec<&str> = Vec::new();
+        let mut extends: Vec<String> = Vec::new();
+        let mut implements: Vec<String> = Vec::new();
+        let mut attributes: Vec<String> = Vec::new();
+        let mut owner = None;
+        let mut template_args = None;
+        let mut dsl = None;
+        let mut overload = false;
+        let mut entry = None;
+        let mut http_method = None;
+        let mut handler = None;
 
         for cap in m.captures {
             let node = cap.node;
             let text = node.utf8_text(code.as_bytes()).unwrap_or("");
-            let cap_name = query.capture_names()[cap.index as usize];
 
-            // 跳过以 _ 开头的辅助 capture
-            if cap_name.starts_with('_') {
+            match roles[cap.index as usize] {
+                CaptureRole::Name => {
+                    // 多个 @name 取首个起点到末个终点的源码
+                    let (start, end) = name_span.unwrap_or((node.start_byte(), node.end_byte()));
+                    let span = (start.min(node.start_byte()), end.max(node.end_byte()));
+                    name_text = collapse_whitespace(&code[span.0..span.1]);
+                    name_span = Some(span);
+                }
+                CaptureRole::Definition(kind) => definition = Some((kind, node)),
+                CaptureRole::Fragment => fragment_owner = Some(node.id()),
+                CaptureRole::Detail => detail.push(text.trim()),
+                CaptureRole::Doc => doc.push(text),
+                CaptureRole::Visibility => visibility.push(text.trim()),
+                CaptureRole::TypeParam => type_params.push((
+                    node.start_byte(),
+                    TypeParam {
+                        name: text.trim().to_string(),
+                        bounds: Vec::new(),
+                    },
+                )),
+                CaptureRole::TypeBound => bounds.push(collapse_whitespace(text)),
+                CaptureRole::Parameters => {
+                    has_signature = true;
+                    parameters = Some(node.id());
+                }
+                CaptureRole::Param => params.push((
+                    node.start_byte(),
+                    Parameter {
+                        name: collapse_whitespace(text),
+                        label: None,
+                        type_name: None,
+                        default: None,
+                        variadic: false,
+                    },
+                )),
+                CaptureRole::ParamDeclaration => {
+                    params.push((node.start_byte(), declared_parameter(&node, code)))
+                }
+                CaptureRole::ParamLabel => param_label = Some(collapse_whitespace(text)),
+                CaptureRole::ParamType => {
+                    param_type = Some(collapse_whitespace(text));
+                    param_type_start = node.start_byte();
+                }
+                CaptureRole::ParamDefault => param_default = Some(collapse_whitespace(text)),
+                CaptureRole::ParamVariadic => variadic = true,
+                CaptureRole::Returns => {
+                    // 多个 @returns 同样取首尾之间的源码 (C 的 `const char *`)
+                    let (start, end) = returns_span.unwrap_or((node.start_byte(), node.end_byte()));
+                    let span = (start.min(node.start_byte()), end.max(node.end_byte()));
+                    returns = Some(collapse_whitespace(&code[span.0..span.1]));
+                    returns_span = Some(span);
+                }
+                CaptureRole::Modifier => modifiers.push(text),
+                CaptureRole::Extends => extends.push(collapse_whitespace(text)),
+                CaptureRole::Implements => implements.push(collapse_whitespace(text)),
+                CaptureRole::Attribute => attributes.push(clean_attribute(text)),
+                CaptureRole::Owner => owner = Some(text.trim().to_string()),
+                CaptureRole::QualifiedName => {
+                    let qualified = split_qualified(text);
+                    name_text = qualified.name;
+                    owner = qualified.scope;
+                    template_args = qualified.args;
+                }
+                CaptureRole::Dsl => dsl = Some(text.trim()),
+                CaptureRole::Overload => overload = true,
+                CaptureRole::Export => {
+                    exports.insert(text.trim().trim_matches(['"', '\'']).to_string());
+                }
+                CaptureRole::Entry(kind) => entry = Some((kind, node)),
+                CaptureRole::HttpMethod => http_method = Some(text.trim()),
+                CaptureRole::Handler => handler = Some(collapse_whitespace(text)),
+                CaptureRole::Helper => {}
+            }
+        }
+
+        if let Some((kind, node)) = entry {
+            let name = entry_name(kind, &name_text, http_method);
+            if !name.is_empty() {
+                let line = node.start_position().row + 1;
+                match entries
+                    .iter_mut()
+                    .find(|e| e.kind == kind && e.name == name && e.line == line)
+                {
+                    Some(existing) => existing.handler = existing.handler.take().or(handler),
+                    None => entries.push(EntryPoint {
+                        kind,
+                        name,
+                        handler,
+                        line,
+                    }),
+                }
+            }
+        }
+
+        // 只有类型的参数 (C++ `const A&`、Go `func(int, string)`) 名称为空
+        if params.is_empty() && param_type.is_some() {
+            params.push((param_type_start, Parameter::default()));
+        }
+
+        // 同一匹配中的标签 / 类型 / 默认值属于该匹配捕获的参数 (如 Go 的 `a, b int`)
+        for (_, param) in &mut params {
+            param.label = param_label.clone().or(param.label.take());
+            param.type_name = param_type.clone().or(param.type_name.take());
+            param.default = param_default.clone().or(param.default.take());
+            param.variadic |= variadic;
+        }
+
+        // 同一匹配中的约束属于该匹配捕获的参数 (如 Go 的 `K, V any`)
+        for (_, param) in &mut type_params {
+            param.bounds.clone_from(&bounds);
+        }
+
+        let Some((mut kind, node)) = definition else {
+            // 单独的泛型参数 / 参数 / 返回类型匹配归入所属符号 (见第二步)
+            let owners: Vec<usize> = fragment_owner.into_iter().chain(parameters).collect();
+            if !owners.is_empty() {
+                fragments.push(Fragment {
+                    owners,
+                    type_params,
+                    params,
+                    returns,
+                });
+            }
+            continue;
+        };
+
+        // DSL 调用: 类型查 catalog，方法名记入 detail，符号名 (或名称列表) 去掉 Ruby symbol 的冒号
+        if let Some(method) = dsl {
+            let Some(dsl_kind) = queries.dsl_kind(lang, method) else {
                 continue;
+            };
+            kind = dsl_kind;
+            name_text = name_text
+                .split(", ")
+                .map(|name| name.trim_start_matches(':'))
+                .collect::<Vec<_>>()
+                .join(", ");
+            if name_text != method {
+                detail.insert(0, method);
+            }
+        }
+        if name_text.is_empty() {
+            continue;
+        }
+
+        let mut symbol = Symbol::new(name_text, kind, node_range(&node));
+        symbol.detail = Some(detail.join(" ")).filter(|d| !d.is_empty());
+        symbol.doc = clean_doc(&doc);
+        symbol.visibility = Some(visibility.join(" ")).filter(|v| !v.is_empty());
+        symbol.extends = extends;
+        symbol.implements = implements;
+        symbol.attributes = attributes;
+        symbol.owner = owner;
+        symbol.has_body = has_body(&node);
+
+        // 显式特化 (`template<> void Foo<int>::bar`、`template<> int add<int>`): 名称不含模板实参，
+        // 实参记入 detail，聚合时不与泛型成员配对
+        if let Some(args) = template_args.filter(|_| is_explicit_specialization(&node)) {
+            symbol.detail = Some(match symbol.detail.take() {
+                Some(detail) => format!("{} {}", args, detail),
+                None => args,
+            });
+        }
+
+        if has_signature || !params.is_empty() || returns.is_some() {
+            let mut signature = Signature {
+                returns,
+                ..Signature::default()
+            };
+            for modifier in &modifiers {
+                signature.apply_modifier(modifier);
+            }
+            symbol.signature = Some(signature);
+        }
+
+        let key = (symbol.name.clone(), symbol.range.start.line);
+        let flat = FlatSymbol {
+            symbol,
+            start_byte: node.start_byte(),
+            end_byte: node.end_byte(),
+            pattern: m.pattern_index,
+            overload,
+            type_params,
+            params,
+        };
+        let index = seen.get(&key).copied().unwrap_or(flat_symbols.len());
+        owners.insert(node.id(), index);
+        if let Some(list) = parameters {
+            owners.insert(list, index);
+        }
+        match seen.get(&key) {
+            Some(&i) if flat.pattern > flat_symbols[i].pattern => {
+                let mut flat = flat;
+                flat.overload |= flat_symbols[i].overload;
+                let other = std::mem::replace(&mut flat_symbols[i], flat);
+                merge_metadata(&mut flat_symbols[i].symbol, other.symbol);
+                merge_type_params(&mut flat_symbols[i].type_params, other.type_params);
+                merge_params(&mut flat_symbols[i].params, other.params);
+            }
+            Some(&i) => {
+                flat_symbols[i].overload |= flat.overload;
+                merge_metadata(&mut flat_symbols[i].symbol, flat.symbol);
+                merge_type_params(&mut flat_symbols[i].type_params, flat.type_params);
+                merge_params(&mut flat_symbols[i].params, flat.params);
+            }
+            None => {
+                seen.insert(key, flat_symbols.len());
+                flat_symbols.push(flat);
             }
+        }
+    }
+
+    // 第二步: 单独的匹配归入以其 owner 为符号节点或参数列表的符号，
+    // 其余 (如函数指针的参数) 丢弃；单独匹配的返回类型 (尾置返回类型) 优先
+    for fragment in fragments {
+        if let Some(&i) = fragment.owners.iter().find_map(|owner| owners.get(owner)) {
+            let flat = &mut flat_symbols[i];
+            merge_type_params(&mut flat.type_params, fragment.type_params);
+            merge_params(&mut flat.params, fragment.params);
+            if let Some(returns) = fragment.returns {
+                flat.symbol
+                    .signature
+                    .get_or_insert_with(Signature::default)
+                    .returns = Some(returns);
+            }
+        }
+    }
+
+    // 第三步: 按 start_byte 排序
+    flat_symbols.sort_by_key(|s| s.start_byte);
+
+    // 第四步: 相邻的同名重载签名归并为一个符号
+    let flat_symbols = group_overloads(flat_symbols);
+
+    // 第五步: 构建嵌套树
+    let mut symbols = build_nested_tree(flat_symbols);
+
+    // 第六步: 导出列表中的顶层符号标记为导出
+    for symbol in &mut symbols {
+        if symbol.visibility.is_none() && exports.contains(&symbol.name) {
+            symbol.visibility = Some("export".to_string());
+        }
+    }
+
+    entries.sort_by_key(|e| e.line);
+    Ok((symbols, entries))
+}
 
-            if Some(cap.index) == name_idx {
-                name_text = text.to_string();
+/// 入口点名称: 去掉字符串引号；命令取首个单词 (cobra `Use: "serve [flags]"`)；
+/// 路由前加大写的 HTTP 方法 (`GET /users`)，其他路由 DSL 保留原样 (`resources :users`)
+fn entry_name(kind: EntryKind, name: &str, http_method: Option<&str>) -> String {
+    let name = name.trim_matches(['"', '\'', '`']);
+    if name.is_empty() {
+        return String::new();
+    }
+    match (kind, http_method) {
+        (EntryKind::Command, _) => name.split_whitespace().next().unwrap_or("").to_string(),
+        (EntryKind::Route, Some(method)) => {
+            const VERBS: &[&str] = &[
+                "get", "post", "put", "delete", "patch", "head", "options", "all",
+            ];
+            if VERBS.iter().any(|v| v.eq_ignore_ascii_case(method)) {
+                format!("{} {}", method.to_ascii_uppercase(), name)
             } else {
-                kind = parse_kind(cap_name);
-                start_byte = node.start_byte();
-                end_byte = node.end_byte();
-                range = Some(Range {
-                    start: Position {
-                        line: node.start_position().row + 1,
-                        column: node.start_position().column,
-                    },
-                    end: Position {
-                        line: node.end_position().row + 1,
-                        column: node.end_position().column,
-                    },
+                format!("{} {}", method, name)
+            }
+        }
+        _ => name.to_string(),
+    }
+}
+
+fn node_range(node: &Node) -> Range {
+    Range {
+        start: Position {
+            line: node.start_position().row + 1,
+            column: node.start_position().column,
+        },
+        end: Position {
+            line: node.end_position().row + 1,
+            column: node.end_position().column,
+        },
+    }
+}
+
+/// 拆分后的限定名
+#[derive(Debug, PartialEq)]
+struct Qualified {
+    /// 作用域 (去掉模板实参)
+    scope: Option<String>,
+    name: String,
+    /// 显式特化的模板实参: 作用域末段 (`Foo<int>::bar`) 或名称 (`Foo::as<int>`、`add<int>`) 上的 `<int>`
+    args: Option<String>,
+}
+
+/// 拆分限定名 `ns::Foo<int>::bar` → (ns::Foo, bar, `<int>`)，模板参数不参与拆分；
+/// 名称上的模板实参同样拆出 (`Foo::as<int>` → (Foo, as, `<int>`))，运算符名不拆
+fn split_qualified(text: &str) -> Qualified {
+    let mut segments = Vec::new();
+    let mut depth = 0usize;
+    let mut current = String::new();
+    let mut chars = text.chars().peekable();
+
+    while let Some(c) = chars.next() {
+        match c {
+            '<' => {
+                depth += 1;
+                current.push(c);
+            }
+            '>' => {
+                depth = depth.saturating_sub(1);
+                current.push(c);
+            }
+            ':' if depth == 0 && chars.peek() == Some(&':') => {
+                chars.next();
+                segments.push(std::mem::take(&mut current));
+            }
+            _ => current.push(c),
+        }
+    }
+
+    let mut name = current.trim().to_string();
+    let scope_args = segments
+        .last()
+        .and_then(|last| last.find('<').map(|at| last[at..].trim().to_string()));
+    let name_args = match name.find('<') {
+        Some(at) if !name.starts_with("operator") && name.ends_with('>') => {
+            let args = name[at..].to_string();
+            name.truncate(at);
+            name.truncate(name.trim_end().len());
+            Some(args)
+        }
+        _ => None,
+    };
+    let args = match (scope_args, name_args) {
+        (Some(scope), Some(name)) => Some(format!("{} {}", scope, name)),
+        (scope, name) => scope.or(name),
+    };
+    let scope: Vec<String> = segments
+        .iter()
+        .map(|s| s.split('<').next().unwrap_or("").trim().to_string())
+        .filter(|s| !s.is_empty())
+        .collect();
+
+    Qualified {
+        scope: (!scope.is_empty()).then(|| scope.join("::")),
+        name,
+        args,
+    }
+}
+
+/// 带函数体: `body` 字段 (C/C++ 函数定义) 或复合语句子节点 (ObjC 方法定义)，
+/// C++ 模板看其中的定义
+fn has_body(node: &Node) -> bool {
+    node.child_by_field_name("body").is_some()
+        || node.named_children(&mut node.walk()).any(|child| {
+            child.kind() == "compound_statement"
+                || (node.kind() == "template_declaration" && has_body(&child))
+        })
+}
+
+/// 外层为 `template<>` 的 C++ 显式特化
+fn is_explicit_specialization(node: &Node) -> bool {
+    node.parent()
+        .filter(|parent| parent.kind() == "template_declaration")
+        .and_then(|parent| parent.child_by_field_name("parameters"))
+        .is_some_and(|params| params.named_child_count() == 0)
+}
+
+/// 连续空白 (含换行) 压缩为单个空格
+fn collapse_whitespace(text: &str) -> String {
+    text.split_whitespace().collect::<Vec<_>>().join(" ")
+}
+
+/// C 风格参数声明 (`@param_declaration`): 参数名取声明符链中的标识符 (抽象声明符为空)，
+/// 类型为去掉参数名与默认值后的声明源码 (`const char *`、`int (*)(int)`)
+fn declared_parameter(node: &Node, code: &str) -> Parameter {
+    let declarator = node.child_by_field_name("declarator");
+    let start = node.start_byte();
+    let end = match node.child_by_field_name("default_value") {
+        Some(_) => declarator
+            .or_else(|| node.child_by_field_name("type"))
+            .map_or(node.end_byte(), |n| n.end_byte()),
+        None => node.end_byte(),
+    };
+    let name = declarator.and_then(declarator_name);
+    let type_text = match name {
+        Some(name) => format!(
+            "{} {}",
+            &code[start..name.start_byte()],
+            &code[name.end_byte()..end]
+        ),
+        None => code[start..end].to_string(),
+    };
+    Parameter {
+        name: name
+            .and_then(|n| n.utf8_text(code.as_bytes()).ok())
+            .unwrap_or("")
+            .to_string(),
+        type_name: Some(tidy_declaration(&type_text)).filter(|t| !t.is_empty()),
+        ..Parameter::default()
+    }
+}
+
+/// 声明符链 (`*name`、`(*name)(int)`、`name[]`、`&name`) 中的标识符
+fn declarator_name(node: Node) -> Option<Node> {
+    let mut current = node;
+    loop {
+        if current.kind() == "identifier" {
+            return Some(current);
+        }
+        current = match current.child_by_field_name("declarator") {
+            Some(next) => next,
+            None => {
+                let mut cursor = current.walk();
+                let next = current.named_children(&mut cursor).find(|child| {
+                    child.kind() == "identifier" || child.kind().ends_with("declarator")
                 });
+                next?
+            }
+        };
+    }
+}
+
+/// 去掉参数名后的声明: 合并空白，括号与 `*` / `&` / `[` 前后不留空格 (`int (* )(int)` → `int (*)(int)`)
+fn tidy_declaration(text: &str) -> String {
+    let text = collapse_whitespace(text);
+    let mut result = String::with_capacity(text.len());
+    let chars: Vec<char> = text.chars().collect();
+    for (i, &c) in chars.iter().enumerate() {
+        if c == ' ' {
+            let prev = chars[i - 1];
+            let next = chars[i + 1];
+            if matches!(prev, '(' | '*' | '&' | '[') || matches!(next, ')' | '[' | ',') {
+                continue;
             }
         }
+        result.push(c);
+    }
+    result
+}
 
-        if !name_text.is_empty() {
-            if let Some(r) = range {
-                let key = (name_text.clone(), r.start.line);
-                if seen.insert(key) {
-                    flat_symbols.push(FlatSymbol {
-                        name: name_text,
-                        kind,
-                        start_byte,
-                        end_byte,
-                        range: r,
-                    });
+/// 去掉注解语法符号: `@Test` → `Test`，`#[derive(Debug)]` → `derive(Debug)`，`[HttpGet]` → `HttpGet`
+fn clean_attribute(text: &str) -> String {
+    let text = text.trim();
+    let inner = text
+        .strip_prefix("#![")
+        .or_else(|| text.strip_prefix("#["))
+        .or_else(|| text.strip_prefix('['))
+        .and_then(|t| t.strip_suffix(']'))
+        .unwrap_or(text);
+    collapse_whitespace(inner.trim_start_matches('@'))
+}
+
+/// 重复匹配只补充缺失的元数据，类型与范围以靠后的模式为准
+fn merge_metadata(target: &mut Symbol, other: Symbol) {
+    target.detail = target.detail.take().or(other.detail);
+    target.doc = target.doc.take().or(other.doc);
+    target.visibility = target.visibility.take().or(other.visibility);
+    merge_list(&mut target.extends, other.extends);
+    merge_list(&mut target.implements, other.implements);
+    merge_list(&mut target.attributes, other.attributes);
+    target.owner = target.owner.take().or(other.owner);
+    target.signature = match (target.signature.take(), other.signature) {
+        (Some(mut signature), Some(other)) => {
+            signature.merge(other);
+            Some(signature)
+        }
+        (signature, other) => signature.or(other),
+    };
+}
+
+/// 连续的同名同类型重载签名归并: 其后紧跟实现时归入实现，否则归入首个签名
+fn group_overloads(flat_symbols: Vec<FlatSymbol>) -> Vec<FlatSymbol> {
+    let mut result = Vec::with_capacity(flat_symbols.len());
+    let mut pending: Vec<FlatSymbol> = Vec::new();
+
+    for mut flat in flat_symbols {
+        let continues = pending.last().is_some_and(|last| {
+            last.symbol.name == flat.symbol.name && last.symbol.kind == flat.symbol.kind
+        });
+        if !continues {
+            flush_overloads(&mut pending, &mut result);
+        }
+
+        if flat.overload {
+            pending.push(flat);
+        } else {
+            if continues {
+                for signature in pending.drain(..) {
+                    flat.symbol.overloads.push(signature.symbol.range.clone());
+                    merge_metadata(&mut flat.symbol, signature.symbol);
                 }
             }
+            result.push(flat);
         }
     }
+    flush_overloads(&mut pending, &mut result);
 
-    // 第二步: 按 start_byte 排序
-    flat_symbols.sort_by_key(|s| s.start_byte);
+    result
+}
+
+/// 没有实现的签名组 (如 .d.ts): 首个签名作为符号，其余记为重载
+fn flush_overloads(pending: &mut Vec<FlatSymbol>, result: &mut Vec<FlatSymbol>) {
+    let mut signatures = pending.drain(..);
+    if let Some(mut first) = signatures.next() {
+        first.symbol.overloads = signatures.map(|s| s.symbol.range).collect();
+        result.push(first);
+    }
+}
+
+/// 泛型参数按名称合并约束，按起始位置排序 (where 子句中的约束归入已声明的参数)
+fn merge_type_params(target: &mut Vec<(usize, TypeParam)>, other: Vec<(usize, TypeParam)>) {
+    merge_positioned(
+        target,
+        other,
+        |(_, a), (_, b)| a.name == b.name,
+        |existing, param| merge_list(&mut existing.bounds, param.bounds),
+    );
+}
+
+/// 函数参数按名称 (无名参数按位置) 合并 (多个模式匹配同一参数时补充类型、默认值)，按起始位置排序
+fn merge_params(target: &mut Vec<(usize, Parameter)>, other: Vec<(usize, Parameter)>) {
+    merge_positioned(
+        target,
+        other,
+        |(start_a, a), (start_b, b)| a.name == b.name && (!a.name.is_empty() || start_a == start_b),
+        |existing, param| {
+            existing.label = existing.label.take().or(param.label);
+            existing.type_name = existing.type_name.take().or(param.type_name);
+            existing.default = existing.default.take().or(param.default);
+            existing.variadic |= param.variadic;
+        },
+    );
+}
+
+/// 带起始位置的条目: 相同的条目合并并取较早的位置，其余追加，最后按位置排序
+fn merge_positioned<T>(
+    target: &mut Vec<(usize, T)>,
+    other: Vec<(usize, T)>,
+    same: impl Fn((usize, &T), (usize, &T)) -> bool,
+    merge: impl Fn(&mut T, T),
+) {
+    for (start, item) in other {
+        match target
+            .iter_mut()
+            .find(|(existing_start, existing)| same((*existing_start, existing), (start, &item)))
+        {
+            Some((existing_start, existing)) => {
+                *existing_start = (*existing_start).min(start);
+                merge(existing, item);
+            }
+            None => target.push((start, item)),
+        }
+    }
+    target.sort_by_key(|(start, _)| *start);
+}
+
+/// 列表型元数据取并集，保持先后顺序
+fn merge_list(target: &mut Vec<String>, other: Vec<String>) {
+    for item in other {
+        if !target.contains(&item) {
+            target.push(item);
+        }
+    }
+}
+
+/// 去掉注释符号，多个注释节点按行拼接
+fn clean_doc(parts: &[&str]) -> Option<String> {
+    const MARKERS: &[&str] = &[
+        "///", "//!", "//", "/**", "/*", "*/", "*", "#", "\"\"\"", "'''",
+    ];
+
+    let lines: Vec<&str> = parts
+        .iter()
+        .flat_map(|part| part.lines())
+        .map(|line| {
+            let mut line = line.trim();
+            for marker in MARKERS {
+                line = line.strip_prefix(marker).unwrap_or(line).trim();
+                line = line.strip_suffix(marker).unwrap_or(line).trim();
+            }
+            line
+        })
+        .filter(|line| !line.is_empty())
+        .collect();
 
-    // 第三步: 构建嵌套树
-    Ok(build_nested_tree(flat_symbols))
+    if lines.is_empty() {
+        None
+    } else {
+        Some(lines.join("\n"))
+    }
 }
 
 /// 基于范围包含关系构建嵌套树
@@ -129,13 +775,11 @@ fn build_nested_tree(flat_symbols: Vec<FlatSymbol>) -> Vec<Symbol> {
     let mut stack: Vec<(Symbol, usize)> = Vec::new(); // (symbol, end_byte)
 
     for flat in flat_symbols {
-        let symbol = Symbol {
-            name: flat.name,
-            kind: flat.kind,
-            detail: None,
-            range: flat.range,
-            children: Vec::new(),
-        };
+        let mut symbol = flat.symbol;
+        symbol.type_params = flat.type_params.into_iter().map(|(_, p)| p).collect();
+        if let Some(signature) = &mut symbol.signature {
+            signature.parameters = flat.params.into_iter().map(|(_, p)| p).collect();
+        }
 
         // 弹出所有已结束的父符号
         while let Some((_, parent_end)) = stack.last() {
@@ -176,74 +820,475 @@ fn build_nested_tree(flat_symbols: Vec<FlatSymbol>) -> Vec<Symbol> {
     result
 }
 
-fn parse_kind(capture_name: &str) -> SymbolKind {
-    if capture_name.contains("enum_member") {
-        SymbolKind::EnumMember
-    } else if capture_name.contains("enum") {
-        SymbolKind::Enum
-    } else if capture_name.contains("class") {
-        SymbolKind::Class
-    } else if capture_name.contains("method") {
-        SymbolKind::Method
-    } else if capture_name.contains("function") {
-        SymbolKind::Function
-    } else if capture_name.contains("interface") {
-        SymbolKind::Interface
-    } else if capture_name.contains("field") {
-        SymbolKind::Field
-    } else if capture_name.contains("property") {
-        SymbolKind::Property
-    } else if capture_name.contains("variable") {
-        SymbolKind::Variable
-    } else if capture_name.contains("constant") {
-        SymbolKind::Constant
-    } else if capture_name.contains("module") {
-        SymbolKind::Module
-    } else if capture_name.contains("namespace") {
-        SymbolKind::Namespace
-    } else if capture_name.contains("type") {
-        SymbolKind::Type
-    } else {
-        SymbolKind::Function
-    }
-}
-
-fn get_language(lang: &Language) -> tree_sitter::Language {
-    match lang {
-        Language::Python => tree_sitter_python::LANGUAGE.into(),
-        Language::TypeScript => tree_sitter_typescript::LANGUAGE_TYPESCRIPT.into(),
-        Language::Tsx => tree_sitter_typescript::LANGUAGE_TSX.into(),
-        Language::JavaScript => tree_sitter_javascript::LANGUAGE.into(),
-        Language::Jsx => tree_sitter_javascript::LANGUAGE.into(),
-        Language::Go => tree_sitter_go::LANGUAGE.into(),
-        Language::Rust => tree_sitter_rust::LANGUAGE.into(),
-        Language::Java => tree_sitter_java::LANGUAGE.into(),
-        Language::C => tree_sitter_c::LANGUAGE.into(),
-        Language::Cpp => tree_sitter_cpp::LANGUAGE.into(),
-        Language::Ruby => tree_sitter_ruby::LANGUAGE.into(),
-        Language::Markdown => tree_sitter_md::LANGUAGE.into(),
-        Language::Swift => tree_sitter_swift::LANGUAGE.into(),
-        Language::ObjC => tree_sitter_objc::LANGUAGE.into(),
-        Language::Kotlin => tree_sitter_kotlin_ng::LANGUAGE.into(),
-        Language::CSharp => tree_sitter_c_sharp::LANGUAGE.into(),
-    }
-}
-
-fn get_query_source(lang: &Language) -> &'static str {
-    match lang {
-        Language::Python => include_str!("queries/python.scm"),
-        Language::TypeScript | Language::Tsx => include_str!("queries/typescript.scm"),
-        Language::JavaScript | Language::Jsx => include_str!("queries/javascript.scm"),
-        Language::Go => include_str!("queries/go.scm"),
-        Language::Rust => include_str!("queries/rust.scm"),
-        Language::Java => include_str!("queries/java.scm"),
-        Language::C => include_str!("queries/c.scm"),
-        Language::Cpp => include_str!("queries/cpp.scm"),
-        Language::Ruby => include_str!("queries/ruby.scm"),
-        Language::Markdown => include_str!("queries/markdown.scm"),
-        Language::Swift => include_str!("queries/swift.scm"),
-        Language::ObjC => include_str!("queries/objc.scm"),
-        Language::Kotlin => include_str!("queries/kotlin.scm"),
-        Language::CSharp => include_str!("queries/csharp.scm"),
+// ============================================================
+// 单元测试
+// ============================================================
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::symbol::SymbolKind;
+    use std::fs;
+    use tempfile::TempDir;
+
+    fn extract_symbols(code: &str, lang: &Language, queries: &Queries) -> Result<Vec<Symbol>> {
+        let tree = parse(code, lang)?;
+        extract_tree(code, &tree, lang, queries).map(|(symbols, _)| symbols)
+    }
+
+    #[test]
+    fn test_clean_doc() {
+        assert_eq!(
+            clean_doc(&["/// 创建服务\n", "/// 第二行"]),
+            Some("创建服务\n第二行".to_string())
+        );
+        assert_eq!(
+            clean_doc(&["/**\n * Javadoc\n */"]),
+            Some("Javadoc".to_string())
+        );
+        assert_eq!(clean_doc(&["#"]), None);
+        assert_eq!(clean_doc(&[]), None);
+    }
+
+    #[test]
+    fn test_clean_attribute() {
+        assert_eq!(clean_attribute("@Test"), "Test");
+        assert_eq!(
+            clean_attribute("#[derive(Debug,\n  Clone)]"),
+            "derive(Debug, Clone)"
+        );
+        assert_eq!(clean_attribute("#![allow(dead_code)]"), "allow(dead_code)");
+        assert_eq!(clean_attribute("HttpGet(\"{id}\")"), "HttpGet(\"{id}\")");
+        assert_eq!(clean_attribute("@app.route(\"/\")"), "app.route(\"/\")");
+    }
+
+    #[test]
+    fn test_split_qualified() {
+        let split = |text| {
+            let q = split_qualified(text);
+            (q.scope, q.name, q.args)
+        };
+        assert_eq!(
+            split("Foo::bar"),
+            (Some("Foo".to_string()), "bar".to_string(), None)
+        );
+        assert_eq!(
+            split("app::Foo::operator=="),
+            (Some("app::Foo".to_string()), "operator==".to_string(), None)
+        );
+        assert_eq!(
+            split("Repo<std::map<int, int>>::add"),
+            (
+                Some("Repo".to_string()),
+                "add".to_string(),
+                Some("<std::map<int, int>>".to_string())
+            )
+        );
+        assert_eq!(
+            split("app::Repo<int>::add"),
+            (
+                Some("app::Repo".to_string()),
+                "add".to_string(),
+                Some("<int>".to_string())
+            )
+        );
+        assert_eq!(
+            split("Foo::~Foo"),
+            (Some("Foo".to_string()), "~Foo".to_string(), None)
+        );
+        assert_eq!(split("main"), (None, "main".to_string(), None));
+        // 名称上的模板实参
+        assert_eq!(
+            split("Foo::as<int>"),
+            (
+                Some("Foo".to_string()),
+                "as".to_string(),
+                Some("<int>".to_string())
+            )
+        );
+        assert_eq!(
+            split("add<std::pair<int, int>>"),
+            (
+                None,
+                "add".to_string(),
+                Some("<std::pair<int, int>>".to_string())
+            )
+        );
+        assert_eq!(
+            split("Foo::operator<"),
+            (Some("Foo".to_string()), "operator<".to_string(), None)
+        );
+        assert_eq!(
+            split("Foo::operator->"),
+            (Some("Foo".to_string()), "operator->".to_string(), None)
+        );
+    }
+
+    #[test]
+    fn test_visibility_captured() {
+        let code = "pub fn open() {}\nfn close() {}\n";
+        let symbols = extract_symbols(code, &Language::Rust, &Queries::builtin()).unwrap();
+
+        assert_eq!(symbols[0].visibility.as_deref(), Some("pub"));
+        assert_eq!(symbols[1].visibility, None);
+    }
+
+    #[test]
+    fn test_type_params_merged_in_source_order() {
+        let code = "fn pair<A, B: Clone>(a: A, b: B) where B: Send, A: Copy {}\n";
+        let symbols = extract_symbols(code, &Language::Rust, &Queries::builtin()).unwrap();
+
+        let params = &symbols[0].type_params;
+        assert_eq!(params.len(), 2);
+        assert_eq!(params[0].name, "A");
+        assert_eq!(params[0].bounds, vec!["Copy"]);
+        assert_eq!(params[1].name, "B");
+        assert_eq!(params[1].bounds, vec!["Clone", "Send"]);
+    }
+
+    #[test]
+    fn test_type_params_attached_to_enclosing_symbol() {
+        // 箭头函数的泛型参数按参数列表归入变量声明
+        let code = "const id = <T extends object>(x: T): T => x;\n";
+        let symbols = extract_symbols(code, &Language::TypeScript, &Queries::builtin()).unwrap();
+        assert_eq!(symbols[0].type_params[0].name, "T");
+        assert_eq!(symbols[0].type_params[0].bounds, vec!["object"]);
+
+        // 模板参数归入模板中的成员函数
+        let code = "class A {\n    template <typename U> U get();\n};\n";
+        let symbols = extract_symbols(code, &Language::Cpp, &Queries::builtin()).unwrap();
+        assert!(symbols[0].type_params.is_empty());
+        assert_eq!(symbols[0].children[0].type_params[0].name, "U");
+    }
+
+    #[test]
+    fn test_go_grouped_type_params_share_constraint() {
+        let code = "package p\n\nfunc Zip[K, V any](k K, v V) {}\n";
+        let symbols = extract_symbols(code, &Language::Go, &Queries::builtin()).unwrap();
+
+        let params = &symbols[0].type_params;
+        assert_eq!(params.len(), 2);
+        assert!(params.iter().all(|p| p.bounds == vec!["any"]));
+    }
+
+    #[test]
+    fn test_signature_extracted() {
+        let code = "class A:\n    @staticmethod\n    async def f(a, b: int = 1, *args, **kw) -> str:\n        pass\n";
+        let symbols = extract_symbols(code, &Language::Python, &Queries::builtin()).unwrap();
+
+        let signature = symbols[0].children[0].signature.as_ref().unwrap();
+        let names: Vec<_> = signature
+            .parameters
+            .iter()
+            .map(|p| p.name.as_str())
+            .collect();
+        assert_eq!(names, vec!["a", "b", "*args", "**kw"]);
+        assert_eq!(signature.parameters[1].type_name.as_deref(), Some("int"));
+        assert_eq!(signature.parameters[1].default.as_deref(), Some("1"));
+        assert!(signature.parameters[2].variadic);
+        assert_eq!(signature.returns.as_deref(), Some("str"));
+        assert!(signature.is_async && signature.is_static);
+    }
+
+    #[test]
+    fn test_go_grouped_params_share_type() {
+        let code = "package p\n\nfunc Div(a, b int) (int, error) {}\n";
+        let symbols = extract_symbols(code, &Language::Go, &Queries::builtin()).unwrap();
+
+        let signature = symbols[0].signature.as_ref().unwrap();
+        assert_eq!(signature.parameters.len(), 2);
+        assert!(signature
+            .parameters
+            .iter()
+            .all(|p| p.type_name.as_deref() == Some("int")));
+        assert_eq!(signature.returns.as_deref(), Some("(int, error)"));
+    }
+
+    #[test]
+    fn test_go_unnamed_params_kept() {
+        let code = "package p\n\ntype S struct{}\n\nfunc (s *S) Set(int, ...string) {}\n";
+        let symbols = extract_symbols(code, &Language::Go, &Queries::builtin()).unwrap();
+
+        let signature = symbols[1].signature.as_ref().unwrap();
+        let params: Vec<_> = signature
+            .parameters
+            .iter()
+            .map(|p| (p.name.as_str(), p.type_name.as_deref(), p.variadic))
+            .collect();
+        // 接收者不是参数
+        assert_eq!(
+            params,
+            vec![("", Some("int"), false), ("", Some("string"), true)]
+        );
+    }
+
+    #[test]
+    fn test_c_declarator_types() {
+        let code = "static const char *find(const char *key, int (*cmp)(int), char *argv[], ...);\nint count(void);\n";
+        let symbols = extract_symbols(code, &Language::C, &Queries::builtin()).unwrap();
+
+        let signature = symbols[0].signature.as_ref().unwrap();
+        let params: Vec<_> = signature
+            .parameters
+            .iter()
+            .map(|p| (p.name.as_str(), p.type_name.as_deref()))
+            .collect();
+        assert_eq!(
+            params,
+            vec![
+                ("key", Some("const char *")),
+                ("cmp", Some("int (*)(int)")),
+                ("argv", Some("char *[]")),
+                ("...", None),
+            ]
+        );
+        assert_eq!(signature.returns.as_deref(), Some("const char *"));
+        assert!(signature.is_static);
+        // `(void)` 没有参数
+        assert!(symbols[1].signature.as_ref().unwrap().parameters.is_empty());
+    }
+
+    #[test]
+    fn test_cpp_declarator_types() {
+        let code = "class A {\n    const std::string& name(const A&, int n = 3);\n    constexpr int twice(int x) { return x * 2; }\n    auto size() -> std::size_t;\n};\n";
+        let symbols = extract_symbols(code, &Language::Cpp, &Queries::builtin()).unwrap();
+        let members = &symbols[0].children;
+
+        let name = members[0].signature.as_ref().unwrap();
+        assert_eq!(name.returns.as_deref(), Some("const std::string&"));
+        let params: Vec<_> = name
+            .parameters
+            .iter()
+            .map(|p| {
+                (
+                    p.name.as_str(),
+                    p.type_name.as_deref(),
+                    p.default.as_deref(),
+                )
+            })
+            .collect();
+        assert_eq!(
+            params,
+            vec![("", Some("const A&"), None), ("n", Some("int"), Some("3"))]
+        );
+
+        let twice = members[1].signature.as_ref().unwrap();
+        assert_eq!(twice.returns.as_deref(), Some("int"));
+        assert!(twice.is_const);
+
+        let size = members[2].signature.as_ref().unwrap();
+        assert_eq!(size.returns.as_deref(), Some("std::size_t"));
+    }
+
+    #[test]
+    fn test_cpp_specialization_names() {
+        let code = "struct Foo {\n    template <typename T> T as() const;\n    template <> int as<int>() const { return 1; }\n};\ntemplate <> float Foo::as<float>() const { return 1; }\ntemplate <> int add<int>(int a, int b) { return a + b; }\n";
+        let symbols = extract_symbols(code, &Language::Cpp, &Queries::builtin()).unwrap();
+        let summary = |sym: &Symbol| (sym.name.clone(), sym.kind, sym.detail.clone());
+
+        // 名称不含模板实参，特化的实参记入 detail；成员为 method
+        let members: Vec<_> = symbols[0].children.iter().map(summary).collect();
+        assert_eq!(
+            members,
+            vec![
+                ("as".to_string(), SymbolKind::Method, None),
+                (
+                    "as".to_string(),
+                    SymbolKind::Method,
+                    Some("<int>".to_string())
+                ),
+            ]
+        );
+        assert_eq!(
+            summary(&symbols[1]),
+            (
+                "as".to_string(),
+                SymbolKind::Method,
+                Some("<float>".to_string())
+            )
+        );
+        assert_eq!(symbols[1].owner.as_deref(), Some("Foo"));
+        assert_eq!(
+            summary(&symbols[2]),
+            (
+                "add".to_string(),
+                SymbolKind::Function,
+                Some("<int>".to_string())
+            )
+        );
+    }
+
+    #[test]
+    fn test_python_all_marks_exports() {
+        let code = "__all__ = ['open']\n__all__ += (\"Reader\",)\ndef open():\n    def helper():\n        pass\nclass Reader:\n    def open(self):\n        pass\ndef close():\n    pass\n";
+        let symbols = extract_symbols(code, &Language::Python, &Queries::builtin()).unwrap();
+
+        let exported: Vec<_> = symbols
+            .iter()
+            .filter(|s| s.visibility.as_deref() == Some("export"))
+            .map(|s| s.name.as_str())
+            .collect();
+        assert_eq!(exported, vec!["open", "Reader"]);
+        // 只标记顶层符号
+        assert_eq!(symbols[2].children[0].visibility, None);
+        assert_eq!(symbols[1].children[0].name, "helper");
+    }
+
+    #[test]
+    fn test_overloads_grouped() {
+        let code = "function f(a: string): void;\nfunction f(a: number): void;\nfunction f(a: any) {}\nfunction g(): void;\nfunction h() {}\n";
+        let symbols = extract_symbols(code, &Language::TypeScript, &Queries::builtin()).unwrap();
+
+        let names: Vec<_> = symbols.iter().map(|s| s.name.as_str()).collect();
+        assert_eq!(names, vec!["f", "g", "h"]);
+        // 实现作为符号本身，签名记为重载
+        assert_eq!(symbols[0].range.start.line, 3);
+        let lines: Vec<_> = symbols[0].overloads.iter().map(|r| r.start.line).collect();
+        assert_eq!(lines, vec![1, 2]);
+        assert!(symbols[1].overloads.is_empty());
+    }
+
+    #[test]
+    fn test_user_metadata_captures() {
+        let tmp = TempDir::new().unwrap();
+        fs::write(
+            tmp.path().join("python.scm"),
+            r#"
+            ((comment)+ @doc
+              .
+              (function_definition
+                name: (identifier) @name
+                parameters: (parameters) @detail) @definition.function)
+            "#,
+        )
+        .unwrap();
+        let queries = Queries::load(tmp.path()).unwrap();
+
+        let code = "# Say hello\n# to someone\ndef greet(name):\n    pass\n";
+        let symbols = extract_symbols(code, &Language::Python, &queries).unwrap();
+
+        assert_eq!(symbols.len(), 1);
+        assert_eq!(symbols[0].detail.as_deref(), Some("(name)"));
+        assert_eq!(symbols[0].doc.as_deref(), Some("Say hello\nto someone"));
+    }
+
+    #[test]
+    fn test_later_pattern_wins() {
+        let tmp = TempDir::new().unwrap();
+        fs::write(
+            tmp.path().join("python.scm"),
+            r#"
+            (function_definition
+              name: (identifier) @name
+              parameters: (parameters) @detail) @definition.function
+            (class_definition
+              body: (block
+                (function_definition
+                  name: (identifier) @name) @definition.method))
+            "#,
+        )
+        .unwrap();
+        let queries = Queries::load(tmp.path()).unwrap();
+
+        let code = "class A:\n    def run(self):\n        pass\n";
+        let symbols = extract_symbols(code, &Language::Python, &queries).unwrap();
+
+        let run = &symbols[0];
+        assert_eq!(run.kind, SymbolKind::Method);
+        // 元数据由其他匹配补充
+        assert_eq!(run.detail.as_deref(), Some("(self)"));
+    }
+
+    #[test]
+    fn test_c_typedef_kind_from_specific_pattern() {
+        let code = "typedef struct {\n    int x;\n} Point;\ntypedef enum { RED } Color;\ntypedef int Id;\n";
+        let symbols = extract_symbols(code, &Language::C, &Queries::builtin()).unwrap();
+
+        let kinds: Vec<_> = symbols
+            .iter()
+            .filter(|s| s.kind != SymbolKind::Field && s.kind != SymbolKind::EnumMember)
+            .map(|s| (s.name.as_str(), s.kind))
+            .collect();
+        assert_eq!(
+            kinds,
+            vec![
+                ("Point", SymbolKind::Struct),
+                ("Color", SymbolKind::Enum),
+                ("Id", SymbolKind::Type),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_multiple_name_captures_span_source() {
+        let code = "class V {\n    public static V operator  +(V a, V b) => a;\n    public int this[int i] => i;\n}\n";
+        let symbols = extract_symbols(code, &Language::CSharp, &Queries::builtin()).unwrap();
+
+        let names: Vec<_> = symbols[0]
+            .children
+            .iter()
+            .map(|s| s.name.as_str())
+            .collect();
+        assert_eq!(names, vec!["operator +", "this[int i]"]);
+        assert_eq!(symbols[0].children[0].detail.as_deref(), Some("static"));
+        assert_eq!(symbols[0].children[0].visibility.as_deref(), Some("public"));
+    }
+
+    #[test]
+    fn test_entry_points_from_queries() {
+        let code = "func main() {\n\thttp.HandleFunc(\"/users\", users)\n\tr.GET(\"/items\", h.List)\n\tcmd := &cobra.Command{Use: \"serve [flags]\"}\n}\n";
+        let tree = parse(code, &Language::Go).unwrap();
+        let (_, entries) = extract_tree(code, &tree, &Language::Go, &Queries::builtin()).unwrap();
+
+        let found: Vec<_> = entries
+            .iter()
+            .map(|e| (e.kind, e.name.as_str(), e.handler.as_deref(), e.line))
+            .collect();
+        assert_eq!(
+            found,
+            vec![
+                (EntryKind::Route, "/users", Some("users"), 2),
+                (EntryKind::Route, "GET /items", Some("h.List"), 3),
+                (EntryKind::Command, "serve", None, 4),
+            ]
+        );
+
+        // 同一路由的两个模式合并，保留 handler
+        let code =
+            "app.get('/users/:id', auth, getUser);\nrouter.post('/items', (req, res) => {});\n";
+        let tree = parse(code, &Language::JavaScript).unwrap();
+        let (_, entries) =
+            extract_tree(code, &tree, &Language::JavaScript, &Queries::builtin()).unwrap();
+        assert_eq!(entries.len(), 2);
+        assert_eq!(entries[0].name, "GET /users/:id");
+        assert_eq!(entries[0].handler.as_deref(), Some("getUser"));
+        assert_eq!(entries[1].name, "POST /items");
+        assert_eq!(entries[1].handler, None);
+    }
+
+    #[test]
+    fn test_parse_diagnostics() {
+        let tree = parse("def ok():\n    pass\n", &Language::Python).unwrap();
+        assert_eq!(collect_diagnostics(tree.root_node(), ""), (0, Vec::new()));
+
+        let code = "int main() { return 0 }\n";
+        let map = extract(code, "main.c", &Language::C, &Queries::builtin()).unwrap();
+        assert_eq!(map.parse_errors, 1);
+        assert_eq!(
+            map.diagnostics,
+            vec![Diagnostic {
+                kind: DiagnosticKind::Missing,
+                line: 1,
+                column: 21,
+                text: ";".to_string(),
+            }]
+        );
+        // 出错的文件仍然提取符号
+        assert_eq!(map.symbols[0].name, "main");
+
+        let code = "def broken(:\n    pass\n";
+        let map = extract(code, "a.py", &Language::Python, &Queries::builtin()).unwrap();
+        assert!(map.parse_errors > 0);
+        assert_eq!(map.diagnostics[0].line, 1);
+        assert_eq!(map.diagnostics[0].message(), "line 1:11 missing `)`");
     }
 }
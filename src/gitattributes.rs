@@ -0,0 +1,231 @@
+// ============================================================
+// GitAttributes: .gitattributes 中的 linguist 属性
+// ============================================================
+
+use anyhow::{Context, Result};
+use globset::{Glob, GlobMatcher};
+use std::path::Path;
+
+/// 一条规则: 模式与 linguist-generated / linguist-vendored / linguist-language 的取值
+struct Rule {
+    matcher: GlobMatcher,
+    pattern: String,
+    generated: Option<bool>,
+    vendored: Option<bool>,
+    language: Option<String>,
+}
+
+/// 输入目录及其上级 (到仓库根) 的 .gitattributes 规则，路径均相对输入目录
+#[derive(Default)]
+pub struct Attributes {
+    rules: Vec<Rule>,
+}
+
+impl Attributes {
+    /// 加载 root (输入目录；单文件输入时为其所在目录) 及其上级的 .gitattributes
+    pub fn load(root: &Path) -> Result<Self> {
+        let mut root = root.canonicalize().unwrap_or_else(|_| root.to_path_buf());
+        if root.is_file() {
+            root.pop();
+        }
+        let mut files = Vec::new();
+        for dir in root.ancestors() {
+            let path = dir.join(".gitattributes");
+            if path.is_file() {
+                // 规则按相对该文件所在目录匹配: 记录输入目录相对它的前缀
+                let prefix = root
+                    .strip_prefix(dir)
+                    .unwrap_or(Path::new(""))
+                    .to_path_buf();
+                files.push((path, prefix));
+            }
+            if dir.join(".git").exists() {
+                break;
+            }
+        }
+
+        // 上级目录的规则在前，同一文件内靠后的规则优先
+        let mut rules = Vec::new();
+        for (path, prefix) in files.into_iter().rev() {
+            let text = std::fs::read_to_string(&path)
+                .with_context(|| format!("Failed to read {}", path.display()))?;
+            rules.extend(parse(&text, &prefix));
+        }
+        Ok(Self { rules })
+    }
+
+    /// linguist-generated 的取值与规则模式
+    pub fn generated(&self, rel: &Path) -> Option<(bool, &str)> {
+        self.last_match(rel, |rule| rule.generated)
+    }
+
+    /// linguist-vendored 的取值与规则模式
+    pub fn vendored(&self, rel: &Path) -> Option<(bool, &str)> {
+        self.last_match(rel, |rule| rule.vendored)
+    }
+
+    /// linguist-language 指定的语言名 (原样，如 `C++`、`Objective-C`)
+    pub fn language(&self, rel: &Path) -> Option<&str> {
+        self.last_match(rel, |rule| rule.language.as_deref())
+            .map(|(language, _)| language)
+    }
+
+    /// 最后一条匹配且设置了该属性的规则
+    fn last_match<'a, T>(
+        &'a self,
+        rel: &Path,
+        value: impl Fn(&'a Rule) -> Option<T>,
+    ) -> Option<(T, &'a str)> {
+        self.rules
+            .iter()
+            .rev()
+            .filter(|rule| rule.matcher.is_match(rel))
+            .find_map(|rule| value(rule).map(|v| (v, rule.pattern.as_str())))
+    }
+}
+
+/// 解析 .gitattributes: `pattern attr...`，只关心 linguist 属性
+/// (`attr`、`attr=true` 为真，`-attr`、`attr=false` 为假；`linguist-language=Name`)；
+/// prefix 为输入目录相对该文件的路径
+fn parse(text: &str, prefix: &Path) -> Vec<Rule> {
+    let mut rules = Vec::new();
+    for line in text.lines() {
+        let line = line.trim();
+        if line.is_empty() || line.starts_with('#') {
+            continue;
+        }
+        let mut parts = line.split_whitespace();
+        let Some(pattern) = parts.next() else {
+            continue;
+        };
+        let mut generated = None;
+        let mut vendored = None;
+        let mut language = None;
+        for attr in parts {
+            let (name, value) = match attr.strip_prefix('-') {
+                Some(name) => (name, None),
+                None => match attr.split_once('=') {
+                    Some((name, value)) => (name, Some(value)),
+                    None => (attr, Some("true")),
+                },
+            };
+            match name {
+                "linguist-generated" => generated = Some(value.is_some_and(|v| v != "false")),
+                "linguist-vendored" => vendored = Some(value.is_some_and(|v| v != "false")),
+                "linguist-language" => language = value.map(str::to_string),
+                _ => {}
+            }
+        }
+        if generated.is_none() && vendored.is_none() && language.is_none() {
+            continue;
+        }
+        let Some(matcher) = glob(pattern, prefix) else {
+            continue;
+        };
+        rules.push(Rule {
+            matcher,
+            pattern: pattern.to_string(),
+            generated,
+            vendored,
+            language,
+        });
+    }
+    rules
+}
+
+/// gitattributes 模式转为相对输入目录的 glob:
+/// 不含 `/` 的模式匹配任意层级的文件名，含 `/` 的模式相对 .gitattributes 所在目录
+fn glob(pattern: &str, prefix: &Path) -> Option<GlobMatcher> {
+    let glob = if pattern.trim_end_matches('/').contains('/') {
+        let anchored = pattern.trim_start_matches('/');
+        match prefix.to_str().filter(|p| !p.is_empty()) {
+            // 规则来自上级目录: 去掉输入目录的前缀
+            Some(prefix) => {
+                let prefix = format!("{}/", prefix.replace('\\', "/"));
+                if let Some(rest) = anchored.strip_prefix(&prefix) {
+                    rest.to_string()
+                } else if anchored.starts_with("**") {
+                    anchored.to_string()
+                } else {
+                    return None;
+                }
+            }
+            None => anchored.to_string(),
+        }
+    } else {
+        format!("**/{}", pattern)
+    };
+    Glob::new(&glob).ok().map(|g| g.compile_matcher())
+}
+
+// ============================================================
+// 单元测试
+// ============================================================
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::fs;
+    use tempfile::TempDir;
+
+    #[test]
+    fn test_parse_rules() {
+        let rules = Attributes {
+            rules: parse(
+                "# linguist\n\
+                 *.gen.ts linguist-generated\n\
+                 vendor/** -linguist-vendored\n\
+                 *.h linguist-language=C++\n\
+                 legacy/*.h linguist-language=C text eol=lf\n\
+                 *.pb.go linguist-generated=false\n\
+                 *.txt text\n",
+                Path::new(""),
+            ),
+        };
+        assert_eq!(rules.rules.len(), 5);
+        assert_eq!(
+            rules.generated(Path::new("a/b.gen.ts")),
+            Some((true, "*.gen.ts"))
+        );
+        assert_eq!(
+            rules.generated(Path::new("api.pb.go")),
+            Some((false, "*.pb.go"))
+        );
+        assert_eq!(
+            rules.vendored(Path::new("vendor/x.go")),
+            Some((false, "vendor/**"))
+        );
+        // 靠后的规则优先，未设置该属性的规则不影响
+        assert_eq!(rules.language(Path::new("src/a.h")), Some("C++"));
+        assert_eq!(rules.language(Path::new("legacy/a.h")), Some("C"));
+        assert_eq!(rules.language(Path::new("a.txt")), None);
+    }
+
+    #[test]
+    fn test_load_from_ancestors() {
+        let tmp = TempDir::new().unwrap();
+        fs::create_dir(tmp.path().join(".git")).unwrap();
+        fs::create_dir_all(tmp.path().join("src/schema")).unwrap();
+        fs::write(
+            tmp.path().join(".gitattributes"),
+            "src/schema/** linguist-generated=true\nlibs/** linguist-vendored\n",
+        )
+        .unwrap();
+        fs::write(
+            tmp.path().join("src/.gitattributes"),
+            "*.inc linguist-language=C\n",
+        )
+        .unwrap();
+
+        let attributes = Attributes::load(&tmp.path().join("src")).unwrap();
+        // 从子目录扫描时，带 `/` 的模式去掉子目录前缀
+        assert!(attributes.generated(Path::new("schema/user.rs")).is_some());
+        assert!(attributes.vendored(Path::new("libs/x.js")).is_none());
+        assert_eq!(attributes.language(Path::new("a/b.inc")), Some("C"));
+
+        // 单文件输入按所在目录加载
+        let file = tmp.path().join("src/main.inc");
+        fs::write(&file, "").unwrap();
+        let attributes = Attributes::load(&file).unwrap();
+        assert_eq!(attributes.language(Path::new("main.inc")), Some("C"));
+    }
+}
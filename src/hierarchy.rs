@@ -0,0 +1,252 @@
+// ============================================================
+// Hierarchy: 类型继承关系查询 (祖先与后代)
+// ============================================================
+
+use crate::symbol::{FileMap, Symbol, SymbolKind};
+use anyhow::{bail, Result};
+use serde::Serialize;
+use std::collections::{HashMap, HashSet};
+
+/// 类型的一处定义
+#[derive(Debug, Clone, Serialize)]
+pub struct TypeDef {
+    pub name: String,
+    pub kind: SymbolKind,
+    pub path: String,
+    pub line: usize,
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub extends: Vec<String>,
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub implements: Vec<String>,
+}
+
+impl TypeDef {
+    pub fn supertypes(&self) -> impl Iterator<Item = &String> {
+        self.extends.iter().chain(&self.implements)
+    }
+}
+
+/// 继承树节点，外部类型 (如标准库) 没有 definitions
+#[derive(Debug, Serialize)]
+pub struct Node {
+    pub name: String,
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub definitions: Vec<TypeDef>,
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub children: Vec<Node>,
+}
+
+/// 查询结果: ancestors 的子节点为更上层的父类型，descendants 的子节点为更下层的子类型
+#[derive(Debug, Serialize)]
+pub struct Hierarchy {
+    pub name: String,
+    pub definitions: Vec<TypeDef>,
+    pub ancestors: Vec<Node>,
+    pub descendants: Vec<Node>,
+}
+
+/// 类型图: 以简单名 (去掉泛型参数与命名空间) 为键
+#[derive(Default)]
+struct TypeGraph {
+    definitions: HashMap<String, Vec<TypeDef>>,
+    parents: HashMap<String, Vec<String>>,
+    children: HashMap<String, Vec<String>>,
+}
+
+// ------------------------------------------------------------
+// 公开接口
+// ------------------------------------------------------------
+
+/// 在所有文件中查找类型 name 的祖先与后代
+pub fn build(maps: &[FileMap], name: &str) -> Result<Hierarchy> {
+    let mut graph = TypeGraph::default();
+    for map in maps {
+        collect(&map.symbols, &map.path, &mut graph);
+    }
+
+    let key = simple_name(name);
+    let definitions = graph.definitions.get(&key).cloned().unwrap_or_default();
+    if definitions.is_empty()
+        && !graph.parents.contains_key(&key)
+        && !graph.children.contains_key(&key)
+    {
+        bail!("Type not found: {}", name);
+    }
+
+    let mut path = HashSet::from([key.clone()]);
+    Ok(Hierarchy {
+        name: name.to_string(),
+        definitions,
+        ancestors: walk(&graph, &graph.parents, &key, &mut path),
+        descendants: walk(&graph, &graph.children, &key, &mut path),
+    })
+}
+
+// ------------------------------------------------------------
+// 内部实现
+// ------------------------------------------------------------
+
+/// 递归收集类型定义与继承边 (impl / extension 只贡献继承边)
+fn collect(symbols: &[Symbol], home: &str, graph: &mut TypeGraph) {
+    for sym in symbols {
+        let key = simple_name(&sym.name);
+        for base in sym.supertypes() {
+            let base = simple_name(base);
+            if base.is_empty() || base == key {
+                continue;
+            }
+            push_unique(graph.parents.entry(key.clone()).or_default(), &base);
+            push_unique(graph.children.entry(base).or_default(), &key);
+        }
+
+        if is_type_kind(sym.kind) {
+            graph.definitions.entry(key).or_default().push(TypeDef {
+                name: sym.name.clone(),
+                kind: sym.kind,
+                path: sym.path.clone().unwrap_or_else(|| home.to_string()),
+                line: sym.range.start.line,
+                extends: sym.extends.clone(),
+                implements: sym.implements.clone(),
+            });
+        }
+
+        // 子符号从其他文件归并而来时沿用其路径
+        collect(&sym.children, sym.path.as_deref().unwrap_or(home), graph);
+    }
+}
+
+/// 沿 edges 展开，path 为当前路径上的类型 (防止循环继承)
+fn walk(
+    graph: &TypeGraph,
+    edges: &HashMap<String, Vec<String>>,
+    key: &str,
+    path: &mut HashSet<String>,
+) -> Vec<Node> {
+    let Some(next) = edges.get(key) else {
+        return Vec::new();
+    };
+
+    let mut nodes = Vec::new();
+    for name in next {
+        if !path.insert(name.clone()) {
+            continue;
+        }
+        nodes.push(Node {
+            name: name.clone(),
+            definitions: graph.definitions.get(name).cloned().unwrap_or_default(),
+            children: walk(graph, edges, name, path),
+        });
+        path.remove(name);
+    }
+    nodes
+}
+
+/// `ns::Base<T>` / `pkg.Base` / `Generic[T]` → 末段类型名
+fn simple_name(text: &str) -> String {
+    let base = text.split(['<', '[', '(']).next().unwrap_or("").trim();
+    base.rsplit("::")
+        .next()
+        .and_then(|s| s.rsplit('.').next())
+        .unwrap_or(base)
+        .trim()
+        .to_string()
+}
+
+fn push_unique(list: &mut Vec<String>, item: &str) {
+    if !list.iter().any(|existing| existing == item) {
+        list.push(item.to_string());
+    }
+}
+
+fn is_type_kind(kind: SymbolKind) -> bool {
+    matches!(
+        kind,
+        SymbolKind::Class
+            | SymbolKind::Struct
+            | SymbolKind::Interface
+            | SymbolKind::Trait
+            | SymbolKind::Protocol
+            | SymbolKind::Enum
+            | SymbolKind::Record
+            | SymbolKind::Module
+    )
+}
+
+// ============================================================
+// 单元测试
+// ============================================================
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_simple_name() {
+        assert_eq!(simple_name("Base"), "Base");
+        assert_eq!(simple_name("ns::Base<int>"), "Base");
+        assert_eq!(simple_name("ActiveRecord::Base"), "Base");
+        assert_eq!(simple_name("typing.Generic[T]"), "Generic");
+        assert_eq!(simple_name("mixin(B)"), "mixin");
+    }
+
+    #[test]
+    fn test_ancestors_and_descendants() {
+        let maps = vec![
+            FileMap::new("animal.ts", "typescript").with_symbols(vec![
+                Symbol::at("Named", SymbolKind::Interface, 1),
+                Symbol::at("Animal", SymbolKind::Class, 3).with_supertypes(&[], &["Named"]),
+            ]),
+            FileMap::new("dog.ts", "typescript").with_symbols(vec![
+                Symbol::at("Dog", SymbolKind::Class, 1)
+                    .with_supertypes(&["Animal"], &["Serializable<Dog>"]),
+                Symbol::at("Puppy", SymbolKind::Class, 5).with_supertypes(&["Dog"], &[]),
+            ]),
+        ];
+
+        let h = build(&maps, "Dog").unwrap();
+        assert_eq!(h.definitions[0].path, "dog.ts");
+
+        let ancestors: Vec<_> = h.ancestors.iter().map(|n| n.name.as_str()).collect();
+        assert_eq!(ancestors, vec!["Animal", "Serializable"]);
+        assert_eq!(h.ancestors[0].children[0].name, "Named");
+        assert_eq!(h.ancestors[0].definitions[0].path, "animal.ts");
+        // 外部类型没有定义
+        assert!(h.ancestors[1].definitions.is_empty());
+
+        assert_eq!(h.descendants.len(), 1);
+        assert_eq!(h.descendants[0].name, "Puppy");
+    }
+
+    #[test]
+    fn test_impl_contributes_edges_only() {
+        let user = Symbol::at("User", SymbolKind::Struct, 1).with_children(vec![Symbol::at(
+            "helper",
+            SymbolKind::Function,
+            2,
+        )]);
+        let display = Symbol::at("User", SymbolKind::Impl, 5).with_supertypes(&[], &["Display"]);
+        let maps = vec![FileMap::new("lib.rs", "rust").with_symbols(vec![user, display])];
+
+        let h = build(&maps, "Display").unwrap();
+        assert!(h.definitions.is_empty());
+        assert_eq!(h.descendants[0].name, "User");
+        assert_eq!(h.descendants[0].definitions.len(), 1);
+        assert_eq!(h.descendants[0].definitions[0].kind, SymbolKind::Struct);
+    }
+
+    #[test]
+    fn test_cycle_terminates() {
+        let maps = vec![FileMap::new("a.ts", "typescript").with_symbols(vec![
+            Symbol::at("A", SymbolKind::Interface, 1).with_supertypes(&["B"], &[]),
+            Symbol::at("B", SymbolKind::Interface, 2).with_supertypes(&["A"], &[]),
+        ])];
+
+        let h = build(&maps, "A").unwrap();
+        assert_eq!(h.ancestors[0].name, "B");
+        assert!(h.ancestors[0].children.is_empty());
+    }
+
+    #[test]
+    fn test_unknown_type() {
+        assert!(build(&[FileMap::new("a.ts", "typescript")], "Missing").is_err());
+    }
+}
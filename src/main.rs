@@ -2,16 +2,44 @@
 // agent-codemap: AI 代码索引生成器
 // ============================================================
 
+mod aggregate;
 mod cli;
+mod config;
 mod detector;
+mod encoding;
+mod entrypoint;
 mod extractor;
+mod gitattributes;
+mod hierarchy;
 mod output;
+mod query;
 mod scanner;
+mod skip;
 mod symbol;
+mod testmap;
 
-use anyhow::Result;
-use cli::Cli;
-use symbol::FileMap;
+use anyhow::{Context, Result};
+use cli::OutputFormat;
+use cli::{Cli, Command};
+use config::Config;
+use detector::{Language, PathMatch};
+use encoding::Decoded;
+use gitattributes::Attributes;
+use query::Queries;
+use scanner::ScanOptions;
+use skip::{SkipReason, Skipper};
+use std::io::Read;
+use std::path::{Path, PathBuf};
+use symbol::{FileMap, SymbolKind};
+
+/// 表示 stdin 的输入路径
+const STDIN: &str = "-";
+
+/// 未给出 --path-hint 时 stdin 源码的输出路径
+const STDIN_LABEL: &str = "<stdin>";
+
+/// 跳过的文件: (相对路径, 原因)
+type Skipped = Vec<(String, SkipReason)>;
 
 fn main() -> Result<()> {
     let cli = Cli::parse_args();
@@ -22,27 +50,369 @@ fn main() -> Result<()> {
 
 /// 扫描 → 解析 → 渲染
 fn run(cli: &Cli) -> Result<String> {
-    let files = scanner::scan(&cli.input)?;
+    match &cli.command {
+        Some(Command::Hierarchy { name, input }) => {
+            let (maps, render) = index_inputs(cli, input)?;
+            let hierarchy = hierarchy::build(&maps, name)?;
+            Ok(output::render_hierarchy(&hierarchy, render.format))
+        }
+        None => {
+            let (mut maps, render) = index_inputs(cli, &cli.input)?;
+
+            // 测试过滤: --no-tests 去掉测试文件与测试代码，--tests-only 只保留测试 (连同所在容器)
+            if cli.no_tests {
+                maps.retain(|map| !testmap::is_test_file(&map.path));
+                for map in &mut maps {
+                    map.retain_symbols(&|sym| !testmap::is_test_code(sym));
+                }
+            }
+            if cli.tests_only {
+                for map in &mut maps {
+                    map.keep_matching(&|sym| sym.kind == SymbolKind::Test);
+                    map.entry_points.clear();
+                }
+                maps.retain(|map| !map.symbols.is_empty());
+            }
+
+            // 按注解过滤 (聚合之后，保留匹配符号的容器)
+            if !cli.with_attr.is_empty() {
+                for map in &mut maps {
+                    map.keep_matching(&|sym| {
+                        cli.with_attr.iter().any(|name| sym.has_attribute(name))
+                    });
+                    map.entry_points.clear();
+                }
+                maps.retain(|map| !map.symbols.is_empty());
+            }
+
+            Ok(match render.budget {
+                Some(budget) => output::render_within(&maps, render.format, budget),
+                None => output::render_all(&maps, render.format),
+            })
+        }
+    }
+}
+
+/// 一个输入根的索引结果 (跨文件聚合在合并所有根之后进行)
+struct Indexed {
+    maps: Vec<FileMap>,
+    /// 读取或解析失败的文件数
+    unreadable: usize,
+    skipped: Skipped,
+    render: Render,
+}
+
+/// 输出选项 (命令行优先于配置)
+#[derive(Clone, Copy, Default)]
+struct Render {
+    format: OutputFormat,
+    /// token 预算，超出时截断输出
+    budget: Option<usize>,
+}
+
+/// 索引所有输入并在 stderr 汇总: --files-from 的文件列表、单个输入，或多个根 (各自加载配置，
+/// 输出路径前加根标签)；合并后统一跨文件聚合，输出选项取命令行或第一个根的配置
+fn index_inputs(cli: &Cli, inputs: &[PathBuf]) -> Result<(Vec<FileMap>, Render)> {
+    let roots = if let Some(source) = &cli.files_from {
+        let list = read_file_list(source)?;
+        vec![index(cli, Path::new("."), Some(&list), None)?]
+    } else if let [input] = inputs {
+        vec![index(cli, input, None, None)?]
+    } else {
+        if inputs.iter().any(|input| input == Path::new(STDIN)) {
+            anyhow::bail!("stdin input (`-`) cannot be combined with other inputs");
+        }
+        inputs
+            .iter()
+            .map(|input| index(cli, input, None, Some(&scanner::root_label(input))))
+            .collect::<Result<Vec<_>>>()?
+    };
+
+    let render = roots.first().map(|root| root.render).unwrap_or_default();
+    let mut maps = Vec::new();
+    let mut unreadable = 0;
+    let mut skipped = Vec::new();
+    for root in roots {
+        maps.extend(root.maps);
+        unreadable += root.unreadable;
+        skipped.extend(root.skipped);
+    }
+
+    let with_errors = report(&maps, unreadable, &skipped);
+    if cli.strict && with_errors + unreadable > 0 {
+        anyhow::bail!(
+            "--strict: {} of {} files failed to parse",
+            with_errors + unreadable,
+            maps.len() + unreadable
+        );
+    }
+
+    // 跨文件聚合 (跨根的头文件配对、测试映射等)
+    aggregate::attach_owned_members(&mut maps);
+    aggregate::pair_headers(&mut maps);
+    aggregate::merge_type_parts(&mut maps);
+    testmap::link_tests(&mut maps);
+
+    Ok((maps, render))
+}
+
+/// 读取 --files-from 的文件列表 (`-` 为 stdin)
+fn read_file_list(source: &Path) -> Result<Vec<PathBuf>> {
+    let mut bytes = Vec::new();
+    if source == Path::new(STDIN) {
+        std::io::stdin()
+            .read_to_end(&mut bytes)
+            .context("Failed to read file list from stdin")?;
+    } else {
+        bytes = std::fs::read(source)
+            .with_context(|| format!("Failed to read file list: {}", source.display()))?;
+    }
+    Ok(scanner::parse_file_list(&bytes))
+}
+
+/// 扫描一个输入并提取所有文件的符号: input 为 `-` 时读取 stdin 中的单个源码，
+/// list 为 --files-from 给出的文件 (代替扫描)，label 为多个根时输出路径的前缀
+fn index(
+    cli: &Cli,
+    input: &Path,
+    list: Option<&[PathBuf]>,
+    label: Option<&str>,
+) -> Result<Indexed> {
+    let stdin = input == Path::new(STDIN);
+    if !stdin && (cli.lang.is_some() || cli.path_hint.is_some()) {
+        anyhow::bail!("--lang and --path-hint only apply to stdin input (`-`)");
+    }
+
+    // stdin 的配置从 --path-hint 所在目录 (不存在时为当前目录) 向上查找
+    let config_root = if stdin {
+        cli.path_hint
+            .as_deref()
+            .and_then(Path::parent)
+            .filter(|dir| dir.is_dir())
+            .unwrap_or(Path::new("."))
+    } else {
+        input
+    };
+    let config = if cli.no_config {
+        Config::default()
+    } else {
+        Config::load(config_root)?
+    };
+
+    let render = Render {
+        format: cli.format.or(config.format).unwrap_or_default(),
+        budget: cli.budget.or(config.budget),
+    };
+    let queries = match cli.queries_dir.as_ref().or(config.queries_dir.as_ref()) {
+        Some(dir) => Queries::load(dir)?,
+        None => Queries::builtin(),
+    }
+    .with_dsl(config.dsl.clone());
+
+    let (maps, unreadable, skipped) = if stdin {
+        (vec![index_stdin(cli, &config, &queries)?], 0, Vec::new())
+    } else {
+        index_files(cli, input, list, label, &config, &queries)?
+    };
+
+    Ok(Indexed {
+        maps,
+        unreadable,
+        skipped,
+        render,
+    })
+}
+
+/// 扫描文件或目录 (或 list 中的文件)，返回 FileMap、读取 / 解析失败的文件数与跳过的文件
+fn index_files(
+    cli: &Cli,
+    input: &Path,
+    list: Option<&[PathBuf]>,
+    label: Option<&str>,
+    config: &Config,
+    queries: &Queries,
+) -> Result<(Vec<FileMap>, usize, Skipped)> {
+    // 合并配置: 命令行优先
+    let options = ScanOptions {
+        include: pick(&cli.include, &config.include),
+        exclude: pick(&cli.exclude, &config.exclude),
+    };
+    let files = match list {
+        Some(list) => scanner::scan_list(input, list, &options)?,
+        None => scanner::scan(input, &options)?,
+    };
+
+    let attributes = Attributes::load(input)?;
+
+    // 扫描目录时跳过生成、vendored、压缩与过大的文件 (直接给出的文件不跳过)
+    let skipper = if cli.no_skip || !input.is_dir() {
+        None
+    } else {
+        let max_size = cli
+            .max_file_size
+            .or(config.max_file_size)
+            .unwrap_or(skip::DEFAULT_MAX_FILE_SIZE);
+        Some(Skipper::new(&attributes, max_size)?)
+    };
 
     // 收集所有 FileMap
+    let mut unreadable = 0;
+    let mut skipped: Skipped = Vec::new();
     let maps: Vec<FileMap> = files
         .iter()
         .filter_map(|path| {
-            let lang = detector::detect(path)?;
-            match extractor::extract(path, &lang) {
-                Ok(mut map) => {
-                    // 设置相对路径
-                    let rel = scanner::relative_path(&cli.input, path);
-                    map.path = rel.to_string_lossy().to_string();
-                    Some(map)
+            // 输出使用相对路径
+            let rel_path = scanner::relative_path(input, path);
+            // 先只按路径判断，确定要读取的文件再做跳过检查
+            let by_path = match config.language_for(path) {
+                Some(lang) => PathMatch::Fixed(lang),
+                None => detector::detect_path(path, &rel_path, &attributes)?,
+            };
+            let rel = match label {
+                // 多个根: 目录下的文件加根标签，直接给出的文件用给出的路径
+                Some(label) if input.is_file() => label.to_string(),
+                Some(label) => format!("{}/{}", label, rel_path.to_string_lossy()),
+                None => rel_path.to_string_lossy().to_string(),
+            };
+            if let Some(skipper) = &skipper {
+                let size = path.metadata().map(|m| m.len()).unwrap_or(0);
+                if let Some(reason) = skipper.check_path(&rel_path, size) {
+                    // 语言待内容确定的无扩展名文件跳过时不报告
+                    if by_path != PathMatch::Unknown {
+                        skipped.push((rel, reason));
+                    }
+                    return None;
                 }
+            }
+            let result = std::fs::read(path)
+                .with_context(|| format!("Failed to read file: {}", path.display()))
+                .and_then(|bytes| {
+                    // 非 UTF-8 源码解码后解析，列号再映射回原始字节
+                    let decoded = encoding::decode(&bytes, config.encoding_for(&rel_path));
+                    let Some(lang) = detector::detect_content(path, by_path, &decoded.text) else {
+                        return Ok(None);
+                    };
+                    if skipper.is_some() {
+                        if let Some(reason) = skip::check_content(&decoded.text, lang) {
+                            skipped.push((rel.clone(), reason));
+                            return Ok(None);
+                        }
+                    }
+                    build_map(&decoded, &rel, lang, config, queries).map(Some)
+                });
+            match result {
+                Ok(map) => map,
                 Err(e) => {
                     eprintln!("Warning: failed to parse {}: {}", path.display(), e);
+                    unreadable += 1;
                     None
                 }
             }
         })
         .collect();
 
-    Ok(output::render_all(&maps, cli.format))
+    Ok((maps, unreadable, skipped))
+}
+
+/// 读取 stdin 中的单个源码 (编辑器中未保存的缓冲区等)，不读取文件系统中的源文件:
+/// 语言取 --lang，否则按配置与 --path-hint 的文件名、内容检测；输出路径取 --path-hint
+fn index_stdin(cli: &Cli, config: &Config, queries: &Queries) -> Result<FileMap> {
+    let mut bytes = Vec::new();
+    std::io::stdin()
+        .read_to_end(&mut bytes)
+        .context("Failed to read stdin")?;
+
+    let hint = cli.path_hint.as_deref().unwrap_or(Path::new(STDIN));
+    let decoded = encoding::decode(&bytes, config.encoding_for(hint));
+    let lang = cli
+        .lang
+        .or_else(|| config.language_for(hint))
+        .or_else(|| detector::detect_source(hint, &decoded.text))
+        .context("Cannot detect the language of stdin; pass --lang")?;
+
+    let rel = match &cli.path_hint {
+        Some(hint) => hint.to_string_lossy().to_string(),
+        None => STDIN_LABEL.to_string(),
+    };
+    build_map(&decoded, &rel, lang, config, queries)
+}
+
+/// 从解码后的源码提取一个文件的符号、测试与入口点，列号映射回原始字节
+fn build_map(
+    decoded: &Decoded,
+    rel: &str,
+    lang: Language,
+    config: &Config,
+    queries: &Queries,
+) -> Result<FileMap> {
+    let code = &decoded.text;
+    let mut map = extractor::extract(code, rel, &lang, queries)?;
+    testmap::mark_tests(&mut map, lang, code);
+    entrypoint::discover(&mut map, lang);
+    decoded.restore_positions(&mut map);
+    map.retain_symbols(&|sym| config.kind_enabled(lang, sym.kind));
+    Ok(map)
+}
+
+/// 在 stderr 输出解析汇总、有语法错误的文件与跳过的文件，返回有语法错误的文件数
+fn report(maps: &[FileMap], unreadable: usize, skipped: &[(String, SkipReason)]) -> usize {
+    let broken: Vec<&FileMap> = maps.iter().filter(|map| map.parse_errors > 0).collect();
+    let mut summary = format!(
+        "Parsed {} {}: {} clean",
+        maps.len(),
+        if maps.len() == 1 { "file" } else { "files" },
+        maps.len() - broken.len()
+    );
+    if !broken.is_empty() {
+        summary.push_str(&format!(", {} with syntax errors", broken.len()));
+    }
+    if unreadable > 0 {
+        summary.push_str(&format!(", {} failed", unreadable));
+    }
+    if !skipped.is_empty() {
+        // 按分类计数，顺序同首次出现
+        let mut counts: Vec<(&str, usize)> = Vec::new();
+        for (_, reason) in skipped {
+            match counts.iter_mut().find(|(c, _)| *c == reason.category()) {
+                Some((_, n)) => *n += 1,
+                None => counts.push((reason.category(), 1)),
+            }
+        }
+        let counts: Vec<String> = counts.iter().map(|(c, n)| format!("{} {}", n, c)).collect();
+        summary.push_str(&format!(
+            "; skipped {} ({})",
+            skipped.len(),
+            counts.join(", ")
+        ));
+    }
+    eprintln!("{}", summary);
+    for map in &broken {
+        let noun = if map.parse_errors == 1 {
+            "error"
+        } else {
+            "errors"
+        };
+        let first = map
+            .diagnostics
+            .first()
+            .map(|d| d.message())
+            .unwrap_or_default();
+        eprintln!(
+            "  {}: {} parse {}, first at {}",
+            map.path, map.parse_errors, noun, first
+        );
+    }
+    for (path, reason) in skipped {
+        eprintln!("  skipped {}: {}", path, reason);
+    }
+    broken.len()
+}
+
+/// 命令行给出时覆盖配置文件
+fn pick(cli: &[String], config: &[String]) -> Vec<String> {
+    if cli.is_empty() {
+        config.to_vec()
+    } else {
+        cli.to_vec()
+    }
 }
@@ -2,9 +2,15 @@
 // JSON 输出
 // ============================================================
 
+use crate::hierarchy::Hierarchy;
 use crate::symbol::FileMap;
 
 /// 渲染为 JSON
 pub fn render_all(maps: &[FileMap]) -> String {
     serde_json::to_string_pretty(maps).unwrap_or_else(|_| "[]".to_string())
 }
+
+/// 继承关系查询结果
+pub fn render_hierarchy(hierarchy: &Hierarchy) -> String {
+    serde_json::to_string_pretty(hierarchy).unwrap_or_else(|_| "{}".to_string())
+}
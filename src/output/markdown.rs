@@ -2,7 +2,9 @@
 // Markdown 输出
 // ============================================================
 
-use crate::symbol::{FileMap, Symbol};
+use crate::detector::Language;
+use crate::hierarchy::{Hierarchy, Node, TypeDef};
+use crate::symbol::{FileMap, Symbol, TypeParam};
 
 /// 渲染单个文件的符号 (标题为相对路径)
 pub fn render(map: &FileMap) -> String {
@@ -10,32 +12,198 @@ pub fn render(map: &FileMap) -> String {
 
     out.push_str(&format!("# {}\n\n", map.path));
 
+    // 语法错误: 该文件的符号可能缺失或不准确
+    if map.parse_errors > 0 {
+        let noun = match map.parse_errors {
+            1 => "error",
+            _ => "errors",
+        };
+        let mut locations: Vec<String> = map.diagnostics.iter().map(|d| d.message()).collect();
+        if map.parse_errors > map.diagnostics.len() {
+            locations.push("...".to_string());
+        }
+        out.push_str(&format!(
+            "> {} parse {}: {}\n\n",
+            map.parse_errors,
+            noun,
+            locations.join("; ")
+        ));
+    }
+
     for sym in &map.symbols {
-        render_symbol(&mut out, sym, 0);
+        render_symbol(&mut out, sym, 0, None, map);
     }
 
     out
 }
 
-/// 渲染多个文件，按顺序拼接
+/// 渲染多个文件，按顺序拼接；有入口点时在最前面加 Entry points 一节
 pub fn render_all(maps: &[FileMap]) -> String {
-    maps.iter()
-        .map(render)
-        .collect::<Vec<_>>()
-        .join("\n")
+    let files = maps.iter().map(render).collect::<Vec<_>>().join("\n");
+    match render_entry_points(maps) {
+        Some(entries) => format!("{}\n{}", entries, files),
+        None => files,
+    }
 }
 
-fn render_symbol(out: &mut String, sym: &Symbol, depth: usize) {
+/// 所有文件的入口点，每行为类型、名称、handler 与位置
+fn render_entry_points(maps: &[FileMap]) -> Option<String> {
+    if maps.iter().all(|map| map.entry_points.is_empty()) {
+        return None;
+    }
+    let mut out = String::from("# Entry points\n\n");
+    for map in maps {
+        for entry in &map.entry_points {
+            out.push_str(&format!("- [{}] `{}`", entry.kind.as_str(), entry.name));
+            if let Some(handler) = &entry.handler {
+                out.push_str(&format!(" → {}", handler));
+            }
+            out.push_str(&format!(" ({} line {})\n", map.path, entry.line));
+        }
+    }
+    Some(out)
+}
+
+/// file: 符号来自其他文件时的路径 (子符号继承)，map: 当前文件
+fn render_symbol(out: &mut String, sym: &Symbol, depth: usize, file: Option<&str>, map: &FileMap) {
+    let home = map.path.as_str();
     let indent = "  ".repeat(depth);
     let kind = sym.kind.as_str();
-    let line = sym.range.start.line;
+    let file = sym.path.as_deref().or(file);
+    let mut line = match file {
+        Some(path) => format!("{} line {}", path, sym.range.start.line),
+        None => format!("line {}", sym.range.start.line),
+    };
+    if let Some(def) = &sym.definition {
+        // 定义与声明同文件时省略路径
+        if def.path == file.unwrap_or(home) {
+            line.push_str(&format!(", defined at line {}", def.range.start.line));
+        } else {
+            line.push_str(&format!(
+                ", defined at {} line {}",
+                def.path, def.range.start.line
+            ));
+        }
+    }
+    match sym.overloads.len() {
+        0 => {}
+        1 => line.push_str(", 1 overload"),
+        n => line.push_str(&format!(", {} overloads", n)),
+    }
+    let detail = sym
+        .detail
+        .as_ref()
+        .map(|d| format!(": {}", d))
+        .unwrap_or_default();
+
+    let type_params = type_params_suffix(&sym.type_params, &map.language);
+    let supertypes = supertypes_suffix(sym.supertypes());
+    let attributes: String = sym
+        .attributes
+        .iter()
+        .map(|attr| format!(" @{}", attr))
+        .collect();
+    let covers = if sym.covers.is_empty() {
+        String::new()
+    } else {
+        format!(" → {}", sym.covers.join(", "))
+    };
 
     out.push_str(&format!(
-        "{}- [{}] `{}` (line {})\n",
-        indent, kind, sym.name, line
+        "{}- [{}] `{}`{}{}{}{}{} ({})\n",
+        indent, kind, sym.name, type_params, supertypes, detail, attributes, covers, line
     ));
 
     for child in &sym.children {
-        render_symbol(out, child, depth + 1);
+        render_symbol(out, child, depth + 1, file, map);
+    }
+}
+
+/// 继承关系: 类型自身的定义，再分别列出祖先与后代
+pub fn render_hierarchy(hierarchy: &Hierarchy) -> String {
+    let mut out = format!("# {}\n\n", hierarchy.name);
+
+    for def in &hierarchy.definitions {
+        out.push_str(&format!("- {}\n", type_line(def)));
+    }
+    if hierarchy.definitions.is_empty() {
+        out.push_str(&format!(
+            "- `{}` (not defined in scanned files)\n",
+            hierarchy.name
+        ));
+    }
+
+    for (title, nodes) in [
+        ("Ancestors", &hierarchy.ancestors),
+        ("Descendants", &hierarchy.descendants),
+    ] {
+        out.push_str(&format!("\n## {}\n\n", title));
+        if nodes.is_empty() {
+            out.push_str("- (none)\n");
+        }
+        for node in nodes {
+            render_node(&mut out, node, 0);
+        }
+    }
+
+    out
+}
+
+/// 外部类型只显示名称；同名多处定义时取第一处
+fn render_node(out: &mut String, node: &Node, depth: usize) {
+    let indent = "  ".repeat(depth);
+    match node.definitions.first() {
+        Some(def) => out.push_str(&format!("{}- {}\n", indent, type_line(def))),
+        None => out.push_str(&format!("{}- `{}`\n", indent, node.name)),
+    }
+    for child in &node.children {
+        render_node(out, child, depth + 1);
+    }
+}
+
+fn type_line(def: &TypeDef) -> String {
+    let supertypes = supertypes_suffix(def.supertypes());
+    format!(
+        "[{}] `{}`{} ({} line {})",
+        def.kind.as_str(),
+        def.name,
+        supertypes,
+        def.path,
+        def.line
+    )
+}
+
+/// 泛型参数 `<T: Bound + Other, U>`，Go 按源码写法为 `[K comparable, V any]`，没有时为空
+fn type_params_suffix(params: &[TypeParam], language: &str) -> String {
+    if params.is_empty() {
+        return String::new();
+    }
+    let go = language == Language::Go.as_str();
+    let params: Vec<String> = params
+        .iter()
+        .map(|p| {
+            if p.bounds.is_empty() {
+                p.name.clone()
+            } else if go {
+                format!("{} {}", p.name, p.bounds.join(" "))
+            } else {
+                format!("{}: {}", p.name, p.bounds.join(" + "))
+            }
+        })
+        .collect();
+    if go {
+        format!("[{}]", params.join(", "))
+    } else {
+        format!("<{}>", params.join(", "))
+    }
+}
+
+/// 父类型列表 ` : Base, IFoo`，没有时为空
+fn supertypes_suffix<'a>(names: impl Iterator<Item = &'a String>) -> String {
+    let names: Vec<&str> = names.map(String::as_str).collect();
+    if names.is_empty() {
+        String::new()
+    } else {
+        format!(" : {}", names.join(", "))
     }
 }
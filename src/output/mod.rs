@@ -6,6 +6,7 @@ mod json;
 mod markdown;
 
 use crate::cli::OutputFormat;
+use crate::hierarchy::Hierarchy;
 use crate::symbol::FileMap;
 
 /// 渲染所有文件
@@ -16,29 +17,91 @@ pub fn render_all(maps: &[FileMap], format: OutputFormat) -> String {
     }
 }
 
+/// 估算 token 数时每个 token 对应的字符数
+const CHARS_PER_TOKEN: usize = 4;
+
+/// 粗略估算文本的 token 数
+pub fn estimate_tokens(text: &str) -> usize {
+    text.chars().count().div_ceil(CHARS_PER_TOKEN)
+}
+
+/// 在 token 预算内渲染所有文件
+/// - Markdown: 超出预算时按行截断，末尾追加截断标记行
+/// - JSON: 只保留能完整放下的前若干个文件以保持合法 JSON，截断提示写到 stderr
+pub fn render_within(maps: &[FileMap], format: OutputFormat, budget: usize) -> String {
+    let full = render_all(maps, format);
+    if estimate_tokens(&full) <= budget {
+        return full;
+    }
+    match format {
+        OutputFormat::Markdown => truncate_lines(&full, budget),
+        OutputFormat::Json => {
+            // 文件越少输出越短，二分查找能放下的最多文件数
+            let (mut low, mut high) = (0, maps.len());
+            while low < high {
+                let mid = (low + high).div_ceil(2);
+                if estimate_tokens(&json::render_all(&maps[..mid])) <= budget {
+                    low = mid;
+                } else {
+                    high = mid - 1;
+                }
+            }
+            eprintln!(
+                "Warning: output truncated to the {} token budget, {} of {} files omitted",
+                budget,
+                maps.len() - low,
+                maps.len()
+            );
+            json::render_all(&maps[..low])
+        }
+    }
+}
+
+/// 保留预算内的整行，并为截断标记行预留空间
+fn truncate_lines(text: &str, budget: usize) -> String {
+    let lines: Vec<&str> = text.lines().collect();
+    let marker = |omitted: usize| {
+        format!(
+            "> Truncated to the {} token budget: {} more lines omitted\n",
+            budget, omitted
+        )
+    };
+    let available = (budget * CHARS_PER_TOKEN).saturating_sub(marker(lines.len()).chars().count());
+
+    let mut out = String::new();
+    let mut used = 0;
+    let mut kept = 0;
+    for line in &lines {
+        used += line.chars().count() + 1;
+        if used > available {
+            break;
+        }
+        out.push_str(line);
+        out.push('\n');
+        kept += 1;
+    }
+    out.push_str(&marker(lines.len() - kept));
+    out
+}
+
+/// 渲染类型继承关系查询结果
+pub fn render_hierarchy(hierarchy: &Hierarchy, format: OutputFormat) -> String {
+    match format {
+        OutputFormat::Markdown => markdown::render_hierarchy(hierarchy),
+        OutputFormat::Json => json::render_hierarchy(hierarchy),
+    }
+}
+
 // ============================================================
 // 单元测试
 // ============================================================
 #[cfg(test)]
 mod tests {
     use super::*;
-    use crate::symbol::{Position, Range, Symbol, SymbolKind};
+    use crate::symbol::{Symbol, SymbolKind, TypeParam};
 
     fn make_map(path: &str) -> FileMap {
-        FileMap {
-            path: path.to_string(),
-            language: "rust".to_string(),
-            symbols: vec![Symbol {
-                name: "test".to_string(),
-                kind: SymbolKind::Function,
-                detail: None,
-                range: Range {
-                    start: Position { line: 1, column: 0 },
-                    end: Position { line: 1, column: 10 },
-                },
-                children: vec![],
-            }],
-        }
+        FileMap::new(path, "rust").with_symbols(vec![Symbol::at("test", SymbolKind::Function, 1)])
     }
 
     #[test]
@@ -58,6 +121,70 @@ mod tests {
         assert!(output.contains("\"name\": \"test\""));
     }
 
+    #[test]
+    fn test_render_markdown_trait_impl() {
+        let mut map = make_map("lib.rs");
+        map.symbols[0].kind = SymbolKind::Impl;
+        map.symbols[0].implements = vec!["Display".to_string()];
+
+        let output = render_all(&[map], OutputFormat::Markdown);
+        assert!(output.contains("- [impl] `test` : Display (line 1)"));
+    }
+
+    #[test]
+    fn test_render_markdown_type_params() {
+        let mut map = make_map("lib.rs");
+        map.symbols[0].type_params = vec![
+            TypeParam {
+                name: "T".to_string(),
+                bounds: vec!["Serialize".to_string(), "Clone".to_string()],
+            },
+            TypeParam {
+                name: "U".to_string(),
+                bounds: Vec::new(),
+            },
+        ];
+
+        let output = render_all(&[map], OutputFormat::Markdown);
+        assert!(output.contains("- [function] `test`<T: Serialize + Clone, U> (line 1)"));
+    }
+
+    #[test]
+    fn test_render_markdown_foreign_member() {
+        let mut map = make_map("a.go");
+        let mut member = map.symbols[0].clone();
+        member.name = "Save".to_string();
+        member.path = Some("b.go".to_string());
+        map.symbols[0].children.push(member);
+
+        let output = render_all(&[map], OutputFormat::Markdown);
+        assert!(output.contains("  - [function] `Save` (b.go line 1)"));
+    }
+
+    #[test]
+    fn test_render_within_budget() {
+        let maps: Vec<FileMap> = (0..20).map(|i| make_map(&format!("f{}.rs", i))).collect();
+        let full = render_all(&maps, OutputFormat::Markdown);
+        assert_eq!(render_within(&maps, OutputFormat::Markdown, 10_000), full);
+
+        // Markdown: 按行截断并追加标记，总量不超过预算
+        let output = render_within(&maps, OutputFormat::Markdown, 50);
+        assert!(estimate_tokens(&output) <= 50);
+        assert!(output.starts_with("# f0.rs\n"));
+        let omitted = full.lines().count() - (output.lines().count() - 1);
+        assert!(output.ends_with(&format!(
+            "> Truncated to the 50 token budget: {} more lines omitted\n",
+            omitted
+        )));
+
+        // JSON: 只保留完整的文件
+        let output = render_within(&maps, OutputFormat::Json, 200);
+        assert!(estimate_tokens(&output) <= 200);
+        let parsed: Vec<serde_json::Value> = serde_json::from_str(&output).unwrap();
+        assert!(!parsed.is_empty() && parsed.len() < maps.len());
+        assert_eq!(parsed[0]["path"], "f0.rs");
+    }
+
     #[test]
     fn test_render_multiple_files() {
         let maps = vec![make_map("a.rs"), make_map("b.rs")];
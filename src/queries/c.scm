@@ -2,6 +2,44 @@
 ; C Codemap Query
 ; ============================================================
 
+; ------------------------------------------------------------
+; 签名: 返回类型 (限定符、类型与声明符中的 `*`)、static / inline 修饰
+; ------------------------------------------------------------
+([(function_definition
+    (storage_class_specifier)* @modifier
+    (type_qualifier)* @returns
+    type: (_) @returns
+    declarator: [
+      (function_declarator
+        declarator: (identifier) @name
+        parameters: (parameter_list) @parameters)
+      (pointer_declarator
+        "*" @returns
+        declarator: (function_declarator
+          declarator: (identifier) @name
+          parameters: (parameter_list) @parameters))
+    ])
+  (declaration
+    (storage_class_specifier)* @modifier
+    (type_qualifier)* @returns
+    type: (_) @returns
+    declarator: [
+      (function_declarator
+        declarator: (identifier) @name
+        parameters: (parameter_list) @parameters)
+      (pointer_declarator
+        "*" @returns
+        declarator: (function_declarator
+          declarator: (identifier) @name
+          parameters: (parameter_list) @parameters))
+    ])] @definition.function)
+
+; 参数: 按参数列表归入所属函数，`(void)` 表示没有参数
+((parameter_list
+  [(parameter_declaration) @param_declaration
+   (variadic_parameter) @param @param_variadic]) @parameters
+  (#not-eq? @param_declaration "void"))
+
 ; ------------------------------------------------------------
 ; 函数定义
 ; ------------------------------------------------------------
@@ -14,6 +52,12 @@
   declarator: (function_declarator
     declarator: (identifier) @name)) @definition.function
 
+; 返回指针的函数声明 (头文件原型，与实现文件中的定义配对)
+(declaration
+  declarator: (pointer_declarator
+    declarator: (function_declarator
+      declarator: (identifier) @name))) @definition.function
+
 ; 指针函数
 (function_definition
   declarator: (pointer_declarator
@@ -25,7 +69,7 @@
 ; ------------------------------------------------------------
 (struct_specifier
   name: (type_identifier) @name
-  body: (_) @_body) @definition.class
+  body: (_) @_body) @definition.struct
 
 ; Struct 字段
 (field_declaration
@@ -52,7 +96,7 @@
 ; ------------------------------------------------------------
 (union_specifier
   name: (type_identifier) @name
-  body: (_) @_body) @definition.class
+  body: (_) @_body) @definition.struct
 
 ; ------------------------------------------------------------
 ; 类型定义 (typedef)
@@ -60,13 +104,12 @@
 (type_definition
   declarator: (type_identifier) @name) @definition.type
 
-; typedef struct
+; typedef struct / enum: 同时匹配上面的通用 typedef 模式，靠后的具体模式决定类型
 (type_definition
   type: (struct_specifier
     body: (_) @_body)
-  declarator: (type_identifier) @name) @definition.class
+  declarator: (type_identifier) @name) @definition.struct
 
-; typedef enum
 (type_definition
   type: (enum_specifier
     body: (_) @_body)
@@ -76,7 +119,7 @@
 ; 宏定义
 ; ------------------------------------------------------------
 (preproc_function_def
-  name: (identifier) @name) @definition.function
+  name: (identifier) @name) @definition.macro
 
 ; 宏常量
 (preproc_def
@@ -2,6 +2,97 @@
 ; C++ Codemap Query
 ; ============================================================
 
+; ------------------------------------------------------------
+; 签名: 返回类型 (限定符、类型与声明符中的 `*` / `&`)、static / inline / constexpr 修饰，
+; 函数声明符可被指针/引用包裹
+; ------------------------------------------------------------
+([(function_definition
+    [(storage_class_specifier) (type_qualifier "constexpr") (type_qualifier "consteval")]* @modifier
+    (type_qualifier "const")? @returns
+    type: (_)? @returns
+    declarator: [
+      (function_declarator
+        declarator: [(identifier) @name
+                     (field_identifier) @name
+                     (operator_name) @name
+                     (destructor_name) @name
+                     (qualified_identifier) @qualified_name
+                     (template_function) @qualified_name]
+        parameters: (parameter_list) @parameters)
+      (_
+        ["*" "&" "&&"] @returns
+        (function_declarator
+          declarator: [(identifier) @name
+                       (field_identifier) @name
+                       (operator_name) @name
+                       (destructor_name) @name
+                       (qualified_identifier) @qualified_name
+                       (template_function) @qualified_name]
+          parameters: (parameter_list) @parameters))
+    ])
+  (declaration
+    [(storage_class_specifier) (type_qualifier "constexpr") (type_qualifier "consteval")]* @modifier
+    (type_qualifier "const")? @returns
+    type: (_)? @returns
+    declarator: [
+      (function_declarator
+        declarator: [(identifier) @name
+                     (field_identifier) @name
+                     (operator_name) @name
+                     (destructor_name) @name
+                     (qualified_identifier) @qualified_name
+                     (template_function) @qualified_name]
+        parameters: (parameter_list) @parameters)
+      (_
+        ["*" "&" "&&"] @returns
+        (function_declarator
+          declarator: [(identifier) @name
+                       (field_identifier) @name
+                       (operator_name) @name
+                       (destructor_name) @name
+                       (qualified_identifier) @qualified_name
+                       (template_function) @qualified_name]
+          parameters: (parameter_list) @parameters))
+    ])
+  (field_declaration
+    [(storage_class_specifier) (type_qualifier "constexpr") (type_qualifier "consteval")]* @modifier
+    (type_qualifier "const")? @returns
+    type: (_)? @returns
+    declarator: [
+      (function_declarator
+        declarator: [(identifier) @name
+                     (field_identifier) @name
+                     (operator_name) @name
+                     (destructor_name) @name
+                     (qualified_identifier) @qualified_name
+                     (template_function) @qualified_name]
+        parameters: (parameter_list) @parameters)
+      (_
+        ["*" "&" "&&"] @returns
+        (function_declarator
+          declarator: [(identifier) @name
+                       (field_identifier) @name
+                       (operator_name) @name
+                       (destructor_name) @name
+                       (qualified_identifier) @qualified_name
+                       (template_function) @qualified_name]
+          parameters: (parameter_list) @parameters))
+    ])] @definition.function)
+
+; 尾置返回类型 (`auto f() -> int`): 按参数列表归入所属函数
+(function_declarator
+  parameters: (parameter_list) @parameters
+  (trailing_return_type (type_descriptor) @returns))
+
+; 参数: 按参数列表归入所属函数，`(void)` 表示没有参数
+((parameter_list
+  [(parameter_declaration) @param_declaration
+   (optional_parameter_declaration
+     default_value: (_) @param_default) @param_declaration
+   (variadic_parameter_declaration) @param_declaration @param_variadic
+   "..." @param @param_variadic]) @parameters
+  (#not-eq? @param_declaration "void"))
+
 ; ------------------------------------------------------------
 ; 函数定义
 ; ------------------------------------------------------------
@@ -24,8 +115,40 @@
 ; 结构体定义
 (struct_specifier
   name: (type_identifier) @name
+  body: (_) @_body) @definition.struct
+
+; 基类 (访问说明符不计入)
+(class_specifier
+  name: (type_identifier) @name
+  (base_class_clause
+    [(type_identifier) (template_type) (qualified_identifier)] @extends)
   body: (_) @_body) @definition.class
 
+(struct_specifier
+  name: (type_identifier) @name
+  (base_class_clause
+    [(type_identifier) (template_type) (qualified_identifier)] @extends)
+  body: (_) @_body) @definition.struct
+
+; ------------------------------------------------------------
+; 模板参数: 非类型参数与 concept 约束的参数 (std::integral T) 以类型为约束，归入模板中的类 / 函数
+; ------------------------------------------------------------
+(template_declaration
+  parameters: (template_parameter_list
+    [(type_parameter_declaration
+       (type_identifier) @type_param)
+     (optional_type_parameter_declaration
+       name: (type_identifier) @type_param)
+     (variadic_type_parameter_declaration
+       (type_identifier) @type_param)
+     (parameter_declaration
+       type: (_) @type_bound
+       declarator: (identifier) @type_param)
+     (optional_parameter_declaration
+       type: (_) @type_bound
+       declarator: (identifier) @type_param)])
+  (_) @symbol)
+
 ; ------------------------------------------------------------
 ; 类成员
 ; ------------------------------------------------------------
@@ -49,7 +172,83 @@
 ; 方法声明 (类内)
 (field_declaration
   declarator: (function_declarator
-    declarator: (field_identifier) @name)) @definition.method
+    declarator: [(field_identifier) (operator_name)] @name)) @definition.method
+
+; 返回指针/引用的方法声明 (类内)
+(field_declaration
+  declarator: [
+    (pointer_declarator
+      declarator: (function_declarator
+        declarator: [(field_identifier) (operator_name)] @name))
+    (reference_declarator
+      (function_declarator
+        declarator: [(field_identifier) (operator_name)] @name))
+  ]) @definition.method
+
+; 返回指针/引用的方法定义 (类内)
+(class_specifier
+  body: (field_declaration_list
+    (function_definition
+      declarator: [
+        (pointer_declarator
+          declarator: (function_declarator
+            declarator: [(field_identifier) (operator_name)] @name))
+        (reference_declarator
+          (function_declarator
+            declarator: [(field_identifier) (operator_name)] @name))
+      ]) @definition.method))
+
+; 运算符/析构函数定义 (类内)
+(class_specifier
+  body: (field_declaration_list
+    (function_definition
+      declarator: (function_declarator
+        declarator: [(operator_name) (destructor_name)] @name)) @definition.method))
+
+; 成员模板与类内显式特化 (类内 template<...> 声明 / 定义)，特化的模板实参记入 detail
+(field_declaration_list
+  (template_declaration
+    [(declaration
+       declarator: (function_declarator
+         declarator: [(identifier) (field_identifier) (operator_name)] @name))
+     (declaration
+       declarator: (function_declarator
+         declarator: (template_function) @qualified_name))
+     (function_definition
+       declarator: (function_declarator
+         declarator: [(identifier) (field_identifier) (operator_name)] @name))
+     (function_definition
+       declarator: (function_declarator
+         declarator: (template_function) @qualified_name))] @definition.method))
+
+; 析构函数声明 (类内)
+(field_declaration_list
+  (declaration
+    declarator: (function_declarator
+      declarator: (destructor_name) @name)) @definition.method)
+
+; ------------------------------------------------------------
+; 类外成员定义 (Foo::bar)，由聚合阶段归并到所属类
+; ------------------------------------------------------------
+(function_definition
+  declarator: (function_declarator
+    declarator: (qualified_identifier) @qualified_name)) @definition.method
+
+(function_definition
+  declarator: [
+    (pointer_declarator
+      declarator: (function_declarator
+        declarator: (qualified_identifier) @qualified_name))
+    (reference_declarator
+      (function_declarator
+        declarator: (qualified_identifier) @qualified_name))
+  ]) @definition.method
+
+; 类外成员的显式特化声明 (template<> int Foo::as<int>() const;)
+(template_declaration
+  (declaration
+    declarator: (function_declarator
+      declarator: (qualified_identifier) @qualified_name)) @definition.method)
 
 ; ------------------------------------------------------------
 ; 命名空间
@@ -58,18 +257,9 @@
   name: (namespace_identifier) @name) @definition.namespace
 
 ; ------------------------------------------------------------
-; 模板类
+; 模板类/模板函数: 由上面的 class_specifier / function_definition 匹配，
+; 不单独匹配 template_declaration，避免同名符号嵌套两层
 ; ------------------------------------------------------------
-(template_declaration
-  (class_specifier
-    name: (type_identifier) @name
-    body: (_) @_body)) @definition.class
-
-; 模板函数
-(template_declaration
-  (function_definition
-    declarator: (function_declarator
-      declarator: (identifier) @name))) @definition.function
 
 ; ------------------------------------------------------------
 ; 枚举
@@ -92,7 +282,7 @@
 ; 宏定义
 ; ------------------------------------------------------------
 (preproc_function_def
-  name: (identifier) @name) @definition.function
+  name: (identifier) @name) @definition.macro
 
 ; 宏常量
 (preproc_def
@@ -1,6 +1,7 @@
 ; ============================================================
 ; C# Codemap Query
 ; ============================================================
+; 修饰符: 访问修饰符记入 @visibility，其余 (static、abstract、partial 等) 记入 @detail，特性 ([...]) 记入 @attribute
 
 ; ------------------------------------------------------------
 ; Namespace
@@ -17,64 +18,266 @@
 (file_scoped_namespace_declaration
   name: (qualified_name) @name) @definition.namespace
 
+; ------------------------------------------------------------
+; 继承: 基类列表无法从语法区分基类与接口，按 .NET 命名约定 (IFoo) 区分
+; ------------------------------------------------------------
+(class_declaration
+  name: (identifier) @name
+  (base_list
+    [(identifier) (generic_name) (qualified_name)] @extends
+    (#not-match? @extends "^([A-Za-z_][A-Za-z0-9_]*\\.)*I[A-Z]"))) @definition.class
+
+(class_declaration
+  name: (identifier) @name
+  (base_list
+    [(identifier) (generic_name) (qualified_name)] @implements
+    (#match? @implements "^([A-Za-z_][A-Za-z0-9_]*\\.)*I[A-Z]"))) @definition.class
+
+(record_declaration
+  name: (identifier) @name
+  (base_list
+    [(identifier) (generic_name) (qualified_name)] @extends
+    (#not-match? @extends "^([A-Za-z_][A-Za-z0-9_]*\\.)*I[A-Z]"))) @definition.record
+
+(record_declaration
+  name: (identifier) @name
+  (base_list
+    [(identifier) (generic_name) (qualified_name)] @implements
+    (#match? @implements "^([A-Za-z_][A-Za-z0-9_]*\\.)*I[A-Z]"))) @definition.record
+
+; record R(int X) : Base(X)
+(record_declaration
+  name: (identifier) @name
+  (base_list
+    (primary_constructor_base_type
+      type: (_) @extends))) @definition.record
+
+(struct_declaration
+  name: (identifier) @name
+  (base_list
+    (_) @implements)) @definition.struct
+
+(interface_declaration
+  name: (identifier) @name
+  (base_list
+    (_) @extends)) @definition.interface
+
+; ------------------------------------------------------------
+; 泛型参数与 where 约束 (where T : class, new()): 归入所在的类型 / 方法 / 委托
+; ------------------------------------------------------------
+(_
+  (type_parameter_list
+    (type_parameter
+      name: (identifier) @type_param))) @symbol
+
+(_
+  (type_parameter_constraints_clause
+    (identifier) @type_param
+    (type_parameter_constraint) @type_bound)) @symbol
+
+; ------------------------------------------------------------
+; 签名: 返回类型与 static / async / unsafe，参数见下方按参数列表归入的模式
+; ------------------------------------------------------------
+(method_declaration
+  (modifier)* @modifier
+  returns: (_) @returns
+  name: (identifier) @name
+  parameters: (parameter_list) @parameters) @definition.method
+
+(constructor_declaration
+  (modifier)* @modifier
+  name: (identifier) @name
+  parameters: (parameter_list) @parameters) @definition.method
+
+(operator_declaration
+  (modifier)* @modifier
+  type: (_) @returns
+  "operator" @name
+  operator: _ @name
+  parameters: (parameter_list) @parameters) @definition.method
+
+(local_function_statement
+  (modifier)* @modifier
+  type: (_) @returns
+  name: (identifier) @name
+  parameters: (parameter_list) @parameters) @definition.function
+
+(delegate_declaration
+  (modifier)* @modifier
+  type: (_) @returns
+  name: (identifier) @name
+  parameters: (parameter_list) @parameters) @definition.type
+
+; 参数: 按参数列表归入所属方法 / 委托，params 为可变参数
+(parameter_list
+  [(parameter
+     type: (_) @param_type
+     name: (identifier) @param)
+   (parameter
+     type: (_) @param_type
+     name: (identifier) @param
+     .
+     (_) @param_default)]) @parameters
+
+(parameter_list
+  "params" @param_variadic
+  type: (_) @param_type
+  name: (identifier) @param) @parameters
+
 ; ------------------------------------------------------------
 ; Interface
 ; ------------------------------------------------------------
 (interface_declaration
+  [
+    (attribute_list (attribute) @attribute)
+    (modifier ["public" "private" "protected" "internal" "file"]) @visibility
+    (modifier ["abstract" "async" "const" "extern" "fixed" "new" "override" "partial" "readonly" "required" "sealed" "static" "unsafe" "virtual" "volatile"]) @detail
+  ]*
   name: (identifier) @name) @definition.interface
 
 ; ------------------------------------------------------------
 ; Class
 ; ------------------------------------------------------------
 (class_declaration
+  [
+    (attribute_list (attribute) @attribute)
+    (modifier ["public" "private" "protected" "internal" "file"]) @visibility
+    (modifier ["abstract" "async" "const" "extern" "fixed" "new" "override" "partial" "readonly" "required" "sealed" "static" "unsafe" "virtual" "volatile"]) @detail
+  ]*
   name: (identifier) @name) @definition.class
 
 ; Record (C# 9+)
 (record_declaration
-  name: (identifier) @name) @definition.class
+  [
+    (attribute_list (attribute) @attribute)
+    (modifier ["public" "private" "protected" "internal" "file"]) @visibility
+    (modifier ["abstract" "async" "const" "extern" "fixed" "new" "override" "partial" "readonly" "required" "sealed" "static" "unsafe" "virtual" "volatile"]) @detail
+  ]*
+  name: (identifier) @name) @definition.record
+
+; 主构造函数参数 (record Person(string Name)) 即属性
+(record_declaration
+  (parameter_list
+    (parameter
+      name: (identifier) @name) @definition.property))
 
 ; ------------------------------------------------------------
 ; Struct
 ; ------------------------------------------------------------
 (struct_declaration
-  name: (identifier) @name) @definition.class
+  [
+    (attribute_list (attribute) @attribute)
+    (modifier ["public" "private" "protected" "internal" "file"]) @visibility
+    (modifier ["abstract" "async" "const" "extern" "fixed" "new" "override" "partial" "readonly" "required" "sealed" "static" "unsafe" "virtual" "volatile"]) @detail
+  ]*
+  name: (identifier) @name) @definition.struct
 
 ; ------------------------------------------------------------
 ; Enum
 ; ------------------------------------------------------------
 (enum_declaration
+  [
+    (attribute_list (attribute) @attribute)
+    (modifier ["public" "private" "protected" "internal" "file"]) @visibility
+    (modifier ["abstract" "async" "const" "extern" "fixed" "new" "override" "partial" "readonly" "required" "sealed" "static" "unsafe" "virtual" "volatile"]) @detail
+  ]*
   name: (identifier) @name) @definition.enum
 
 ; Enum members
 (enum_member_declaration
+  (attribute_list (attribute) @attribute)*
   name: (identifier) @name) @definition.enum_member
 
 ; ------------------------------------------------------------
 ; Delegate
 ; ------------------------------------------------------------
 (delegate_declaration
+  [
+    (attribute_list (attribute) @attribute)
+    (modifier ["public" "private" "protected" "internal" "file"]) @visibility
+    (modifier ["abstract" "async" "const" "extern" "fixed" "new" "override" "partial" "readonly" "required" "sealed" "static" "unsafe" "virtual" "volatile"]) @detail
+  ]*
   name: (identifier) @name) @definition.type
 
 ; ------------------------------------------------------------
 ; Method
 ; ------------------------------------------------------------
 (method_declaration
+  [
+    (attribute_list (attribute) @attribute)
+    (modifier ["public" "private" "protected" "internal" "file"]) @visibility
+    (modifier ["abstract" "async" "const" "extern" "fixed" "new" "override" "partial" "readonly" "required" "sealed" "static" "unsafe" "virtual" "volatile"]) @detail
+  ]*
   name: (identifier) @name) @definition.method
 
 ; Constructor
 (constructor_declaration
+  [
+    (attribute_list (attribute) @attribute)
+    (modifier ["public" "private" "protected" "internal" "file"]) @visibility
+    (modifier ["abstract" "async" "const" "extern" "fixed" "new" "override" "partial" "readonly" "required" "sealed" "static" "unsafe" "virtual" "volatile"]) @detail
+  ]*
   name: (identifier) @name) @definition.method
 
+; 运算符重载: 名称为 `operator +`
+(operator_declaration
+  [
+    (attribute_list (attribute) @attribute)
+    (modifier ["public" "private" "protected" "internal" "file"]) @visibility
+    (modifier ["abstract" "async" "const" "extern" "fixed" "new" "override" "partial" "readonly" "required" "sealed" "static" "unsafe" "virtual" "volatile"]) @detail
+  ]*
+  "operator" @name
+  operator: _ @name) @definition.method
+
+; 转换运算符: 名称为 `implicit operator int`
+(conversion_operator_declaration
+  [
+    (attribute_list (attribute) @attribute)
+    (modifier ["public" "private" "protected" "internal" "file"]) @visibility
+    (modifier ["abstract" "async" "const" "extern" "fixed" "new" "override" "partial" "readonly" "required" "sealed" "static" "unsafe" "virtual" "volatile"]) @detail
+  ]*
+  ["implicit" "explicit"] @name
+  type: (_) @name) @definition.method
+
+; 局部函数
+(local_function_statement
+  [
+    (attribute_list (attribute) @attribute)
+    (modifier ["public" "private" "protected" "internal" "file"]) @visibility
+    (modifier ["abstract" "async" "const" "extern" "fixed" "new" "override" "partial" "readonly" "required" "sealed" "static" "unsafe" "virtual" "volatile"]) @detail
+  ]*
+  name: (identifier) @name) @definition.function
+
 ; ------------------------------------------------------------
 ; Property
 ; ------------------------------------------------------------
 (property_declaration
+  [
+    (attribute_list (attribute) @attribute)
+    (modifier ["public" "private" "protected" "internal" "file"]) @visibility
+    (modifier ["abstract" "async" "const" "extern" "fixed" "new" "override" "partial" "readonly" "required" "sealed" "static" "unsafe" "virtual" "volatile"]) @detail
+  ]*
   name: (identifier) @name) @definition.property
 
+; 索引器: 名称为 `this[int index]`
+(indexer_declaration
+  [
+    (attribute_list (attribute) @attribute)
+    (modifier ["public" "private" "protected" "internal" "file"]) @visibility
+    (modifier ["abstract" "async" "const" "extern" "fixed" "new" "override" "partial" "readonly" "required" "sealed" "static" "unsafe" "virtual" "volatile"]) @detail
+  ]*
+  "this" @name
+  parameters: (_) @name) @definition.property
+
 ; ------------------------------------------------------------
 ; Field
 ; ------------------------------------------------------------
 (field_declaration
+  [
+    (attribute_list (attribute) @attribute)
+    (modifier ["public" "private" "protected" "internal" "file"]) @visibility
+    (modifier ["abstract" "async" "const" "extern" "fixed" "new" "override" "partial" "readonly" "required" "sealed" "static" "unsafe" "virtual" "volatile"]) @detail
+  ]*
   (variable_declaration
     (variable_declarator
       (identifier) @name))) @definition.field
@@ -83,9 +286,19 @@
 ; Event
 ; ------------------------------------------------------------
 (event_declaration
-  name: (identifier) @name) @definition.property
+  [
+    (attribute_list (attribute) @attribute)
+    (modifier ["public" "private" "protected" "internal" "file"]) @visibility
+    (modifier ["abstract" "async" "const" "extern" "fixed" "new" "override" "partial" "readonly" "required" "sealed" "static" "unsafe" "virtual" "volatile"]) @detail
+  ]*
+  name: (identifier) @name) @definition.event
 
 (event_field_declaration
+  [
+    (attribute_list (attribute) @attribute)
+    (modifier ["public" "private" "protected" "internal" "file"]) @visibility
+    (modifier ["abstract" "async" "const" "extern" "fixed" "new" "override" "partial" "readonly" "required" "sealed" "static" "unsafe" "virtual" "volatile"]) @detail
+  ]*
   (variable_declaration
     (variable_declarator
-      (identifier) @name))) @definition.property
+      (identifier) @name))) @definition.event
@@ -9,9 +9,21 @@
   name: (identifier) @name) @definition.function
 
 ; ------------------------------------------------------------
-; 方法定义
+; 方法定义 (receiver 类型作为 owner，之后归并到类型声明下)
 ; ------------------------------------------------------------
 (method_declaration
+  receiver: (parameter_list
+    (parameter_declaration
+      type: [
+        (type_identifier) @owner
+        (pointer_type
+          (type_identifier) @owner)
+        (generic_type
+          type: (type_identifier) @owner)
+        (pointer_type
+          (generic_type
+            type: (type_identifier) @owner))
+      ]))
   name: (field_identifier) @name) @definition.method
 
 ; ------------------------------------------------------------
@@ -20,7 +32,7 @@
 (type_declaration
   (type_spec
     name: (type_identifier) @name
-    type: (struct_type) @_struct)) @definition.class
+    type: (struct_type) @_struct)) @definition.struct
 
 ; Struct 字段
 (field_declaration
@@ -52,6 +64,76 @@
     name: (type_identifier) @name
     type: (function_type))) @definition.type
 
+; ------------------------------------------------------------
+; 泛型参数与约束 ([K comparable, V any])
+; ------------------------------------------------------------
+(function_declaration
+  name: (identifier) @name
+  type_parameters: (type_parameter_list
+    (type_parameter_declaration
+      name: (identifier) @type_param
+      type: (type_constraint) @type_bound))) @definition.function
+
+(type_declaration
+  (type_spec
+    name: (type_identifier) @name
+    type_parameters: (type_parameter_list
+      (type_parameter_declaration
+        name: (identifier) @type_param
+        type: (type_constraint) @type_bound))
+    type: (struct_type))) @definition.struct
+
+(type_declaration
+  (type_spec
+    name: (type_identifier) @name
+    type_parameters: (type_parameter_list
+      (type_parameter_declaration
+        name: (identifier) @type_param
+        type: (type_constraint) @type_bound))
+    type: (interface_type))) @definition.interface
+
+(type_declaration
+  (type_spec
+    name: (type_identifier) @name
+    type_parameters: (type_parameter_list
+      (type_parameter_declaration
+        name: (identifier) @type_param
+        type: (type_constraint) @type_bound))
+    type: [(type_identifier) (function_type)])) @definition.type
+
+; ------------------------------------------------------------
+; 签名: 返回类型 (多返回值保留括号)，参数按参数列表归入所属函数
+; ------------------------------------------------------------
+(function_declaration
+  name: (identifier) @name
+  parameters: (parameter_list) @parameters
+  result: (_)? @returns) @definition.function
+
+(method_declaration
+  name: (field_identifier) @name
+  parameters: (parameter_list) @parameters
+  result: (_)? @returns) @definition.method
+
+(method_elem
+  name: (field_identifier) @name
+  parameters: (parameter_list) @parameters
+  result: (_)? @returns) @definition.method
+
+; 参数: 同一声明中的多个参数名共享类型 (`a, b int`)，只写类型的参数名为空 (`func(int, string)`)
+(parameter_list
+  [(parameter_declaration
+     name: (identifier) @param
+     type: (_) @param_type)
+   (parameter_declaration
+     !name
+     type: (_) @param_type)
+   (variadic_parameter_declaration
+     name: (identifier) @param
+     type: (_) @param_type) @param_variadic
+   (variadic_parameter_declaration
+     !name
+     type: (_) @param_type) @param_variadic]) @parameters
+
 ; ------------------------------------------------------------
 ; 常量定义
 ; ------------------------------------------------------------
@@ -63,3 +145,46 @@
 ; ------------------------------------------------------------
 (var_spec
   name: (identifier) @name) @definition.variable
+
+; ------------------------------------------------------------
+; HTTP 路由: http.HandleFunc("/path", handler)、mux.Handle(...)
+; gin / echo / chi 的 r.GET("/path", handler) / r.Get(...)
+; ------------------------------------------------------------
+(call_expression
+  function: (selector_expression
+    field: (field_identifier) @_register)
+  arguments: (argument_list
+    .
+    (interpreted_string_literal) @name
+    .
+    (_) @handler)
+  (#any-of? @_register "HandleFunc" "Handle")) @entry.route
+
+(call_expression
+  function: (selector_expression
+    field: (field_identifier) @http_method)
+  arguments: (argument_list
+    .
+    (interpreted_string_literal) @name
+    .
+    [(identifier) (selector_expression)] @handler)
+  (#any-of? @http_method
+    "GET" "POST" "PUT" "DELETE" "PATCH" "HEAD" "OPTIONS"
+    "Get" "Post" "Put" "Delete" "Patch" "Head" "Options")) @entry.route
+
+; ------------------------------------------------------------
+; CLI 命令: &cobra.Command{Use: "serve [flags]", ...}
+; ------------------------------------------------------------
+(composite_literal
+  type: (qualified_type
+    package: (package_identifier) @_pkg
+    name: (type_identifier) @_type)
+  body: (literal_value
+    (keyed_element
+      key: (literal_element
+        (identifier) @_key)
+      value: (literal_element
+        (interpreted_string_literal) @name)))
+  (#eq? @_pkg "cobra")
+  (#eq? @_type "Command")
+  (#eq? @_key "Use")) @entry.command
@@ -2,6 +2,137 @@
 ; Java Codemap Query
 ; ============================================================
 
+; ------------------------------------------------------------
+; 注解 (@RestController / @Test 等): 放在最前面，类型由后续模式决定
+; ------------------------------------------------------------
+(class_declaration
+  (modifiers
+    [(marker_annotation) (annotation)] @attribute)
+  name: (identifier) @name) @definition.class
+
+(interface_declaration
+  (modifiers
+    [(marker_annotation) (annotation)] @attribute)
+  name: (identifier) @name) @definition.interface
+
+(enum_declaration
+  (modifiers
+    [(marker_annotation) (annotation)] @attribute)
+  name: (identifier) @name) @definition.enum
+
+(record_declaration
+  (modifiers
+    [(marker_annotation) (annotation)] @attribute)
+  name: (identifier) @name) @definition.record
+
+(annotation_type_declaration
+  (modifiers
+    [(marker_annotation) (annotation)] @attribute)
+  name: (identifier) @name) @definition.interface
+
+(method_declaration
+  (modifiers
+    [(marker_annotation) (annotation)] @attribute)
+  name: (identifier) @name) @definition.method
+
+(constructor_declaration
+  (modifiers
+    [(marker_annotation) (annotation)] @attribute)
+  name: (identifier) @name) @definition.method
+
+(field_declaration
+  (modifiers
+    [(marker_annotation) (annotation)] @attribute)
+  declarator: (variable_declarator
+    name: (identifier) @name)) @definition.field
+
+(enum_constant
+  (modifiers
+    [(marker_annotation) (annotation)] @attribute)
+  name: (identifier) @name) @definition.enum_member
+
+; ------------------------------------------------------------
+; 继承: extends 记入 @extends，implements 记入 @implements (接口的 extends 为 @extends)
+; ------------------------------------------------------------
+(class_declaration
+  name: (identifier) @name
+  superclass: (superclass
+    (_) @extends)) @definition.class
+
+(class_declaration
+  name: (identifier) @name
+  interfaces: (super_interfaces
+    (type_list
+      (_) @implements))) @definition.class
+
+(interface_declaration
+  name: (identifier) @name
+  (extends_interfaces
+    (type_list
+      (_) @extends))) @definition.interface
+
+(enum_declaration
+  name: (identifier) @name
+  interfaces: (super_interfaces
+    (type_list
+      (_) @implements))) @definition.enum
+
+(record_declaration
+  name: (identifier) @name
+  interfaces: (super_interfaces
+    (type_list
+      (_) @implements))) @definition.record
+
+; ------------------------------------------------------------
+; 泛型参数与约束 (<T extends Bound & Other>): 归入所在的类型 / 方法
+; ------------------------------------------------------------
+(_
+  type_parameters: (type_parameters
+    [(type_parameter
+       (type_identifier) @type_param)
+     (type_parameter
+       (type_identifier) @type_param
+       (type_bound
+         (_) @type_bound))])) @symbol
+
+; ------------------------------------------------------------
+; 签名: 参数、返回类型与 static (构造函数没有返回类型)
+; ------------------------------------------------------------
+(method_declaration
+  (modifiers)? @modifier
+  type: (_) @returns
+  name: (identifier) @name
+  parameters: (formal_parameters) @parameters) @definition.method
+
+(constructor_declaration
+  (modifiers)? @modifier
+  name: (identifier) @name
+  parameters: (formal_parameters) @parameters) @definition.method
+
+(method_declaration
+  name: (identifier) @name
+  parameters: (formal_parameters
+    [(formal_parameter
+       type: (_) @param_type
+       name: (identifier) @param)
+     (spread_parameter
+       (_) @param_type
+       .
+       (variable_declarator
+         name: (identifier) @param)) @param_variadic])) @definition.method
+
+(constructor_declaration
+  name: (identifier) @name
+  parameters: (formal_parameters
+    [(formal_parameter
+       type: (_) @param_type
+       name: (identifier) @param)
+     (spread_parameter
+       (_) @param_type
+       .
+       (variable_declarator
+         name: (identifier) @param)) @param_variadic])) @definition.method
+
 ; ------------------------------------------------------------
 ; 类定义
 ; ------------------------------------------------------------
@@ -79,4 +210,4 @@
 ; 记录类 (Java 16+)
 ; ------------------------------------------------------------
 (record_declaration
-  name: (identifier) @name) @definition.class
+  name: (identifier) @name) @definition.record
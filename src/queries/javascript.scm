@@ -8,6 +8,12 @@
 (class_declaration
   name: (identifier) @name) @definition.class
 
+; 继承 (extends 的表达式)
+(class_declaration
+  name: (identifier) @name
+  (class_heritage
+    (_) @extends)) @definition.class
+
 ; 导出类
 (export_statement
   (class_declaration
@@ -64,6 +70,63 @@
 (generator_function_declaration
   name: (identifier) @name) @definition.function
 
+; ------------------------------------------------------------
+; 签名: 参数、返回类型与 async / static
+; ------------------------------------------------------------
+(method_definition
+  "static"? @modifier
+  "async"? @modifier
+  name: [(property_identifier) (private_property_identifier)] @name
+  parameters: (formal_parameters) @parameters) @definition.method
+
+(function_declaration
+  "async"? @modifier
+  name: (identifier) @name
+  parameters: (formal_parameters) @parameters) @definition.function
+
+(generator_function_declaration
+  "async"? @modifier
+  name: (identifier) @name
+  parameters: (formal_parameters) @parameters) @definition.function
+
+(lexical_declaration
+  (variable_declarator
+    name: (identifier) @name
+    value: (arrow_function
+      "async"? @modifier
+      parameters: (formal_parameters) @parameters))) @definition.function
+
+(lexical_declaration
+  (variable_declarator
+    name: (identifier) @name
+    value: (arrow_function
+      "async"? @modifier
+      parameter: (identifier) @param))) @definition.function
+
+(variable_declaration
+  (variable_declarator
+    name: (identifier) @name
+    value: (arrow_function
+      "async"? @modifier
+      parameters: (formal_parameters) @parameters))) @definition.function
+
+(variable_declaration
+  (variable_declarator
+    name: (identifier) @name
+    value: (arrow_function
+      "async"? @modifier
+      parameter: (identifier) @param))) @definition.function
+
+; 参数: 按参数列表归入所属函数 / 方法
+(formal_parameters
+  [(identifier) @param
+   (assignment_pattern
+     left: (_) @param
+     right: (_) @param_default)
+   (rest_pattern) @param @param_variadic
+   (object_pattern) @param
+   (array_pattern) @param]) @parameters
+
 ; ------------------------------------------------------------
 ; 变量/常量
 ; ------------------------------------------------------------
@@ -74,3 +137,189 @@
 (variable_declaration
   (variable_declarator
     name: (identifier) @name) @definition.variable)
+
+; ------------------------------------------------------------
+; CommonJS 导出
+; ------------------------------------------------------------
+
+; exports.foo = ... / module.exports.foo = ...
+(expression_statement
+  (assignment_expression
+    left: (member_expression
+      object: [(identifier) @_exports
+               (member_expression
+                 object: (identifier) @_module
+                 property: (property_identifier) @_exports)]
+      property: (property_identifier) @name @export)
+    right: (_)) @definition.variable
+  (#eq? @_exports "exports"))
+
+(expression_statement
+  (assignment_expression
+    left: (member_expression
+      object: [(identifier) @_exports
+               (member_expression
+                 object: (identifier) @_module
+                 property: (property_identifier) @_exports)]
+      property: (property_identifier) @name @export)
+    right: [(function_expression) (arrow_function)]) @definition.function
+  (#eq? @_exports "exports"))
+
+; module.exports = { foo, bar: ..., baz() {} }
+(expression_statement
+  (assignment_expression
+    left: (member_expression
+      object: (identifier) @_module
+      property: (property_identifier) @_exports)
+    right: (object
+      [(shorthand_property_identifier) @export
+       (pair key: (property_identifier) @export)
+       (method_definition name: (property_identifier) @export)]))
+  (#eq? @_module "module")
+  (#eq? @_exports "exports"))
+
+(expression_statement
+  (assignment_expression
+    left: (member_expression
+      object: (identifier) @_module
+      property: (property_identifier) @_exports)
+    right: (object
+      (pair
+        key: (property_identifier) @name) @definition.variable))
+  (#eq? @_module "module")
+  (#eq? @_exports "exports"))
+
+(expression_statement
+  (assignment_expression
+    left: (member_expression
+      object: (identifier) @_module
+      property: (property_identifier) @_exports)
+    right: (object
+      (pair
+        key: (property_identifier) @name
+        value: [(function_expression) (arrow_function)]) @definition.function))
+  (#eq? @_module "module")
+  (#eq? @_exports "exports"))
+
+(expression_statement
+  (assignment_expression
+    left: (member_expression
+      object: (identifier) @_module
+      property: (property_identifier) @_exports)
+    right: (object
+      (method_definition
+        name: (property_identifier) @name) @definition.function))
+  (#eq? @_module "module")
+  (#eq? @_exports "exports"))
+
+; module.exports = function foo() {} / class Foo {} / Foo，匿名函数以 module.exports 为名
+(expression_statement
+  (assignment_expression
+    left: (member_expression
+      object: (identifier) @_module
+      property: (property_identifier) @_exports) @name
+    right: [(function_expression !name) (arrow_function)]) @definition.function
+  (#eq? @_module "module")
+  (#eq? @_exports "exports"))
+
+(expression_statement
+  (assignment_expression
+    left: (member_expression
+      object: (identifier) @_module
+      property: (property_identifier) @_exports)
+    right: (function_expression
+      name: (identifier) @name @export)) @definition.function
+  (#eq? @_module "module")
+  (#eq? @_exports "exports"))
+
+(expression_statement
+  (assignment_expression
+    left: (member_expression
+      object: (identifier) @_module
+      property: (property_identifier) @_exports)
+    right: (class
+      name: (identifier) @name @export)) @definition.class
+  (#eq? @_module "module")
+  (#eq? @_exports "exports"))
+
+(expression_statement
+  (assignment_expression
+    left: (member_expression
+      object: (identifier) @_module
+      property: (property_identifier) @_exports)
+    right: (identifier) @export)
+  (#eq? @_module "module")
+  (#eq? @_exports "exports"))
+
+; ------------------------------------------------------------
+; ES module 导出
+; ------------------------------------------------------------
+
+; export default 匿名函数/类，以 default 为名
+(export_statement
+  "default" @name @export
+  value: [(function_expression !name) (arrow_function)] @definition.function)
+
+(export_statement
+  "default" @name @export
+  value: (class !name) @definition.class)
+
+; export 声明、export { a, b }、export default X: 同名顶层符号标记为导出
+(export_statement
+  declaration: (_
+    name: (_) @export))
+
+(export_statement
+  declaration: (lexical_declaration
+    (variable_declarator
+      name: (identifier) @export)))
+
+(export_statement
+  (export_clause
+    (export_specifier
+      name: (identifier) @export)))
+
+(export_statement
+  (identifier) @export)
+
+; ------------------------------------------------------------
+; 测试块 (describe / it / test，含 describe.only 等)
+; 方法名须在 DSL catalog 中，描述字符串为符号名
+; ------------------------------------------------------------
+(call_expression
+  function: [(identifier) @dsl
+             (member_expression
+               object: (identifier) @dsl)]
+  arguments: (arguments
+    .
+    (string
+      (string_fragment) @name)
+    [(arrow_function) (function_expression)])) @definition.test
+
+; ------------------------------------------------------------
+; HTTP 路由 (Express / Koa Router: app.get("/path", ..., handler))
+; 最后一个参数为标识符或成员访问时记为 handler
+; ------------------------------------------------------------
+(call_expression
+  function: (member_expression
+    object: (identifier) @_router
+    property: (property_identifier) @http_method)
+  arguments: (arguments
+    .
+    (string
+      (string_fragment) @name))
+  (#match? @_router "^(app|api|server|router|[a-zA-Z]+Router)$")
+  (#any-of? @http_method "get" "post" "put" "delete" "patch" "head" "options" "all")) @entry.route
+
+(call_expression
+  function: (member_expression
+    object: (identifier) @_router
+    property: (property_identifier) @http_method)
+  arguments: (arguments
+    .
+    (string
+      (string_fragment) @name)
+    [(identifier) (member_expression)] @handler
+    .)
+  (#match? @_router "^(app|api|server|router|[a-zA-Z]+Router)$")
+  (#any-of? @http_method "get" "post" "put" "delete" "patch" "head" "options" "all")) @entry.route
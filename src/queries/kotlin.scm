@@ -3,35 +3,214 @@
 ; ============================================================
 
 ; ------------------------------------------------------------
-; Interface (class_declaration with "interface" keyword)
+; 注解 (@Serializable / @Test 等): 放在最前面，类型由后续模式决定
 ; ------------------------------------------------------------
 (class_declaration
-  "interface" @_kw
-  name: (identifier) @name) @definition.interface
+  (modifiers
+    (annotation) @attribute)
+  name: (identifier) @name) @definition.class
+
+(object_declaration
+  (modifiers
+    (annotation) @attribute)
+  name: (identifier) @name) @definition.class
+
+(function_declaration
+  (modifiers
+    (annotation) @attribute)
+  ["fun" (type_parameters)]
+  .
+  name: (identifier) @name) @definition.function
+
+(function_declaration
+  (modifiers
+    (annotation) @attribute)
+  [(user_type) (nullable_type)] @name
+  .
+  name: (identifier) @name) @definition.function
+
+(property_declaration
+  (modifiers
+    (annotation) @attribute)
+  (variable_declaration
+    (identifier) @name)) @definition.property
+
+(class_parameter
+  (modifiers
+    (annotation) @attribute)
+  (identifier) @name) @definition.property
+
+; ------------------------------------------------------------
+; 泛型参数与约束 (<T : Bound>、where T : Other): 归入所在的类 / 函数
+; ------------------------------------------------------------
+(_
+  (type_parameters
+    [(type_parameter
+       (identifier) @type_param)
+     (type_parameter
+       (identifier) @type_param
+       .
+       (_) @type_bound)])) @symbol
+
+(_
+  (type_constraints
+    (type_constraint
+      (identifier) @type_param
+      .
+      (_) @type_bound))) @symbol
+
+; ------------------------------------------------------------
+; 签名: 返回类型与 suspend，参数按参数列表归入所属函数 (vararg 为可变参数)
+; ------------------------------------------------------------
+(function_declaration
+  (modifiers)? @modifier
+  ["fun" (type_parameters)]
+  .
+  name: (identifier) @name
+  (function_value_parameters) @parameters
+  .
+  [(user_type) (nullable_type) (function_type) (parenthesized_type)]? @returns) @definition.function
+
+; 扩展函数 (名称含 receiver 类型)
+(function_declaration
+  (modifiers)? @modifier
+  [(user_type) (nullable_type)] @name
+  .
+  name: (identifier) @name
+  (function_value_parameters) @parameters
+  .
+  [(user_type) (nullable_type) (function_type) (parenthesized_type)]? @returns) @definition.function
+
+; 参数: 按参数列表归入所属函数
+(function_value_parameters
+  (parameter
+    (identifier) @param
+    .
+    (_) @param_type)) @parameters
+
+; 默认值为参数之后 `=` 后的表达式
+(function_value_parameters
+  (parameter
+    (identifier) @param)
+  .
+  "="
+  .
+  (_) @param_default) @parameters
+
+(function_value_parameters
+  (parameter_modifiers
+    (parameter_modifier) @param_variadic
+    (#eq? @param_variadic "vararg"))
+  .
+  (parameter
+    (identifier) @param)) @parameters
 
 ; ------------------------------------------------------------
-; Enum class (class_declaration with class_modifier containing "enum")
-; Note: enum keyword is in class_modifier node, which is a child of modifiers
+; 继承: 带构造调用的父类型为父类 (@extends)，其余为接口 (@implements)；
+; 接口的父接口为 @extends
 ; ------------------------------------------------------------
 (class_declaration
-  (modifiers
-    (class_modifier) @_enum)
+  "class"
+  name: (identifier) @name
+  (delegation_specifiers
+    (delegation_specifier
+      (constructor_invocation
+        (user_type) @extends)))) @definition.class
+
+(class_declaration
+  "class"
+  name: (identifier) @name
+  (delegation_specifiers
+    (delegation_specifier
+      [(user_type) @implements
+       (explicit_delegation
+         (user_type) @implements)]))) @definition.class
+
+(class_declaration
+  "interface"
+  name: (identifier) @name
+  (delegation_specifiers
+    (delegation_specifier
+      (user_type) @extends))) @definition.interface
+
+(object_declaration
+  name: (identifier) @name
+  (delegation_specifiers
+    (delegation_specifier
+      (constructor_invocation
+        (user_type) @extends)))) @definition.class
+
+(object_declaration
   name: (identifier) @name
-  (enum_class_body)) @definition.enum
+  (delegation_specifiers
+    (delegation_specifier
+      [(user_type) @implements
+       (explicit_delegation
+         (user_type) @implements)]))) @definition.class
 
 ; ------------------------------------------------------------
-; Class (regular class, data class, sealed class etc.)
+; Class (regular class; modifiers other than visibility go to detail)
 ; ------------------------------------------------------------
 (class_declaration
   "class" @_kw
   name: (identifier) @name) @definition.class
 
+(class_declaration
+  (modifiers
+    [(class_modifier) (inheritance_modifier)]+ @detail)
+  "class" @_kw
+  name: (identifier) @name) @definition.class
+
 ; ------------------------------------------------------------
-; Object (singleton)
+; Interface (class_declaration with "interface" keyword)
+; ------------------------------------------------------------
+(class_declaration
+  "interface" @_kw
+  name: (identifier) @name) @definition.interface
+
+; sealed / fun interface
+(class_declaration
+  (modifiers
+    [(class_modifier) (inheritance_modifier)]+ @detail)
+  "interface" @_kw
+  name: (identifier) @name) @definition.interface
+
+; ------------------------------------------------------------
+; Enum / data / value class (keyword is in class_modifier)
+; ------------------------------------------------------------
+(class_declaration
+  (modifiers
+    (class_modifier) @_modifier)
+  name: (identifier) @name
+  (#eq? @_modifier "enum")) @definition.enum
+
+(class_declaration
+  (modifiers
+    (class_modifier) @_modifier)
+  name: (identifier) @name
+  (#eq? @_modifier "data")) @definition.record
+
+(class_declaration
+  (modifiers
+    (class_modifier) @_modifier)
+  name: (identifier) @name
+  (#eq? @_modifier "value")) @definition.struct
+
+; ------------------------------------------------------------
+; Object (singleton) / companion object
 ; ------------------------------------------------------------
 (object_declaration
   name: (identifier) @name) @definition.class
 
+(companion_object
+  "companion" @detail
+  name: (identifier) @name) @definition.class
+
+; 匿名 companion object 以关键字为名
+(companion_object
+  "companion" @name
+  !name) @definition.class
+
 ; ------------------------------------------------------------
 ; Enum entries
 ; ------------------------------------------------------------
@@ -41,7 +220,30 @@
 ; ------------------------------------------------------------
 ; Function
 ; ------------------------------------------------------------
+; 名称紧跟 fun 或类型参数 (有 receiver 的见下方扩展函数)
+(function_declaration
+  ["fun" (type_parameters)]
+  .
+  name: (identifier) @name) @definition.function
+
+(function_declaration
+  (modifiers
+    [(function_modifier) (inheritance_modifier) (member_modifier)]+ @detail)
+  ["fun" (type_parameters)]
+  .
+  name: (identifier) @name) @definition.function
+
+; Extension function (fun String.foo()): receiver 类型与函数名一起作为符号名
 (function_declaration
+  [(user_type) (nullable_type)] @name
+  .
+  name: (identifier) @name) @definition.function
+
+(function_declaration
+  (modifiers
+    [(function_modifier) (inheritance_modifier) (member_modifier)]+ @detail)
+  [(user_type) (nullable_type)] @name
+  .
   name: (identifier) @name) @definition.function
 
 ; ------------------------------------------------------------
@@ -51,6 +253,18 @@
   (variable_declaration
     (identifier) @name)) @definition.property
 
+(property_declaration
+  (modifiers
+    [(property_modifier) (inheritance_modifier) (member_modifier)]+ @detail)
+  (variable_declaration
+    (identifier) @name)) @definition.property
+
 ; Class parameter (constructor property)
 (class_parameter
   (identifier) @name) @definition.property
+
+; ------------------------------------------------------------
+; Type alias
+; ------------------------------------------------------------
+(type_alias
+  type: (identifier) @name) @definition.type
@@ -4,42 +4,24 @@
 
 ; ------------------------------------------------------------
 ; ATX Headings (# style)
+; 捕获整个 section，使下级标题与代码块嵌套在所属标题下
 ; ------------------------------------------------------------
-
-; H1
-(atx_heading
-  (atx_h1_marker)
-  heading_content: (inline) @name) @definition.module
-
-; H2
-(atx_heading
-  (atx_h2_marker)
-  heading_content: (inline) @name) @definition.class
-
-; H3-H6
-(atx_heading
-  [(atx_h3_marker) (atx_h4_marker) (atx_h5_marker) (atx_h6_marker)]
-  heading_content: (inline) @name) @definition.function
+(section
+  .
+  (atx_heading
+    heading_content: (inline) @name)) @definition.heading
 
 ; ------------------------------------------------------------
 ; Setext Headings (underline style)
+; setext 标题不单独成 section，只捕获标题本身
 ; ------------------------------------------------------------
-
-; H1 (=== underline)
-(setext_heading
-  heading_content: (_
-    (inline) @name)
-  (setext_h1_underline)) @definition.module
-
-; H2 (--- underline)
 (setext_heading
   heading_content: (_
-    (inline) @name)
-  (setext_h2_underline)) @definition.class
+    (inline) @name)) @definition.heading
 
 ; ------------------------------------------------------------
 ; Fenced Code Blocks
 ; ------------------------------------------------------------
 (fenced_code_block
   (info_string
-    (language) @name)) @definition.namespace
+    (language) @name)) @definition.code_block
@@ -12,7 +12,8 @@
 (class_interface
   "@interface" @_kw
   .
-  (identifier) @name) @definition.class
+  (identifier) @name
+  !category) @definition.class
 
 ; ------------------------------------------------------------
 ; Class Implementation (@implementation ClassName)
@@ -20,7 +21,57 @@
 (class_implementation
   "@implementation" @_kw
   .
-  (identifier) @name) @definition.class
+  (identifier) @name
+  !category) @definition.class
+
+; ------------------------------------------------------------
+; Category (@interface ClassName (Category))
+; 类别名放在 detail，聚合阶段归并到主类型
+; ------------------------------------------------------------
+(class_interface
+  "@interface" @_kw
+  .
+  (identifier) @name
+  category: (identifier) @detail) @definition.extension
+
+(class_implementation
+  "@implementation" @_kw
+  .
+  (identifier) @name
+  category: (identifier) @detail) @definition.extension
+
+; ------------------------------------------------------------
+; 继承: 父类为 @extends，<Protocol> 列表为 @implements (协议的父协议为 @extends)
+; ------------------------------------------------------------
+(class_interface
+  "@interface" @_kw
+  .
+  (identifier) @name
+  superclass: (identifier) @extends
+  !category) @definition.class
+
+(class_interface
+  "@interface" @_kw
+  .
+  (identifier) @name
+  (parameterized_arguments
+    (type_name) @implements)
+  !category) @definition.class
+
+(class_interface
+  "@interface" @_kw
+  .
+  (identifier) @name
+  category: (identifier) @detail
+  (parameterized_arguments
+    (type_name) @implements)) @definition.extension
+
+(protocol_declaration
+  "@protocol" @_kw
+  .
+  (identifier) @name
+  (protocol_reference_list
+    (identifier) @extends)) @definition.protocol
 
 ; ------------------------------------------------------------
 ; Protocol (@protocol ProtocolName)
@@ -28,7 +79,7 @@
 (protocol_declaration
   "@protocol" @_kw
   .
-  (identifier) @name) @definition.interface
+  (identifier) @name) @definition.protocol
 
 ; ------------------------------------------------------------
 ; Method Definition (- (type)methodName or + (type)methodName)
@@ -37,7 +88,12 @@
 ; ------------------------------------------------------------
 
 ; Simple method: - (void)doSomething
+; 选择子只取首段为方法名，后续段 (如 initWithName:email: 中的 email) 为参数标签，不是方法
 (method_definition
+  ["-" "+"]
+  .
+  (method_type)?
+  .
   (identifier) @name) @definition.method
 
 ; Method with keyword selector: - (id)initWithName:(NSString *)name
@@ -47,6 +103,10 @@
 
 ; Simple method declaration
 (method_declaration
+  ["-" "+"]
+  .
+  (method_type)?
+  .
   (identifier) @name) @definition.method
 
 ; Method declaration with keyword selector
@@ -54,6 +114,73 @@
   (keyword_declarator
     (identifier) @name)) @definition.method
 
+; 签名: +/- 与返回类型
+([(method_definition
+    ["-" "+"] @modifier
+    .
+    (method_type
+      (type_name) @returns)?
+    .
+    (identifier) @name)
+  (method_declaration
+    ["-" "+"] @modifier
+    .
+    (method_type
+      (type_name) @returns)?
+    .
+    (identifier) @name)] @definition.method)
+
+; 参数: 前一个选择子段为标签 - (void)move:(int)x to:(int)y
+(method_definition
+  ["-" "+"]
+  .
+  (method_type)?
+  .
+  (identifier) @name @param_label
+  .
+  (method_parameter
+    (method_type
+      (type_name) @param_type)
+    (identifier) @param)) @definition.method
+
+(method_definition
+  ["-" "+"]
+  .
+  (method_type)?
+  .
+  (identifier) @name
+  (identifier) @param_label
+  .
+  (method_parameter
+    (method_type
+      (type_name) @param_type)
+    (identifier) @param)) @definition.method
+
+(method_declaration
+  ["-" "+"]
+  .
+  (method_type)?
+  .
+  (identifier) @name @param_label
+  .
+  (method_parameter
+    (method_type
+      (type_name) @param_type)
+    (identifier) @param)) @definition.method
+
+(method_declaration
+  ["-" "+"]
+  .
+  (method_type)?
+  .
+  (identifier) @name
+  (identifier) @param_label
+  .
+  (method_parameter
+    (method_type
+      (type_name) @param_type)
+    (identifier) @param)) @definition.method
+
 ; ------------------------------------------------------------
 ; C Functions
 ; ------------------------------------------------------------
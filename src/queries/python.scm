@@ -3,11 +3,74 @@
 ; ============================================================
 
 ; ------------------------------------------------------------
-; 类定义
+; 类定义 (任意层级，含嵌套类)
 ; ------------------------------------------------------------
 (class_definition
   name: (identifier) @name) @definition.class
 
+; 基类 (metaclass 等关键字参数除外)
+(class_definition
+  name: (identifier) @name
+  superclasses: (argument_list
+    [(identifier) (attribute) (subscript)] @extends)) @definition.class
+
+; 类装饰器 (@dataclass 等)
+(decorated_definition
+  (decorator
+    (_) @attribute)+
+  definition: (class_definition
+    name: (identifier) @name) @definition.class)
+
+; ------------------------------------------------------------
+; 函数定义 (任意层级，含嵌套函数)
+; ------------------------------------------------------------
+(function_definition
+  name: (identifier) @name) @definition.function
+
+; 装饰器
+(decorated_definition
+  (decorator
+    (_) @attribute)+
+  definition: (function_definition
+    name: (identifier) @name) @definition.function)
+
+; ------------------------------------------------------------
+; 签名: 参数、返回类型与 async；@staticmethod 记为 static
+; ------------------------------------------------------------
+(function_definition
+  "async"? @modifier
+  name: (identifier) @name
+  parameters: (parameters) @parameters
+  return_type: (type)? @returns) @definition.function
+
+(function_definition
+  name: (identifier) @name
+  parameters: (parameters
+    [(identifier) @param
+     (typed_parameter
+       (identifier) @param
+       type: (type) @param_type)
+     (default_parameter
+       name: (identifier) @param
+       value: (_) @param_default)
+     (typed_default_parameter
+       name: (identifier) @param
+       type: (type) @param_type
+       value: (_) @param_default)
+     (list_splat_pattern) @param @param_variadic
+     (dictionary_splat_pattern) @param @param_variadic
+     (typed_parameter
+       [(list_splat_pattern) (dictionary_splat_pattern)] @param @param_variadic
+       type: (type) @param_type)])) @definition.function
+
+(decorated_definition
+  (decorator
+    (identifier) @modifier)
+  definition: (function_definition
+    name: (identifier) @name
+    parameters: (parameters) @parameters) @definition.function
+  (#eq? @modifier "staticmethod"))
+
 ; ------------------------------------------------------------
 ; 类变量 (类体内的赋值)
 ; ------------------------------------------------------------
@@ -17,40 +80,91 @@
       (assignment
         left: (identifier) @name)) @definition.variable))
 
+; 带类型注解的类属性 (dataclass / pydantic / attrs 字段)，注解记入 detail
+(class_definition
+  body: (block
+    (expression_statement
+      (assignment
+        left: (identifier) @name
+        type: (type) @detail)) @definition.field))
+
 ; ------------------------------------------------------------
-; 方法定义
+; 方法 (类体内的函数定义)
 ; ------------------------------------------------------------
-
-; 普通方法
 (class_definition
   body: (block
     (function_definition
       name: (identifier) @name) @definition.method))
 
-; 装饰器方法 (@classmethod, @staticmethod, @property 等)
 (class_definition
   body: (block
     (decorated_definition
-      (function_definition
+      (decorator
+        (_) @attribute)+
+      definition: (function_definition
         name: (identifier) @name) @definition.method)))
 
+; @property / @cached_property
+(class_definition
+  body: (block
+    (decorated_definition
+      (decorator
+        [(identifier) @_decorator
+         (attribute attribute: (identifier) @_decorator)])
+      definition: (function_definition
+        name: (identifier) @name) @definition.property)
+    (#any-of? @_decorator "property" "cached_property")))
+
 ; ------------------------------------------------------------
-; 函数定义 (顶层)
+; 模块级变量
 ; ------------------------------------------------------------
 (module
-  (function_definition
-    name: (identifier) @name) @definition.function)
+  (expression_statement
+    (assignment
+      left: (identifier) @name)) @definition.variable)
 
-; 装饰器函数
+; ------------------------------------------------------------
+; 类型别名
+; ------------------------------------------------------------
+; X: TypeAlias = ...
 (module
-  (decorated_definition
-    (function_definition
-      name: (identifier) @name) @definition.function))
+  (expression_statement
+    (assignment
+      left: (identifier) @name
+      type: (type
+        [(identifier) @_type
+         (attribute attribute: (identifier) @_type)])
+      (#eq? @_type "TypeAlias"))) @definition.type)
+
+; PEP 695: type X = ... / type X[T] = ...
+(type_alias_statement
+  left: (type
+    [(identifier) @name
+     (generic_type (identifier) @name)])) @definition.type
 
 ; ------------------------------------------------------------
-; 模块级变量/常量
+; __all__: 列出的顶层符号标记为导出
 ; ------------------------------------------------------------
 (module
   (expression_statement
-    (assignment
-      left: (identifier) @name)) @definition.variable)
+    [(assignment
+       left: (identifier) @_all
+       right: [(list (string (string_content) @export))
+               (tuple (string (string_content) @export))])
+     (augmented_assignment
+       left: (identifier) @_all
+       right: [(list (string (string_content) @export))
+               (tuple (string (string_content) @export))])])
+  (#eq? @_all "__all__"))
+
+; ------------------------------------------------------------
+; 入口点: if __name__ == "__main__":
+; ------------------------------------------------------------
+(module
+  (if_statement
+    condition: (comparison_operator
+      (identifier) @_dunder
+      (string
+        (string_content) @name))
+    (#eq? @_dunder "__name__")
+    (#eq? @name "__main__")) @entry.main)
@@ -8,12 +8,37 @@
 (class
   name: (constant) @name) @definition.class
 
+; 父类
+(class
+  name: (constant) @name
+  superclass: (superclass
+    (_) @extends)) @definition.class
+
+; include / prepend 的 mixin 记入 @implements
+(class
+  name: (constant) @name
+  body: (body_statement
+    (call
+      method: (identifier) @_include
+      arguments: (argument_list
+        [(constant) (scope_resolution)] @implements)))
+  (#any-of? @_include "include" "prepend")) @definition.class
+
 ; ------------------------------------------------------------
 ; 模块定义
 ; ------------------------------------------------------------
 (module
   name: (constant) @name) @definition.module
 
+(module
+  name: (constant) @name
+  body: (body_statement
+    (call
+      method: (identifier) @_include
+      arguments: (argument_list
+        [(constant) (scope_resolution)] @implements)))
+  (#any-of? @_include "include" "prepend")) @definition.module
+
 ; ------------------------------------------------------------
 ; 方法定义
 ; ------------------------------------------------------------
@@ -24,6 +49,44 @@
 (singleton_method
   name: (identifier) @name) @definition.method
 
+; 签名: 参数与默认值；`def self.x` 记为 static
+(method
+  name: (identifier) @name
+  parameters: (method_parameters)? @parameters) @definition.method
+
+(singleton_method
+  object: (self) @modifier
+  name: (identifier) @name
+  parameters: (method_parameters)? @parameters) @definition.method
+
+(method
+  name: (identifier) @name
+  parameters: (method_parameters
+    [(identifier) @param
+     (optional_parameter
+       name: (identifier) @param
+       value: (_) @param_default)
+     (keyword_parameter
+       name: (identifier) @param
+       value: (_)? @param_default)
+     (splat_parameter) @param @param_variadic
+     (hash_splat_parameter) @param @param_variadic
+     (block_parameter) @param])) @definition.method
+
+(singleton_method
+  name: (identifier) @name
+  parameters: (method_parameters
+    [(identifier) @param
+     (optional_parameter
+       name: (identifier) @param
+       value: (_) @param_default)
+     (keyword_parameter
+       name: (identifier) @param
+       value: (_)? @param_default)
+     (splat_parameter) @param @param_variadic
+     (hash_splat_parameter) @param @param_variadic
+     (block_parameter) @param])) @definition.method
+
 ; ------------------------------------------------------------
 ; 类变量 (@@var)
 ; ------------------------------------------------------------
@@ -43,16 +106,94 @@
   left: (constant) @name) @definition.constant
 
 ; ------------------------------------------------------------
-; attr_accessor 等
+; DSL 调用 (attr_accessor、has_many、scope、before_action 等)
+; 方法名须在 DSL catalog 中，类型由 catalog 决定；每个 symbol 参数生成一个符号
 ; ------------------------------------------------------------
 (call
-  method: (identifier) @_method
+  method: (identifier) @dsl
+  arguments: (argument_list
+    (simple_symbol) @name @definition.property)
+  (#not-any-of? @dsl "alias_method" "validates" "validate"))
+
+; alias_method :new_name, :old_name 只取第一个参数
+(call
+  method: (identifier) @dsl
+  arguments: (argument_list
+    .
+    (simple_symbol) @name @definition.method)
+  (#eq? @dsl "alias_method"))
+
+; 校验 (validates :title, :body, presence: true) 每次调用一个符号，名称为属性列表
+(call
+  method: (identifier) @dsl
   arguments: (argument_list
-    (simple_symbol) @name) @definition.property
-  (#match? @_method "^attr_(reader|writer|accessor)$"))
+    .
+    (simple_symbol) @name
+    (simple_symbol)* @name)
+  (#any-of? @dsl "validates" "validate")) @definition.validation
+
+; 无参数的块调用 (included do ... end)，块内的 DSL 调用嵌套其下
+(call
+  method: (identifier) @dsl @name
+  !arguments
+  block: (do_block)) @definition.callback
+
+; 测试块 (RSpec describe / context / it，Rails test "..." do)，描述字符串或类名为符号名
+(call
+  method: (identifier) @dsl
+  arguments: (argument_list
+    .
+    [(string
+       (string_content) @name)
+     (constant) @name
+     (scope_resolution) @name])
+  block: [(do_block) (block)]) @definition.test
 
 ; ------------------------------------------------------------
 ; 别名方法
 ; ------------------------------------------------------------
 (alias
   name: (simple_symbol) @name) @definition.method
+
+; ------------------------------------------------------------
+; Rails 路由 (只在 routes.rb / routes/ 下生效):
+; get "/users", to: "users#index"、post "login" => "sessions#create"、resources :photos
+; ------------------------------------------------------------
+(call
+  method: (identifier) @http_method
+  arguments: (argument_list
+    .
+    (string
+      (string_content) @name))
+  (#any-of? @http_method "get" "post" "put" "patch" "delete" "match")) @entry.route
+
+(call
+  method: (identifier) @http_method
+  arguments: (argument_list
+    .
+    (string
+      (string_content) @name)
+    (pair
+      key: (hash_key_symbol) @_to
+      value: (string
+        (string_content) @handler)))
+  (#any-of? @http_method "get" "post" "put" "patch" "delete" "match")
+  (#eq? @_to "to")) @entry.route
+
+(call
+  method: (identifier) @http_method
+  arguments: (argument_list
+    .
+    (pair
+      key: (string
+        (string_content) @name)
+      value: (string
+        (string_content) @handler)))
+  (#any-of? @http_method "get" "post" "put" "patch" "delete" "match")) @entry.route
+
+(call
+  method: (identifier) @http_method
+  arguments: (argument_list
+    .
+    (simple_symbol) @name)
+  (#any-of? @http_method "resources" "resource")) @entry.route
@@ -2,12 +2,157 @@
 ; Rust Codemap Query
 ; ============================================================
 
+; ------------------------------------------------------------
+; 属性 (#[derive] / #[test] / #[cfg] 等): 为条目之前的兄弟节点，
+; 放在最前面，类型由后续模式决定
+; ------------------------------------------------------------
+((attribute_item
+   (attribute) @attribute)
+  .
+  (attribute_item)*
+  .
+  (function_item
+    name: (identifier) @name) @definition.function)
+
+((attribute_item
+   (attribute) @attribute)
+  .
+  (attribute_item)*
+  .
+  (struct_item
+    name: (type_identifier) @name) @definition.struct)
+
+((attribute_item
+   (attribute) @attribute)
+  .
+  (attribute_item)*
+  .
+  (enum_item
+    name: (type_identifier) @name) @definition.enum)
+
+((attribute_item
+   (attribute) @attribute)
+  .
+  (attribute_item)*
+  .
+  (trait_item
+    name: (type_identifier) @name) @definition.trait)
+
+((attribute_item
+   (attribute) @attribute)
+  .
+  (attribute_item)*
+  .
+  (mod_item
+    name: (identifier) @name) @definition.module)
+
+((attribute_item
+   (attribute) @attribute)
+  .
+  (attribute_item)*
+  .
+  (macro_definition
+    name: (identifier) @name) @definition.macro)
+
+((attribute_item
+   (attribute) @attribute)
+  .
+  (attribute_item)*
+  .
+  (field_declaration
+    name: (field_identifier) @name) @definition.field)
+
+((attribute_item
+   (attribute) @attribute)
+  .
+  (attribute_item)*
+  .
+  (enum_variant
+    name: (identifier) @name) @definition.enum_member)
+
+; ------------------------------------------------------------
+; 泛型参数与约束 (含 where 子句): 归入所在条目 (函数、类型、trait、impl) 的符号
+; ------------------------------------------------------------
+(_
+  type_parameters: (type_parameters
+    [(type_parameter
+       name: (type_identifier) @type_param)
+     (type_parameter
+       name: (type_identifier) @type_param
+       bounds: (trait_bounds
+         (_) @type_bound))
+     (lifetime_parameter
+       name: (lifetime) @type_param)
+     (const_parameter
+       name: (identifier) @type_param
+       type: (_) @type_bound)])) @symbol
+
+(_
+  (where_clause
+    (where_predicate
+      left: (type_identifier) @type_param
+      bounds: (trait_bounds
+        (_) @type_bound)))) @symbol
+
+; ------------------------------------------------------------
+; 签名: 参数、返回类型与 const / async / unsafe 修饰
+; ------------------------------------------------------------
+(function_item
+  (function_modifiers)? @modifier
+  name: (identifier) @name
+  parameters: (parameters) @parameters
+  return_type: (_)? @returns) @definition.function
+
+(function_item
+  name: (identifier) @name
+  parameters: (parameters
+    [(parameter
+       pattern: _ @param
+       type: (_) @param_type)
+     (self_parameter
+       (self) @param) @param_type
+     (variadic_parameter) @param @param_variadic])) @definition.function
+
+(trait_item
+  body: (declaration_list
+    (function_signature_item
+      (function_modifiers)? @modifier
+      name: (identifier) @name
+      parameters: (parameters) @parameters
+      return_type: (_)? @returns) @definition.method))
+
+(trait_item
+  body: (declaration_list
+    (function_signature_item
+      name: (identifier) @name
+      parameters: (parameters
+        [(parameter
+           pattern: _ @param
+           type: (_) @param_type)
+         (self_parameter
+       (self) @param) @param_type
+         (variadic_parameter) @param @param_variadic])) @definition.method))
+
 ; ------------------------------------------------------------
 ; 函数定义
 ; ------------------------------------------------------------
 (function_item
+  (visibility_modifier)? @visibility
   name: (identifier) @name) @definition.function
 
+; ------------------------------------------------------------
+; impl 块: 名称为实现类型，trait 记入 @implements (固有 impl 无)
+; ------------------------------------------------------------
+(impl_item
+  trait: (_)? @implements
+  type: [
+    (type_identifier) @name
+    (generic_type
+      type: (type_identifier) @name)
+    (scoped_type_identifier
+      name: (type_identifier) @name)
+  ]) @definition.impl
+
 ; ------------------------------------------------------------
 ; 方法定义 (impl 块内)
 ; ------------------------------------------------------------
@@ -20,16 +165,19 @@
 ; 结构体定义
 ; ------------------------------------------------------------
 (struct_item
-  name: (type_identifier) @name) @definition.class
+  (visibility_modifier)? @visibility
+  name: (type_identifier) @name) @definition.struct
 
 ; Struct 字段
 (field_declaration
+  (visibility_modifier)? @visibility
   name: (field_identifier) @name) @definition.field
 
 ; ------------------------------------------------------------
 ; 枚举定义
 ; ------------------------------------------------------------
 (enum_item
+  (visibility_modifier)? @visibility
   name: (type_identifier) @name) @definition.enum
 
 ; 枚举 variants
@@ -40,7 +188,14 @@
 ; Trait 定义
 ; ------------------------------------------------------------
 (trait_item
-  name: (type_identifier) @name) @definition.interface
+  (visibility_modifier)? @visibility
+  name: (type_identifier) @name) @definition.trait
+
+; Supertrait (trait Foo: Bar + Baz)
+(trait_item
+  name: (type_identifier) @name
+  bounds: (trait_bounds
+    [(type_identifier) (generic_type) (scoped_type_identifier)] @extends)) @definition.trait
 
 ; Trait 方法签名
 (trait_item
@@ -58,28 +213,32 @@
 ; 类型别名
 ; ------------------------------------------------------------
 (type_item
+  (visibility_modifier)? @visibility
   name: (type_identifier) @name) @definition.type
 
 ; ------------------------------------------------------------
 ; 常量定义
 ; ------------------------------------------------------------
 (const_item
+  (visibility_modifier)? @visibility
   name: (identifier) @name) @definition.constant
 
 ; ------------------------------------------------------------
 ; 静态变量
 ; ------------------------------------------------------------
 (static_item
+  (visibility_modifier)? @visibility
   name: (identifier) @name) @definition.variable
 
 ; ------------------------------------------------------------
 ; 模块定义
 ; ------------------------------------------------------------
 (mod_item
+  (visibility_modifier)? @visibility
   name: (identifier) @name) @definition.module
 
 ; ------------------------------------------------------------
 ; 宏定义
 ; ------------------------------------------------------------
 (macro_definition
-  name: (identifier) @name) @definition.function
+  name: (identifier) @name) @definition.macro
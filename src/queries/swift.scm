@@ -2,6 +2,24 @@
 ; Swift Codemap Query
 ; ============================================================
 
+; ------------------------------------------------------------
+; 泛型参数与约束 (<T: Bound>、where T: Other): 归入所在的类型 / 函数 / init
+; ------------------------------------------------------------
+(_
+  (type_parameters
+    [(type_parameter
+       (type_identifier) @type_param)
+     (type_parameter
+       (type_identifier) @type_param
+       name: (_) @type_bound)])) @symbol
+
+(_
+  (type_constraints
+    (type_constraint
+      (inheritance_constraint
+        constrained_type: (_) @type_param
+        name: (_) @type_bound)))) @symbol
+
 ; ------------------------------------------------------------
 ; Class, Struct, Enum, Extension (unified in class_declaration)
 ; declaration_kind: class | struct | enum | extension | actor
@@ -12,31 +30,155 @@
   declaration_kind: "class"
   name: (_) @name) @definition.class
 
-; Struct (mapped to class)
+; Struct
 (class_declaration
   declaration_kind: "struct"
-  name: (_) @name) @definition.class
+  name: (_) @name) @definition.struct
 
 ; Enum
 (class_declaration
   declaration_kind: "enum"
   name: (_) @name) @definition.enum
 
-; Extension (namespace)
+; Extension
 (class_declaration
   declaration_kind: "extension"
-  name: (_) @name) @definition.namespace
+  name: (_) @name) @definition.extension
 
 ; Actor (mapped to class)
 (class_declaration
   declaration_kind: "actor"
   name: (_) @name) @definition.class
 
+; ------------------------------------------------------------
+; 继承: 语法上无法区分父类与协议，class 的首个父类型视为父类 (Swift 要求父类写在最前)，
+; 其余以及 struct / enum / actor / extension 的父类型均为协议
+; ------------------------------------------------------------
+(class_declaration
+  declaration_kind: "class"
+  name: (_) @name
+  .
+  (type_parameters)?
+  .
+  (inheritance_specifier
+    inherits_from: (_) @extends)) @definition.class
+
+(class_declaration
+  declaration_kind: "class"
+  name: (_) @name
+  (inheritance_specifier)
+  .
+  (inheritance_specifier
+    inherits_from: (_) @implements)) @definition.class
+
+(class_declaration
+  declaration_kind: "struct"
+  name: (_) @name
+  (inheritance_specifier
+    inherits_from: (_) @implements)) @definition.struct
+
+(class_declaration
+  declaration_kind: "enum"
+  name: (_) @name
+  (inheritance_specifier
+    inherits_from: (_) @implements)) @definition.enum
+
+(class_declaration
+  declaration_kind: "extension"
+  name: (_) @name
+  (inheritance_specifier
+    inherits_from: (_) @implements)) @definition.extension
+
+(class_declaration
+  declaration_kind: "actor"
+  name: (_) @name
+  (inheritance_specifier
+    inherits_from: (_) @implements)) @definition.class
+
+(protocol_declaration
+  name: (type_identifier) @name
+  (inheritance_specifier
+    inherits_from: (_) @extends)) @definition.protocol
+
+; ------------------------------------------------------------
+; 签名: 参数直接挂在声明节点下，外部参数名记为 label；返回类型为 `->` 之后的 name 字段
+; ------------------------------------------------------------
+(function_declaration
+  (modifiers)? @modifier
+  "func" @parameters
+  name: (simple_identifier) @name
+  "async"? @modifier) @definition.function
+
+(function_declaration
+  name: (simple_identifier) @name
+  "->"
+  .
+  name: (_) @returns) @definition.function
+
+(init_declaration
+  (modifiers)? @modifier
+  name: "init" @name @parameters
+  "async"? @modifier) @definition.method
+
+(function_declaration
+  name: (simple_identifier) @name
+  (parameter
+    name: (simple_identifier) @param
+    name: (_) @param_type)) @definition.function
+
+(function_declaration
+  name: (simple_identifier) @name
+  (parameter
+    external_name: (_) @param_label
+    name: (simple_identifier) @param)) @definition.function
+
+(function_declaration
+  name: (simple_identifier) @name
+  (parameter
+    name: (simple_identifier) @param
+    "..." @param_variadic)) @definition.function
+
+(function_declaration
+  name: (simple_identifier) @name
+  (parameter
+    name: (simple_identifier) @param)
+  .
+  "="
+  .
+  default_value: (_) @param_default) @definition.function
+
+(init_declaration
+  name: "init" @name
+  (parameter
+    name: (simple_identifier) @param
+    name: (_) @param_type)) @definition.method
+
+(init_declaration
+  name: "init" @name
+  (parameter
+    external_name: (_) @param_label
+    name: (simple_identifier) @param)) @definition.method
+
+(init_declaration
+  name: "init" @name
+  (parameter
+    name: (simple_identifier) @param
+    "..." @param_variadic)) @definition.method
+
+(init_declaration
+  name: "init" @name
+  (parameter
+    name: (simple_identifier) @param)
+  .
+  "="
+  .
+  default_value: (_) @param_default) @definition.method
+
 ; ------------------------------------------------------------
 ; Protocol
 ; ------------------------------------------------------------
 (protocol_declaration
-  name: (type_identifier) @name) @definition.interface
+  name: (type_identifier) @name) @definition.protocol
 
 ; ------------------------------------------------------------
 ; Enum case
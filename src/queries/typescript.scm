@@ -2,6 +2,85 @@
 ; TypeScript Codemap Query
 ; ============================================================
 
+; ------------------------------------------------------------
+; 装饰器 (@Component / @Input 等): 放在最前面，类型由后续模式决定
+; ------------------------------------------------------------
+(class_declaration
+  decorator: (decorator) @attribute
+  name: (type_identifier) @name) @definition.class
+
+(abstract_class_declaration
+  decorator: (decorator) @attribute
+  name: (type_identifier) @name) @definition.class
+
+; 导出类的装饰器在 export 语句上
+(export_statement
+  decorator: (decorator) @attribute
+  declaration: [
+    (class_declaration
+      name: (type_identifier) @name)
+    (abstract_class_declaration
+      name: (type_identifier) @name)
+  ] @definition.class)
+
+; 方法装饰器为方法之前的兄弟节点
+(class_body
+  (decorator) @attribute
+  .
+  (decorator)*
+  .
+  (method_definition
+    name: (property_identifier) @name) @definition.method)
+
+(public_field_definition
+  decorator: (decorator) @attribute
+  name: (property_identifier) @name) @definition.field
+
+; ------------------------------------------------------------
+; 继承: extends 记入 @extends，implements 记入 @implements
+; ------------------------------------------------------------
+(class_declaration
+  name: (type_identifier) @name
+  (class_heritage
+    (extends_clause
+      value: (_) @extends))) @definition.class
+
+(class_declaration
+  name: (type_identifier) @name
+  (class_heritage
+    (implements_clause
+      (_) @implements))) @definition.class
+
+(abstract_class_declaration
+  name: (type_identifier) @name
+  (class_heritage
+    (extends_clause
+      value: (_) @extends))) @definition.class
+
+(abstract_class_declaration
+  name: (type_identifier) @name
+  (class_heritage
+    (implements_clause
+      (_) @implements))) @definition.class
+
+(interface_declaration
+  name: (type_identifier) @name
+  (extends_type_clause
+    type: (_) @extends)) @definition.interface
+
+; ------------------------------------------------------------
+; 泛型参数与约束 (T extends Bound): 归入所在的类型 / 函数，箭头函数按参数列表归入所在的变量声明
+; ------------------------------------------------------------
+(_
+  type_parameters: (type_parameters
+    [(type_parameter
+       name: (type_identifier) @type_param)
+     (type_parameter
+       name: (type_identifier) @type_param
+       constraint: (constraint
+         (_) @type_bound))])
+  parameters: (formal_parameters)? @parameters) @symbol
+
 ; ------------------------------------------------------------
 ; 类定义
 ; ------------------------------------------------------------
@@ -36,6 +115,11 @@
 (abstract_method_signature
   name: (property_identifier) @name) @definition.method
 
+; 方法重载签名 (类体、declare class)
+(class_body
+  (method_signature
+    name: (property_identifier) @name) @definition.method @overload)
+
 ; getter/setter
 (method_definition
   name: (property_identifier) @name) @definition.method
@@ -56,7 +140,7 @@
 (interface_declaration
   body: (interface_body
     (method_signature
-      name: (property_identifier) @name) @definition.method))
+      name: (property_identifier) @name) @definition.method @overload))
 
 ; ------------------------------------------------------------
 ; Type Alias
@@ -95,6 +179,87 @@
   (function_declaration
     name: (identifier) @name) @definition.function)
 
+; 函数签名 (重载、declare function)，相邻同名签名归并到实现
+(function_signature
+  name: (identifier) @name) @definition.function @overload
+
+; ------------------------------------------------------------
+; 签名: 参数、返回类型与 async / static
+; ------------------------------------------------------------
+(method_definition
+  "static"? @modifier
+  "async"? @modifier
+  name: [(property_identifier) (private_property_identifier)] @name
+  parameters: (formal_parameters) @parameters
+  return_type: (type_annotation
+    (_) @returns)?) @definition.method
+
+(method_signature
+  "static"? @modifier
+  "async"? @modifier
+  name: (property_identifier) @name
+  parameters: (formal_parameters) @parameters
+  return_type: (type_annotation
+    (_) @returns)?) @definition.method
+
+(abstract_method_signature
+  name: (property_identifier) @name
+  parameters: (formal_parameters) @parameters
+  return_type: (type_annotation
+    (_) @returns)?) @definition.method
+
+(function_declaration
+  "async"? @modifier
+  name: (identifier) @name
+  parameters: (formal_parameters) @parameters
+  return_type: (type_annotation
+    (_) @returns)?) @definition.function
+
+(function_signature
+  "async"? @modifier
+  name: (identifier) @name
+  parameters: (formal_parameters) @parameters
+  return_type: (type_annotation
+    (_) @returns)?) @definition.function
+
+(generator_function_declaration
+  "async"? @modifier
+  name: (identifier) @name
+  parameters: (formal_parameters) @parameters
+  return_type: (type_annotation
+    (_) @returns)?) @definition.function
+
+(lexical_declaration
+  (variable_declarator
+    name: (identifier) @name
+    value: (arrow_function
+      "async"? @modifier
+      parameters: (formal_parameters) @parameters
+      return_type: (type_annotation
+        (_) @returns)?))) @definition.function
+
+(lexical_declaration
+  (variable_declarator
+    name: (identifier) @name
+    value: (arrow_function
+      "async"? @modifier
+      parameter: (identifier) @param))) @definition.function
+
+; 参数: 按参数列表归入所属函数 / 方法
+(formal_parameters
+  [(required_parameter
+     pattern: (_) @param
+     type: (type_annotation
+       (_) @param_type)?
+     value: (_)? @param_default)
+   (required_parameter
+     pattern: (rest_pattern) @param @param_variadic)
+   (optional_parameter
+     pattern: (_) @param
+     type: (type_annotation
+       (_) @param_type)?
+     value: (_)? @param_default)]) @parameters
+
 ; ------------------------------------------------------------
 ; 变量/常量
 ; ------------------------------------------------------------
@@ -103,3 +268,108 @@
     name: (identifier) @name
     value: (_) @_val) @definition.variable
   (#not-match? @_val "^\\("))
+
+; 无初始值的声明 (declare const / let x: T)
+(lexical_declaration
+  (variable_declarator
+    name: (identifier) @name
+    !value)) @definition.variable
+
+(variable_declaration
+  (variable_declarator
+    name: (identifier) @name
+    !value)) @definition.variable
+
+; ------------------------------------------------------------
+; 模块与命名空间
+; ------------------------------------------------------------
+
+; declare module "x" { ... }
+(module
+  name: (string
+    (string_fragment) @name)) @definition.module
+
+; namespace A.B { ... } / module A { ... }
+(internal_module
+  name: [(identifier) (nested_identifier)] @name) @definition.namespace
+
+(module
+  name: [(identifier) (nested_identifier)] @name) @definition.namespace
+
+; declare global { ... }
+(ambient_declaration
+  "global" @name
+  (statement_block)) @definition.namespace
+
+; ------------------------------------------------------------
+; 导出
+; ------------------------------------------------------------
+
+; export default 匿名函数/类，以 default 为名
+(export_statement
+  "default" @name @export
+  value: [(function_expression !name) (arrow_function)] @definition.function)
+
+(export_statement
+  "default" @name @export
+  value: (class !name) @definition.class)
+
+; export 声明、export { a, b }、export default X / export = X: 同名顶层符号标记为导出
+(export_statement
+  declaration: (_
+    name: (_) @export))
+
+(export_statement
+  declaration: (lexical_declaration
+    (variable_declarator
+      name: (identifier) @export)))
+
+(export_statement
+  (export_clause
+    (export_specifier
+      name: (identifier) @export)))
+
+(export_statement
+  (identifier) @export)
+
+; ------------------------------------------------------------
+; 测试块 (describe / it / test，含 describe.only 等)
+; 方法名须在 DSL catalog 中，描述字符串为符号名
+; ------------------------------------------------------------
+(call_expression
+  function: [(identifier) @dsl
+             (member_expression
+               object: (identifier) @dsl)]
+  arguments: (arguments
+    .
+    (string
+      (string_fragment) @name)
+    [(arrow_function) (function_expression)])) @definition.test
+
+; ------------------------------------------------------------
+; HTTP 路由 (Express / Koa Router: app.get("/path", ..., handler))
+; 最后一个参数为标识符或成员访问时记为 handler
+; ------------------------------------------------------------
+(call_expression
+  function: (member_expression
+    object: (identifier) @_router
+    property: (property_identifier) @http_method)
+  arguments: (arguments
+    .
+    (string
+      (string_fragment) @name))
+  (#match? @_router "^(app|api|server|router|[a-zA-Z]+Router)$")
+  (#any-of? @http_method "get" "post" "put" "delete" "patch" "head" "options" "all")) @entry.route
+
+(call_expression
+  function: (member_expression
+    object: (identifier) @_router
+    property: (property_identifier) @http_method)
+  arguments: (arguments
+    .
+    (string
+      (string_fragment) @name)
+    [(identifier) (member_expression)] @handler
+    .)
+  (#match? @_router "^(app|api|server|router|[a-zA-Z]+Router)$")
+  (#any-of? @http_method "get" "post" "put" "delete" "patch" "head" "options" "all")) @entry.route
@@ -0,0 +1,554 @@
+// ============================================================
+// Query: Tree-sitter 语法与查询加载 (内置 + 用户覆盖)
+// ============================================================
+
+use crate::detector::Language;
+use crate::symbol::{EntryKind, SymbolKind};
+use anyhow::{anyhow, Context, Result};
+use std::borrow::Cow;
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+use tree_sitter::Query;
+
+/// 首行为此注释时，用户查询追加到内置查询之后，否则整体替换
+const EXTENDS_MARKER: &str = "; extends";
+
+/// 定义 capture 前缀: `@definition.<kind>`，kind 为 SymbolKind::as_str()
+const DEFINITION_PREFIX: &str = "definition.";
+
+/// 入口点 capture 前缀: `@entry.<kind>`，kind 为 EntryKind::as_str()
+const ENTRY_PREFIX: &str = "entry.";
+
+/// capture 的作用
+///
+/// | capture | 作用 |
+/// |---------|------|
+/// | `@name` | 符号名 (多个时取首尾之间的源码) |
+/// | `@definition.<kind>` | 符号节点 (范围)，kind 见 SymbolKind::as_str() |
+/// | `@symbol` | 所属符号的节点 (没有 `@definition.<kind>` 的匹配)，本匹配的泛型参数 / 参数归入以该节点为 `@definition.<kind>` 的符号 |
+/// | `@detail` | 附加说明 (签名、修饰符等) |
+/// | `@doc` | 文档注释，多个节点按行拼接 |
+/// | `@visibility` | 可见性修饰符 |
+/// | `@type_param` / `@type_bound` | 泛型参数名 / 约束，同一匹配中的约束属于该匹配的参数 |
+/// | `@parameters` | 参数列表节点，有此 capture 的符号带签名 (即使没有参数)；没有 `@definition.*` 的参数 / 返回类型匹配归入以该列表为参数列表的符号 |
+/// | `@param` / `@param_label` / `@param_type` / `@param_default` / `@param_variadic` | 参数名 / 调用标签 / 类型 / 默认值 / 可变参数标记，同一匹配中的类型等属于该匹配的参数；只有类型时参数名为空 |
+/// | `@param_declaration` | C 风格参数声明，参数名取声明符中的标识符，类型为去掉参数名后的源码 (`int (*)(int)`) |
+/// | `@returns` | 返回类型 (多个时取首尾之间的源码) |
+/// | `@modifier` | 修饰符，其中的 async / static / const / unsafe 记为签名标记 |
+/// | `@extends` / `@implements` | 父类型 / 实现的接口，每个 capture 一项 |
+/// | `@attribute` | 注解 / 装饰器 / 属性，每个 capture 记为一项 |
+/// | `@owner` | 所属类型名 (成员定义在类型体之外时) |
+/// | `@qualified_name` | 限定名 (`A::B::name`)，末段为符号名，其余为 owner |
+/// | `@dsl` | DSL 方法名，符号类型由 DSL catalog 决定，不在 catalog 中的匹配忽略 |
+/// | `@overload` | 重载签名 (与 `@definition.*` 同节点)，相邻同名签名归并到实现或首个签名 |
+/// | `@export` | 导出名 (如 Python `__all__` 中的字符串)，同名顶层符号标记为导出 |
+/// | `@entry.<kind>` | 入口点节点 (行号)，kind 见 EntryKind::as_str()；`@name` 为路径 / 命令名 |
+/// | `@http_method` / `@handler` | 路由的 HTTP 方法 / 处理函数 |
+/// | `@_*` | 辅助 capture (谓词用)，忽略 |
+///
+/// 同一符号 (同名同行) 被多个模式匹配时靠后的模式优先: 类型与范围取靠后的模式，
+/// 其余匹配只补充缺失的元数据。因此查询文件中通用模式在前、具体模式在后。
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum CaptureRole {
+    Name,
+    Definition(SymbolKind),
+    Fragment,
+    Detail,
+    Doc,
+    Visibility,
+    TypeParam,
+    TypeBound,
+    Parameters,
+    Param,
+    ParamDeclaration,
+    ParamLabel,
+    ParamType,
+    ParamDefault,
+    ParamVariadic,
+    Returns,
+    Modifier,
+    Extends,
+    Implements,
+    Attribute,
+    Owner,
+    QualifiedName,
+    Dsl,
+    Overload,
+    Export,
+    Entry(EntryKind),
+    HttpMethod,
+    Handler,
+    Helper,
+}
+
+/// 解析 capture 名，未知名称返回 None
+pub fn capture_role(name: &str) -> Option<CaptureRole> {
+    if name.starts_with('_') {
+        return Some(CaptureRole::Helper);
+    }
+    if let Some(kind) = name.strip_prefix(DEFINITION_PREFIX) {
+        return SymbolKind::from_name(kind).map(CaptureRole::Definition);
+    }
+    if let Some(kind) = name.strip_prefix(ENTRY_PREFIX) {
+        return EntryKind::from_name(kind).map(CaptureRole::Entry);
+    }
+    match name {
+        "name" => Some(CaptureRole::Name),
+        "symbol" => Some(CaptureRole::Fragment),
+        "detail" => Some(CaptureRole::Detail),
+        "doc" => Some(CaptureRole::Doc),
+        "visibility" => Some(CaptureRole::Visibility),
+        "type_param" => Some(CaptureRole::TypeParam),
+        "type_bound" => Some(CaptureRole::TypeBound),
+        "parameters" => Some(CaptureRole::Parameters),
+        "param" => Some(CaptureRole::Param),
+        "param_declaration" => Some(CaptureRole::ParamDeclaration),
+        "param_label" => Some(CaptureRole::ParamLabel),
+        "param_type" => Some(CaptureRole::ParamType),
+        "param_default" => Some(CaptureRole::ParamDefault),
+        "param_variadic" => Some(CaptureRole::ParamVariadic),
+        "returns" => Some(CaptureRole::Returns),
+        "modifier" => Some(CaptureRole::Modifier),
+        "extends" => Some(CaptureRole::Extends),
+        "implements" => Some(CaptureRole::Implements),
+        "attribute" => Some(CaptureRole::Attribute),
+        "owner" => Some(CaptureRole::Owner),
+        "qualified_name" => Some(CaptureRole::QualifiedName),
+        "dsl" => Some(CaptureRole::Dsl),
+        "overload" => Some(CaptureRole::Overload),
+        "export" => Some(CaptureRole::Export),
+        "http_method" => Some(CaptureRole::HttpMethod),
+        "handler" => Some(CaptureRole::Handler),
+        _ => None,
+    }
+}
+
+/// 用户提供的查询文件
+struct UserQuery {
+    path: PathBuf,
+    source: String,
+    extends: bool,
+}
+
+/// 所有语言的查询来源
+#[derive(Default)]
+pub struct Queries {
+    overrides: HashMap<&'static str, UserQuery>,
+    /// 用户 DSL catalog (方法名 → 符号类型)，覆盖内置项
+    dsl: HashMap<Language, HashMap<String, SymbolKind>>,
+}
+
+impl Queries {
+    /// 仅使用内置查询
+    pub fn builtin() -> Self {
+        Self::default()
+    }
+
+    /// 从目录加载 `<lang>.scm` 覆盖文件，并立即编译校验
+    pub fn load(dir: &Path) -> Result<Self> {
+        if !dir.is_dir() {
+            return Err(anyhow!("Queries directory not found: {}", dir.display()));
+        }
+
+        let mut overrides = HashMap::new();
+        for lang in Language::ALL {
+            let name = query_name(lang);
+            if overrides.contains_key(name) {
+                continue;
+            }
+
+            let path = dir.join(format!("{}.scm", name));
+            if !path.is_file() {
+                continue;
+            }
+
+            let source = std::fs::read_to_string(&path)
+                .with_context(|| format!("Failed to read query: {}", path.display()))?;
+            let extends = source
+                .lines()
+                .next()
+                .is_some_and(|line| line.trim() == EXTENDS_MARKER);
+            overrides.insert(
+                name,
+                UserQuery {
+                    path,
+                    source,
+                    extends,
+                },
+            );
+        }
+
+        let queries = Self {
+            overrides,
+            ..Self::default()
+        };
+        queries.validate()?;
+        Ok(queries)
+    }
+
+    /// 追加用户 DSL catalog
+    pub fn with_dsl(mut self, dsl: HashMap<Language, HashMap<String, SymbolKind>>) -> Self {
+        self.dsl = dsl;
+        self
+    }
+
+    /// DSL 方法对应的符号类型，用户 catalog 优先
+    pub fn dsl_kind(&self, lang: &Language, method: &str) -> Option<SymbolKind> {
+        self.dsl
+            .get(lang)
+            .and_then(|catalog| catalog.get(method))
+            .copied()
+            .or_else(|| {
+                builtin_dsl(lang)
+                    .iter()
+                    .find(|(name, _)| *name == method)
+                    .map(|(_, kind)| *kind)
+            })
+    }
+
+    /// 获取某语言最终使用的查询源码
+    pub fn source(&self, lang: &Language) -> Cow<'static, str> {
+        let builtin = builtin_source(lang);
+        match self.overrides.get(query_name(lang)) {
+            Some(user) if user.extends => Cow::Owned(format!("{}\n{}", builtin, user.source)),
+            Some(user) => Cow::Owned(user.source.clone()),
+            None => Cow::Borrowed(builtin),
+        }
+    }
+
+    /// 编译某语言的查询，并校验所有 capture 名
+    pub fn compile(&self, lang: &Language) -> Result<Query> {
+        let query = Query::new(&get_language(lang), &self.source(lang))?;
+        if let Some(name) = unknown_capture(&query) {
+            return Err(anyhow!(
+                "Unknown capture @{} in {} query",
+                name,
+                query_name(lang)
+            ));
+        }
+        Ok(query)
+    }
+
+    /// 单独编译每个用户文件，使错误行号对应用户文件本身
+    fn validate(&self) -> Result<()> {
+        for lang in Language::ALL {
+            let Some(user) = self.overrides.get(query_name(lang)) else {
+                continue;
+            };
+            let query = Query::new(&get_language(lang), &user.source).map_err(|e| {
+                anyhow!(
+                    "{}:{}:{}: invalid query ({:?}): {}",
+                    user.path.display(),
+                    e.row + 1,
+                    e.column + 1,
+                    e.kind,
+                    e.message.trim()
+                )
+            })?;
+            if let Some(name) = unknown_capture(&query) {
+                return Err(anyhow!(
+                    "{}:{}: unknown capture @{}",
+                    user.path.display(),
+                    capture_line(&user.source, name),
+                    name
+                ));
+            }
+        }
+        Ok(())
+    }
+}
+
+fn unknown_capture(query: &Query) -> Option<&str> {
+    query
+        .capture_names()
+        .iter()
+        .copied()
+        .find(|name| capture_role(name).is_none())
+}
+
+/// capture 首次出现的行号 (1-based)
+fn capture_line(source: &str, name: &str) -> usize {
+    let token = format!("@{}", name);
+    source
+        .lines()
+        .position(|line| {
+            line.match_indices(&token).any(|(i, _)| {
+                let rest = &line[i + token.len()..];
+                !rest.starts_with(|c: char| c.is_alphanumeric() || c == '_' || c == '.')
+            })
+        })
+        .map_or(1, |i| i + 1)
+}
+
+/// 查询文件名 (不含扩展名)，TSX/JSX 与 TS/JS 共用
+pub fn query_name(lang: &Language) -> &'static str {
+    match lang {
+        Language::Tsx => "typescript",
+        Language::Jsx => "javascript",
+        _ => lang.as_str(),
+    }
+}
+
+pub fn get_language(lang: &Language) -> tree_sitter::Language {
+    match lang {
+        Language::Python => tree_sitter_python::LANGUAGE.into(),
+        Language::TypeScript => tree_sitter_typescript::LANGUAGE_TYPESCRIPT.into(),
+        Language::Tsx => tree_sitter_typescript::LANGUAGE_TSX.into(),
+        Language::JavaScript => tree_sitter_javascript::LANGUAGE.into(),
+        Language::Jsx => tree_sitter_javascript::LANGUAGE.into(),
+        Language::Go => tree_sitter_go::LANGUAGE.into(),
+        Language::Rust => tree_sitter_rust::LANGUAGE.into(),
+        Language::Java => tree_sitter_java::LANGUAGE.into(),
+        Language::C => tree_sitter_c::LANGUAGE.into(),
+        Language::Cpp => tree_sitter_cpp::LANGUAGE.into(),
+        Language::Ruby => tree_sitter_ruby::LANGUAGE.into(),
+        Language::Markdown => tree_sitter_md::LANGUAGE.into(),
+        Language::Swift => tree_sitter_swift::LANGUAGE.into(),
+        Language::ObjC => tree_sitter_objc::LANGUAGE.into(),
+        Language::Kotlin => tree_sitter_kotlin_ng::LANGUAGE.into(),
+        Language::CSharp => tree_sitter_c_sharp::LANGUAGE.into(),
+    }
+}
+
+/// 内置 DSL catalog
+fn builtin_dsl(lang: &Language) -> &'static [(&'static str, SymbolKind)] {
+    match lang {
+        Language::Ruby => RUBY_DSL,
+        Language::TypeScript | Language::Tsx | Language::JavaScript | Language::Jsx => JS_DSL,
+        _ => &[],
+    }
+}
+
+/// Ruby / Rails 常见 DSL
+const RUBY_DSL: &[(&str, SymbolKind)] = &[
+    // 属性
+    ("attr_reader", SymbolKind::Property),
+    ("attr_writer", SymbolKind::Property),
+    ("attr_accessor", SymbolKind::Property),
+    ("cattr_accessor", SymbolKind::Property),
+    ("mattr_accessor", SymbolKind::Property),
+    ("class_attribute", SymbolKind::Property),
+    // 元编程定义的方法
+    ("define_method", SymbolKind::Method),
+    ("alias_method", SymbolKind::Method),
+    ("delegate", SymbolKind::Method),
+    // ActiveRecord 关联
+    ("belongs_to", SymbolKind::Association),
+    ("has_one", SymbolKind::Association),
+    ("has_many", SymbolKind::Association),
+    ("has_and_belongs_to_many", SymbolKind::Association),
+    // 作用域
+    ("scope", SymbolKind::Scope),
+    // 校验
+    ("validates", SymbolKind::Validation),
+    ("validate", SymbolKind::Validation),
+    // 回调
+    ("before_validation", SymbolKind::Callback),
+    ("after_validation", SymbolKind::Callback),
+    ("before_save", SymbolKind::Callback),
+    ("after_save", SymbolKind::Callback),
+    ("around_save", SymbolKind::Callback),
+    ("before_create", SymbolKind::Callback),
+    ("after_create", SymbolKind::Callback),
+    ("before_update", SymbolKind::Callback),
+    ("after_update", SymbolKind::Callback),
+    ("before_destroy", SymbolKind::Callback),
+    ("after_destroy", SymbolKind::Callback),
+    ("after_commit", SymbolKind::Callback),
+    ("after_initialize", SymbolKind::Callback),
+    ("before_action", SymbolKind::Callback),
+    ("after_action", SymbolKind::Callback),
+    ("around_action", SymbolKind::Callback),
+    // ActiveSupport::Concern 块
+    ("included", SymbolKind::Callback),
+    ("class_methods", SymbolKind::Module),
+    // RSpec / Rails 测试块
+    ("describe", SymbolKind::Test),
+    ("context", SymbolKind::Test),
+    ("it", SymbolKind::Test),
+    ("specify", SymbolKind::Test),
+    ("test", SymbolKind::Test),
+];
+
+/// JavaScript / TypeScript 测试框架 (Jest、Mocha、Vitest、node:test)
+const JS_DSL: &[(&str, SymbolKind)] = &[
+    ("describe", SymbolKind::Test),
+    ("context", SymbolKind::Test),
+    ("suite", SymbolKind::Test),
+    ("it", SymbolKind::Test),
+    ("test", SymbolKind::Test),
+    ("specify", SymbolKind::Test),
+];
+
+fn builtin_source(lang: &Language) -> &'static str {
+    match lang {
+        Language::Python => include_str!("queries/python.scm"),
+        Language::TypeScript | Language::Tsx => include_str!("queries/typescript.scm"),
+        Language::JavaScript | Language::Jsx => include_str!("queries/javascript.scm"),
+        Language::Go => include_str!("queries/go.scm"),
+        Language::Rust => include_str!("queries/rust.scm"),
+        Language::Java => include_str!("queries/java.scm"),
+        Language::C => include_str!("queries/c.scm"),
+        Language::Cpp => include_str!("queries/cpp.scm"),
+        Language::Ruby => include_str!("queries/ruby.scm"),
+        Language::Markdown => include_str!("queries/markdown.scm"),
+        Language::Swift => include_str!("queries/swift.scm"),
+        Language::ObjC => include_str!("queries/objc.scm"),
+        Language::Kotlin => include_str!("queries/kotlin.scm"),
+        Language::CSharp => include_str!("queries/csharp.scm"),
+    }
+}
+
+// ============================================================
+// 单元测试
+// ============================================================
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::fs;
+    use tempfile::TempDir;
+
+    #[test]
+    fn test_builtin_queries_compile() {
+        let queries = Queries::builtin();
+        for lang in Language::ALL {
+            assert!(queries.compile(lang).is_ok(), "{:?}", lang);
+        }
+    }
+
+    #[test]
+    fn test_capture_role() {
+        assert_eq!(capture_role("name"), Some(CaptureRole::Name));
+        assert_eq!(
+            capture_role("definition.enum_member"),
+            Some(CaptureRole::Definition(SymbolKind::EnumMember))
+        );
+        assert_eq!(capture_role("symbol"), Some(CaptureRole::Fragment));
+        assert_eq!(capture_role("doc"), Some(CaptureRole::Doc));
+        assert_eq!(capture_role("type_param"), Some(CaptureRole::TypeParam));
+        assert_eq!(capture_role("_body"), Some(CaptureRole::Helper));
+        assert_eq!(
+            capture_role("entry.route"),
+            Some(CaptureRole::Entry(EntryKind::Route))
+        );
+        assert_eq!(capture_role("entry.function"), None);
+        assert_eq!(capture_role("definition.type_class"), None);
+        assert_eq!(capture_role("definition"), None);
+        assert_eq!(capture_role("class"), None);
+    }
+
+    #[test]
+    fn test_user_query_replaces_builtin() {
+        let tmp = TempDir::new().unwrap();
+        let user = "(function_item name: (identifier) @name) @definition.function\n";
+        fs::write(tmp.path().join("rust.scm"), user).unwrap();
+
+        let queries = Queries::load(tmp.path()).unwrap();
+        assert_eq!(queries.source(&Language::Rust), user);
+        assert_eq!(queries.source(&Language::Go), builtin_source(&Language::Go));
+    }
+
+    #[test]
+    fn test_user_query_extends_builtin() {
+        let tmp = TempDir::new().unwrap();
+        let user = "; extends\n(macro_invocation macro: (identifier) @name) @definition.function\n";
+        fs::write(tmp.path().join("rust.scm"), user).unwrap();
+
+        let queries = Queries::load(tmp.path()).unwrap();
+        let source = queries.source(&Language::Rust);
+        assert!(source.starts_with(builtin_source(&Language::Rust)));
+        assert!(source.ends_with(user));
+        assert!(queries.compile(&Language::Rust).is_ok());
+    }
+
+    #[test]
+    fn test_shared_query_file_for_tsx() {
+        let tmp = TempDir::new().unwrap();
+        let user = "(class_declaration name: (type_identifier) @name) @definition.class\n";
+        fs::write(tmp.path().join("typescript.scm"), user).unwrap();
+
+        let queries = Queries::load(tmp.path()).unwrap();
+        assert_eq!(queries.source(&Language::Tsx), user);
+    }
+
+    #[test]
+    fn test_user_query_error_reports_location() {
+        let tmp = TempDir::new().unwrap();
+        fs::write(
+            tmp.path().join("python.scm"),
+            "; extends\n\n(no_such_node) @definition.class\n",
+        )
+        .unwrap();
+
+        let err = Queries::load(tmp.path()).err().unwrap().to_string();
+        assert!(err.contains("python.scm:3:2"), "{}", err);
+        assert!(err.contains("no_such_node"), "{}", err);
+    }
+
+    #[test]
+    fn test_user_query_unknown_capture() {
+        let tmp = TempDir::new().unwrap();
+        fs::write(
+            tmp.path().join("rust.scm"),
+            "(struct_item\n  name: (type_identifier) @name) @definition.name_struct\n\n(enum_item) @definition.name_struct_extra\n",
+        )
+        .unwrap();
+
+        let err = Queries::load(tmp.path()).err().unwrap().to_string();
+        assert!(
+            err.contains("rust.scm:2: unknown capture @definition.name_struct"),
+            "{}",
+            err
+        );
+    }
+
+    #[test]
+    fn test_dsl_catalog() {
+        let queries = Queries::builtin();
+        assert_eq!(
+            queries.dsl_kind(&Language::Ruby, "has_many"),
+            Some(SymbolKind::Association)
+        );
+        assert_eq!(
+            queries.dsl_kind(&Language::Ruby, "validates"),
+            Some(SymbolKind::Validation)
+        );
+        assert_eq!(queries.dsl_kind(&Language::Ruby, "puts"), None);
+        // skip_before_action 移除回调，不生成符号
+        assert_eq!(
+            queries.dsl_kind(&Language::Ruby, "skip_before_action"),
+            None
+        );
+        assert_eq!(queries.dsl_kind(&Language::Python, "has_many"), None);
+        assert_eq!(
+            queries.dsl_kind(&Language::Tsx, "describe"),
+            Some(SymbolKind::Test)
+        );
+
+        let user = HashMap::from([(
+            Language::Ruby,
+            HashMap::from([
+                ("has_many".to_string(), SymbolKind::Property),
+                ("acts_as_tree".to_string(), SymbolKind::Association),
+            ]),
+        )]);
+        let queries = Queries::builtin().with_dsl(user);
+        assert_eq!(
+            queries.dsl_kind(&Language::Ruby, "has_many"),
+            Some(SymbolKind::Property)
+        );
+        assert_eq!(
+            queries.dsl_kind(&Language::Ruby, "acts_as_tree"),
+            Some(SymbolKind::Association)
+        );
+        assert_eq!(
+            queries.dsl_kind(&Language::Ruby, "scope"),
+            Some(SymbolKind::Scope)
+        );
+    }
+
+    #[test]
+    fn test_missing_queries_dir() {
+        assert!(Queries::load(Path::new("/nonexistent/queries")).is_err());
+    }
+}
@@ -2,16 +2,24 @@
 // Scanner: 文件扫描 (支持单文件/目录，自动 gitignore)
 // ============================================================
 
-use anyhow::Result;
+use anyhow::{Context, Result};
+use globset::{Glob, GlobSet, GlobSetBuilder};
 use ignore::WalkBuilder;
 use std::cmp::Ordering;
 use std::path::{Path, PathBuf};
 
+/// 扫描选项: include/exclude glob (相对输入目录)
+#[derive(Debug, Default)]
+pub struct ScanOptions {
+    pub include: Vec<String>,
+    pub exclude: Vec<String>,
+}
+
 /// 扫描输入路径，返回所有源码文件
 /// - 单文件: 直接返回
-/// - 目录: 递归扫描，尊重 .gitignore
+/// - 目录: 递归扫描，尊重 .gitignore 与 include/exclude
 /// - 排序: 目录深度优先，同级按名字字典序
-pub fn scan(input: &Path) -> Result<Vec<PathBuf>> {
+pub fn scan(input: &Path, options: &ScanOptions) -> Result<Vec<PathBuf>> {
     let input_abs = input.canonicalize().unwrap_or_else(|_| input.to_path_buf());
 
     // 单文件直接返回
@@ -19,6 +27,9 @@ pub fn scan(input: &Path) -> Result<Vec<PathBuf>> {
         return Ok(vec![input_abs]);
     }
 
+    let include = build_globset(&options.include)?;
+    let exclude = build_globset(&options.exclude)?;
+
     let mut files = Vec::new();
     let walker = WalkBuilder::new(&input_abs)
         .hidden(true)
@@ -27,9 +38,19 @@ pub fn scan(input: &Path) -> Result<Vec<PathBuf>> {
 
     for entry in walker.flatten() {
         let entry_path = entry.path();
-        if entry_path.is_file() {
-            files.push(entry_path.to_path_buf());
+        if !entry_path.is_file() {
+            continue;
+        }
+
+        let rel = entry_path.strip_prefix(&input_abs).unwrap_or(entry_path);
+        if !options.include.is_empty() && !include.is_match(rel) {
+            continue;
+        }
+        if exclude.is_match(rel) {
+            continue;
         }
+
+        files.push(entry_path.to_path_buf());
     }
 
     // 排序: 目录深度优先，同级按名字字典序
@@ -38,6 +59,77 @@ pub fn scan(input: &Path) -> Result<Vec<PathBuf>> {
     Ok(files)
 }
 
+/// 按列表给出的文件 (相对 root 或绝对路径) 代替目录扫描，同样应用 include/exclude 并排序；
+/// 列表中的目录忽略，不存在的文件保留 (读取时报告失败)
+pub fn scan_list(root: &Path, list: &[PathBuf], options: &ScanOptions) -> Result<Vec<PathBuf>> {
+    let root_abs = root.canonicalize().unwrap_or_else(|_| root.to_path_buf());
+    let include = build_globset(&options.include)?;
+    let exclude = build_globset(&options.exclude)?;
+
+    let mut files = Vec::new();
+    for entry in list {
+        let path = root_abs.join(entry);
+        if path.is_dir() {
+            continue;
+        }
+        let path = path.canonicalize().unwrap_or(path);
+
+        let rel = path.strip_prefix(&root_abs).unwrap_or(&path);
+        if !options.include.is_empty() && !include.is_match(rel) {
+            continue;
+        }
+        if exclude.is_match(rel) {
+            continue;
+        }
+        if !files.contains(&path) {
+            files.push(path);
+        }
+    }
+
+    files.sort_by(|a, b| compare_paths(a, b, &root_abs));
+
+    Ok(files)
+}
+
+/// 解析文件列表: 含 NUL 时按 NUL 分隔 (`git ls-files -z`、`fd -0`)，否则按行；空项忽略。
+/// 按原始字节切分，Unix 上非 UTF-8 的文件名原样保留
+pub fn parse_file_list(bytes: &[u8]) -> Vec<PathBuf> {
+    let separator = if bytes.contains(&0) { 0 } else { b'\n' };
+    bytes
+        .split(|b| *b == separator)
+        .map(|entry| entry.strip_suffix(b"\r").unwrap_or(entry))
+        .filter(|entry| !entry.is_empty())
+        .map(path_from_bytes)
+        .collect()
+}
+
+#[cfg(unix)]
+fn path_from_bytes(bytes: &[u8]) -> PathBuf {
+    use std::os::unix::ffi::OsStrExt;
+    PathBuf::from(std::ffi::OsStr::from_bytes(bytes))
+}
+
+#[cfg(not(unix))]
+fn path_from_bytes(bytes: &[u8]) -> PathBuf {
+    PathBuf::from(String::from_utf8_lossy(bytes).into_owned())
+}
+
+/// 多个输入时的根标签: 输入路径原样 (去掉开头的 `./` 与末尾的 `/`)，当前目录取目录名
+pub fn root_label(input: &Path) -> String {
+    let text = input.to_string_lossy().replace('\\', "/");
+    let label = text.trim_start_matches("./").trim_end_matches('/');
+    if !label.is_empty() && label != "." {
+        return label.to_string();
+    }
+    std::env::current_dir()
+        .ok()
+        .and_then(|dir| {
+            dir.file_name()
+                .map(|name| name.to_string_lossy().to_string())
+        })
+        .unwrap_or_else(|| ".".to_string())
+}
+
 /// 计算相对路径
 pub fn relative_path(input: &Path, file: &Path) -> PathBuf {
     let input_abs = input.canonicalize().unwrap_or_else(|_| input.to_path_buf());
@@ -58,6 +150,15 @@ pub fn relative_path(input: &Path, file: &Path) -> PathBuf {
         .unwrap_or_else(|_| file_abs)
 }
 
+fn build_globset(patterns: &[String]) -> Result<GlobSet> {
+    let mut builder = GlobSetBuilder::new();
+    for pattern in patterns {
+        let glob = Glob::new(pattern).with_context(|| format!("Invalid glob: {}", pattern))?;
+        builder.add(glob);
+    }
+    Ok(builder.build()?)
+}
+
 /// 路径比较: 同级目录优先于文件，各自按字典序
 fn compare_paths(a: &Path, b: &Path, base: &Path) -> Ordering {
     let rel_a = a.strip_prefix(base).unwrap_or(a);
@@ -108,7 +209,7 @@ mod tests {
         let file = tmp.path().join("main.rs");
         fs::write(&file, "fn main() {}").unwrap();
 
-        let result = scan(&file).unwrap();
+        let result = scan(&file, &ScanOptions::default()).unwrap();
         assert_eq!(result.len(), 1);
         assert!(result[0].to_string_lossy().ends_with("main.rs"));
     }
@@ -119,7 +220,7 @@ mod tests {
         fs::write(tmp.path().join("a.rs"), "").unwrap();
         fs::write(tmp.path().join("b.py"), "").unwrap();
 
-        let result = scan(tmp.path()).unwrap();
+        let result = scan(tmp.path(), &ScanOptions::default()).unwrap();
         assert_eq!(result.len(), 2);
     }
 
@@ -133,7 +234,7 @@ mod tests {
         fs::write(tmp.path().join("a.rs"), "").unwrap();
         fs::write(sub.join("m.rs"), "").unwrap();
 
-        let result = scan(tmp.path()).unwrap();
+        let result = scan(tmp.path(), &ScanOptions::default()).unwrap();
         let rel_paths: Vec<_> = result
             .iter()
             .map(|p| {
@@ -185,8 +286,90 @@ mod tests {
         fs::write(tmp.path().join("included.rs"), "").unwrap();
         fs::write(tmp.path().join("ignored.rs"), "").unwrap();
 
-        let result = scan(tmp.path()).unwrap();
+        let result = scan(tmp.path(), &ScanOptions::default()).unwrap();
         assert_eq!(result.len(), 1);
         assert!(result[0].to_string_lossy().ends_with("included.rs"));
     }
+
+    #[test]
+    fn test_scan_include_exclude() {
+        let tmp = TempDir::new().unwrap();
+        let src = tmp.path().join("src");
+        let gen = src.join("gen");
+        fs::create_dir_all(&gen).unwrap();
+
+        fs::write(tmp.path().join("build.rs"), "").unwrap();
+        fs::write(src.join("lib.rs"), "").unwrap();
+        fs::write(gen.join("out.rs"), "").unwrap();
+
+        let options = ScanOptions {
+            include: vec!["src/**".to_string()],
+            exclude: vec!["**/gen/**".to_string()],
+        };
+        let result = scan(tmp.path(), &options).unwrap();
+        assert_eq!(result.len(), 1);
+        assert!(result[0].to_string_lossy().ends_with("lib.rs"));
+    }
+
+    #[test]
+    fn test_scan_invalid_glob() {
+        let tmp = TempDir::new().unwrap();
+        let options = ScanOptions {
+            include: vec!["src/[".to_string()],
+            exclude: vec![],
+        };
+        assert!(scan(tmp.path(), &options).is_err());
+    }
+
+    #[test]
+    fn test_scan_list() {
+        let tmp = TempDir::new().unwrap();
+        fs::create_dir(tmp.path().join("src")).unwrap();
+        fs::write(tmp.path().join("src/lib.rs"), "").unwrap();
+        fs::write(tmp.path().join("build.rs"), "").unwrap();
+        fs::write(tmp.path().join("notes.md"), "").unwrap();
+
+        let list = parse_file_list(b"build.rs\0src\0src/lib.rs\0notes.md\0missing.rs\0build.rs\0");
+        let options = ScanOptions {
+            include: Vec::new(),
+            exclude: vec!["*.md".to_string()],
+        };
+        let result = scan_list(tmp.path(), &list, &options).unwrap();
+        let rel_paths: Vec<_> = result
+            .iter()
+            .map(|p| relative_path(tmp.path(), p).to_string_lossy().to_string())
+            .collect();
+
+        // 目录忽略、去重、按扫描顺序排序；不存在的文件保留
+        assert_eq!(rel_paths, vec!["src/lib.rs", "build.rs", "missing.rs"]);
+    }
+
+    #[test]
+    fn test_parse_file_list() {
+        assert_eq!(
+            parse_file_list(b"a.rs\r\n\nsrc/b.rs\n"),
+            vec![PathBuf::from("a.rs"), PathBuf::from("src/b.rs")]
+        );
+        // NUL 分隔时文件名可含换行
+        assert_eq!(
+            parse_file_list(b"a b.rs\0c\nd.rs\0"),
+            vec![PathBuf::from("a b.rs"), PathBuf::from("c\nd.rs")]
+        );
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn test_parse_file_list_non_utf8() {
+        use std::os::unix::ffi::OsStrExt;
+        let list = parse_file_list(b"caf\xE9.py\0ok.py\0");
+        assert_eq!(list[0].as_os_str().as_bytes(), b"caf\xE9.py");
+        assert_eq!(list[1], PathBuf::from("ok.py"));
+    }
+
+    #[test]
+    fn test_root_label() {
+        assert_eq!(root_label(Path::new("./backend/")), "backend");
+        assert_eq!(root_label(Path::new("../web/src")), "../web/src");
+        assert_ne!(root_label(Path::new(".")), "");
+    }
 }
@@ -0,0 +1,304 @@
+// ============================================================
+// Skip: 跳过生成、vendored、压缩与过大的文件
+// ============================================================
+
+use crate::detector::Language;
+use crate::gitattributes::Attributes;
+use anyhow::Result;
+use globset::{Glob, GlobSet, GlobSetBuilder};
+use std::fmt;
+use std::path::Path;
+
+/// 默认文件大小上限 (1 MiB)
+pub const DEFAULT_MAX_FILE_SIZE: u64 = 1024 * 1024;
+
+/// 生成文件的文件名模式 (protobuf、gRPC、Flutter、WinForms 等)
+const GENERATED_NAMES: &[&str] = &[
+    "*.pb.go",
+    "*.pb.cc",
+    "*.pb.h",
+    "*_pb2.py",
+    "*_pb2_grpc.py",
+    "*_pb.js",
+    "*_pb.d.ts",
+    "*_generated.rs",
+    "*_generated.go",
+    "*.generated.*",
+    "*.g.dart",
+    "*.g.cs",
+    "*.designer.cs",
+    "*.Designer.cs",
+    "*.min.js",
+    "*.min.css",
+];
+
+/// vendored 目录名
+const VENDORED_DIRS: &[&str] = &[
+    "vendor",
+    "node_modules",
+    "third_party",
+    "bower_components",
+    "Pods",
+    "Carthage",
+];
+
+/// 文件开头注释中的生成标记 (Facebook `@generated`、.NET `<auto-generated>`)，须为完整的词
+const GENERATED_MARKERS: &[&str] = &["@generated", "<auto-generated"];
+
+/// 检查生成标记的文件开头行数
+const HEADER_LINES: usize = 10;
+
+/// 注释行开头 (生成标记须位于注释中)
+const COMMENT_PREFIXES: &[&str] = &["//", "#", "/*", "*", "<!--", "--", ";", "'"];
+
+/// 判断压缩文件的最小体积与平均行长
+const MINIFIED_MIN_BYTES: usize = 2048;
+const MINIFIED_AVG_LINE: usize = 250;
+
+/// 跳过原因
+#[derive(Debug, Clone, PartialEq)]
+pub enum SkipReason {
+    /// 生成文件 (依据: 文件名模式、生成标记或 .gitattributes)
+    Generated(String),
+    /// vendored 代码 (依据: 目录名或 .gitattributes)
+    Vendored(String),
+    /// 压缩代码 (平均行长)
+    Minified(usize),
+    /// 超过大小上限
+    TooLarge(u64),
+}
+
+impl SkipReason {
+    /// 汇总时的分类名
+    pub fn category(&self) -> &'static str {
+        match self {
+            Self::Generated(_) => "generated",
+            Self::Vendored(_) => "vendored",
+            Self::Minified(_) => "minified",
+            Self::TooLarge(_) => "too large",
+        }
+    }
+}
+
+impl fmt::Display for SkipReason {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Self::Generated(why) | Self::Vendored(why) => {
+                write!(f, "{} ({})", self.category(), why)
+            }
+            Self::Minified(avg) => write!(f, "minified (average line length {})", avg),
+            Self::TooLarge(size) => write!(f, "too large ({} bytes)", size),
+        }
+    }
+}
+
+/// 跳过规则: .gitattributes 的 linguist-generated / linguist-vendored，加上内置的文件名、目录与内容判断
+pub struct Skipper<'a> {
+    attributes: &'a Attributes,
+    generated_names: GlobSet,
+    max_size: u64,
+}
+
+impl<'a> Skipper<'a> {
+    /// max_size 为 0 时不限大小
+    pub fn new(attributes: &'a Attributes, max_size: u64) -> Result<Self> {
+        let mut builder = GlobSetBuilder::new();
+        for pattern in GENERATED_NAMES {
+            builder.add(Glob::new(pattern)?);
+        }
+
+        Ok(Self {
+            attributes,
+            generated_names: builder.build()?,
+            max_size,
+        })
+    }
+    /// 按路径与大小判断 (读取文件之前)
+    pub fn check_path(&self, rel: &Path, size: u64) -> Option<SkipReason> {
+        let generated = self.attributes.generated(rel);
+        let vendored = self.attributes.vendored(rel);
+
+        match generated {
+            Some((true, pattern)) => {
+                return Some(SkipReason::Generated(format!(
+                    "linguist-generated: {}",
+                    pattern
+                )))
+            }
+            Some((false, _)) => {}
+            None => {
+                let name = rel.file_name().unwrap_or_default();
+                if let Some(&i) = self.generated_names.matches(name).first() {
+                    return Some(SkipReason::Generated(GENERATED_NAMES[i].to_string()));
+                }
+            }
+        }
+        match vendored {
+            Some((true, pattern)) => {
+                return Some(SkipReason::Vendored(format!(
+                    "linguist-vendored: {}",
+                    pattern
+                )))
+            }
+            Some((false, _)) => {}
+            None => {
+                if let Some(dir) = vendored_dir(rel) {
+                    return Some(SkipReason::Vendored(format!("{}/", dir)));
+                }
+            }
+        }
+
+        if self.max_size > 0 && size > self.max_size {
+            return Some(SkipReason::TooLarge(size));
+        }
+        None
+    }
+}
+
+/// 按内容判断 (解码之后): 开头 10 行注释中的生成标记、压缩代码 (Markdown 段落常为长行，不做压缩判断)
+pub fn check_content(text: &str, lang: Language) -> Option<SkipReason> {
+    let marker = text
+        .lines()
+        .take(HEADER_LINES)
+        .map(str::trim)
+        .filter(|line| COMMENT_PREFIXES.iter().any(|p| line.starts_with(p)))
+        .find_map(generated_marker);
+    if let Some(marker) = marker {
+        return Some(SkipReason::Generated(format!("\"{}\" header", marker)));
+    }
+
+    if lang != Language::Markdown && text.len() >= MINIFIED_MIN_BYTES {
+        let lines = text.lines().count().max(1);
+        let avg = text.len() / lines;
+        if avg > MINIFIED_AVG_LINE {
+            return Some(SkipReason::Minified(avg));
+        }
+    }
+    None
+}
+
+/// 注释行中的生成标记: Go 约定的整行 `// Code generated ... DO NOT EDIT.`，
+/// 或前后不接标识符字符的 `@generated` / `<auto-generated`
+fn generated_marker(line: &str) -> Option<&'static str> {
+    if line.starts_with("// Code generated ") && line.ends_with(" DO NOT EDIT.") {
+        return Some("Code generated ... DO NOT EDIT.");
+    }
+    let is_word = |c: char| c.is_alphanumeric() || c == '_' || c == '-';
+    GENERATED_MARKERS.iter().copied().find(|marker| {
+        line.match_indices(marker).any(|(at, _)| {
+            !line[..at].chars().next_back().is_some_and(is_word)
+                && !line[at + marker.len()..]
+                    .chars()
+                    .next()
+                    .is_some_and(is_word)
+        })
+    })
+}
+
+fn vendored_dir(rel: &Path) -> Option<String> {
+    let parent = rel.parent()?;
+    parent
+        .components()
+        .filter_map(|c| c.as_os_str().to_str())
+        .find(|dir| VENDORED_DIRS.contains(dir))
+        .map(str::to_string)
+}
+
+// ============================================================
+// 单元测试
+// ============================================================
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::fs;
+    use tempfile::TempDir;
+
+    #[test]
+    fn test_generated_and_vendored_paths() {
+        let attributes = Attributes::default();
+        let skipper = Skipper::new(&attributes, 100).unwrap();
+
+        assert_eq!(
+            skipper.check_path(Path::new("api/user.pb.go"), 10),
+            Some(SkipReason::Generated("*.pb.go".to_string()))
+        );
+        assert_eq!(
+            skipper.check_path(Path::new("dist/app.min.js"), 10),
+            Some(SkipReason::Generated("*.min.js".to_string()))
+        );
+        assert_eq!(
+            skipper.check_path(Path::new("vendor/github.com/x/y.go"), 10),
+            Some(SkipReason::Vendored("vendor/".to_string()))
+        );
+        assert_eq!(
+            skipper.check_path(Path::new("dump.py"), 101),
+            Some(SkipReason::TooLarge(101))
+        );
+        assert_eq!(skipper.check_path(Path::new("src/vendor.go"), 10), None);
+    }
+
+    #[test]
+    fn test_gitattributes_rules() {
+        let tmp = TempDir::new().unwrap();
+        fs::create_dir(tmp.path().join(".git")).unwrap();
+        fs::write(
+            tmp.path().join(".gitattributes"),
+            "# linguist\n\
+             *.gen.ts linguist-generated\n\
+             src/schema/** linguist-generated=true\n\
+             vendor/** -linguist-vendored\n\
+             libs/** linguist-vendored\n\
+             *.pb.go linguist-generated=false\n",
+        )
+        .unwrap();
+
+        let attributes = Attributes::load(tmp.path()).unwrap();
+        let skipper = Skipper::new(&attributes, 0).unwrap();
+        assert_eq!(
+            skipper.check_path(Path::new("a/b.gen.ts"), 10),
+            Some(SkipReason::Generated(
+                "linguist-generated: *.gen.ts".to_string()
+            ))
+        );
+        assert_eq!(
+            skipper.check_path(Path::new("libs/x.js"), 10),
+            Some(SkipReason::Vendored(
+                "linguist-vendored: libs/**".to_string()
+            ))
+        );
+        // 显式取消的规则覆盖内置判断；max_size 为 0 不限大小
+        assert_eq!(skipper.check_path(Path::new("vendor/x.go"), u64::MAX), None);
+        assert_eq!(skipper.check_path(Path::new("api.pb.go"), 10), None);
+    }
+
+    #[test]
+    fn test_check_content() {
+        let go = "// Code generated by protoc-gen-go. DO NOT EDIT.\npackage api\n";
+        assert_eq!(
+            check_content(go, Language::Go),
+            Some(SkipReason::Generated(
+                "\"Code generated ... DO NOT EDIT.\" header".to_string()
+            ))
+        );
+        let py = "# @generated by tool\nx = 1\n";
+        assert!(check_content(py, Language::Python).is_some());
+        let cs = "// <auto-generated>\n//     This code was generated by a tool.\nclass A {}\n";
+        assert!(check_content(cs, Language::CSharp).is_some());
+        // 标记须在开头的注释中
+        let code = "MARKER = \"@generated\"\n";
+        assert_eq!(check_content(code, Language::Python), None);
+        // 手写文件中提到 DO NOT EDIT 或标记的一部分不算
+        let go = "// DO NOT EDIT the order below without updating docs\npackage reg\n";
+        assert_eq!(check_content(go, Language::Go), None);
+        let go = "// Autogenerated by hand, see @generated-docs\npackage reg\n";
+        assert_eq!(check_content(go, Language::Go), None);
+
+        let minified = format!("var a=1;{}\n", "b(1);".repeat(1000));
+        assert_eq!(
+            check_content(&minified, Language::JavaScript),
+            Some(SkipReason::Minified(minified.len()))
+        );
+        assert_eq!(check_content(&minified, Language::Markdown), None);
+        assert_eq!(check_content("fn main() {}\n", Language::Rust), None);
+    }
+}
@@ -7,7 +7,7 @@ use serde::{Deserialize, Serialize};
 // ------------------------------------------------------------
 // 符号类型枚举
 // ------------------------------------------------------------
-#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
 #[serde(rename_all = "snake_case")]
 pub enum SymbolKind {
     Class,
@@ -24,9 +24,61 @@ pub enum SymbolKind {
     EnumMember,
     Type,
     Namespace,
+    Struct,
+    Trait,
+    Impl,
+    Macro,
+    Record,
+    Protocol,
+    Extension,
+    Heading,
+    CodeBlock,
+    Test,
+    Event,
+    Association,
+    Scope,
+    Callback,
+    Validation,
 }
 
 impl SymbolKind {
+    pub const ALL: &'static [SymbolKind] = &[
+        Self::Class,
+        Self::Function,
+        Self::Method,
+        Self::Variable,
+        Self::Constant,
+        Self::Interface,
+        Self::Enum,
+        Self::Module,
+        Self::Property,
+        Self::Constructor,
+        Self::Field,
+        Self::EnumMember,
+        Self::Type,
+        Self::Namespace,
+        Self::Struct,
+        Self::Trait,
+        Self::Impl,
+        Self::Macro,
+        Self::Record,
+        Self::Protocol,
+        Self::Extension,
+        Self::Heading,
+        Self::CodeBlock,
+        Self::Test,
+        Self::Event,
+        Self::Association,
+        Self::Scope,
+        Self::Callback,
+        Self::Validation,
+    ];
+
+    /// 由 as_str() 名称反查
+    pub fn from_name(name: &str) -> Option<Self> {
+        Self::ALL.iter().copied().find(|kind| kind.as_str() == name)
+    }
+
     pub fn as_str(&self) -> &'static str {
         match self {
             Self::Class => "class",
@@ -43,10 +95,74 @@ impl SymbolKind {
             Self::EnumMember => "enum_member",
             Self::Type => "type",
             Self::Namespace => "namespace",
+            Self::Struct => "struct",
+            Self::Trait => "trait",
+            Self::Impl => "impl",
+            Self::Macro => "macro",
+            Self::Record => "record",
+            Self::Protocol => "protocol",
+            Self::Extension => "extension",
+            Self::Heading => "heading",
+            Self::CodeBlock => "code_block",
+            Self::Test => "test",
+            Self::Event => "event",
+            Self::Association => "association",
+            Self::Scope => "scope",
+            Self::Callback => "callback",
+            Self::Validation => "validation",
+        }
+    }
+
+    /// LSP SymbolKind 编号 (没有对应项的类型取最接近的一项)
+    pub fn lsp_kind(&self) -> u8 {
+        match self {
+            Self::Module => 2,
+            Self::Namespace => 3,
+            Self::Class | Self::Impl | Self::Extension => 5,
+            Self::Method | Self::Scope => 6,
+            Self::Property => 7,
+            Self::Field | Self::Association => 8,
+            Self::Constructor => 9,
+            Self::Enum => 10,
+            Self::Interface | Self::Trait | Self::Protocol => 11,
+            Self::Function | Self::Macro | Self::Test => 12,
+            Self::Variable => 13,
+            Self::Constant => 14,
+            Self::Heading => 15,
+            Self::CodeBlock => 19,
+            Self::EnumMember => 22,
+            Self::Struct | Self::Record => 23,
+            Self::Event | Self::Callback | Self::Validation => 24,
+            Self::Type => 26,
         }
     }
 }
 
+/// JSON 中 kind 旁附带 lsp_kind，反序列化时忽略
+mod kind_fields {
+    use super::SymbolKind;
+    use serde::{Deserialize, Deserializer, Serialize, Serializer};
+
+    #[derive(Serialize, Deserialize)]
+    struct KindFields {
+        kind: SymbolKind,
+        #[serde(default, skip_deserializing)]
+        lsp_kind: u8,
+    }
+
+    pub fn serialize<S: Serializer>(kind: &SymbolKind, serializer: S) -> Result<S::Ok, S::Error> {
+        KindFields {
+            kind: *kind,
+            lsp_kind: kind.lsp_kind(),
+        }
+        .serialize(serializer)
+    }
+
+    pub fn deserialize<'de, D: Deserializer<'de>>(deserializer: D) -> Result<SymbolKind, D::Error> {
+        Ok(KindFields::deserialize(deserializer)?.kind)
+    }
+}
+
 // ------------------------------------------------------------
 // 位置信息
 // ------------------------------------------------------------
@@ -62,20 +178,262 @@ pub struct Range {
     pub end: Position,
 }
 
+/// 另一处位置 (如类外定义)
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Location {
+    pub path: String,
+    pub range: Range,
+}
+
+/// 泛型参数及其约束 (含 where 子句中的约束)
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct TypeParam {
+    pub name: String,
+    #[serde(skip_serializing_if = "Vec::is_empty", default)]
+    pub bounds: Vec<String>,
+}
+
+/// 函数 / 方法签名
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct Signature {
+    pub parameters: Vec<Parameter>,
+    /// 返回类型 (构造函数、无注解的动态语言函数为空)
+    #[serde(skip_serializing_if = "Option::is_none", default)]
+    pub returns: Option<String>,
+    #[serde(rename = "async", skip_serializing_if = "is_false", default)]
+    pub is_async: bool,
+    #[serde(rename = "static", skip_serializing_if = "is_false", default)]
+    pub is_static: bool,
+    #[serde(rename = "const", skip_serializing_if = "is_false", default)]
+    pub is_const: bool,
+    #[serde(rename = "unsafe", skip_serializing_if = "is_false", default)]
+    pub is_unsafe: bool,
+}
+
+impl Signature {
+    /// 由修饰符文本设置标记 (如 Rust `const unsafe`、Java `static`、C++ `constexpr`、Python `staticmethod`、Ruby `def self.`、ObjC `+`)
+    pub fn apply_modifier(&mut self, text: &str) {
+        for word in text.split_whitespace() {
+            match word {
+                "async" | "suspend" => self.is_async = true,
+                "static" | "staticmethod" | "self" | "+" => self.is_static = true,
+                "const" | "constexpr" | "consteval" => self.is_const = true,
+                "unsafe" => self.is_unsafe = true,
+                _ => {}
+            }
+        }
+    }
+
+    /// 重复匹配时补充缺失的部分
+    pub fn merge(&mut self, other: Signature) {
+        if self.parameters.is_empty() {
+            self.parameters = other.parameters;
+        }
+        self.returns = self.returns.take().or(other.returns);
+        self.is_async |= other.is_async;
+        self.is_static |= other.is_static;
+        self.is_const |= other.is_const;
+        self.is_unsafe |= other.is_unsafe;
+    }
+}
+
+/// 函数参数
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct Parameter {
+    pub name: String,
+    /// 调用时使用的参数标签 (Swift 外部参数名、Objective-C selector 片段)
+    #[serde(skip_serializing_if = "Option::is_none", default)]
+    pub label: Option<String>,
+    #[serde(rename = "type", skip_serializing_if = "Option::is_none", default)]
+    pub type_name: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none", default)]
+    pub default: Option<String>,
+    /// 可变参数 (`...args`、`*args`、`params T[]`、`vararg`)
+    #[serde(skip_serializing_if = "is_false", default)]
+    pub variadic: bool,
+}
+
+fn is_false(value: &bool) -> bool {
+    !*value
+}
+
+fn is_zero(value: &usize) -> bool {
+    *value == 0
+}
+
 // ------------------------------------------------------------
 // 符号定义
 // ------------------------------------------------------------
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct Symbol {
     pub name: String,
+    #[serde(flatten, with = "kind_fields")]
     pub kind: SymbolKind,
     #[serde(skip_serializing_if = "Option::is_none", default)]
     pub detail: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none", default)]
+    pub doc: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none", default)]
+    pub visibility: Option<String>,
+    /// 泛型参数，按源码顺序
+    #[serde(skip_serializing_if = "Vec::is_empty", default)]
+    pub type_params: Vec<TypeParam>,
+    /// 函数 / 方法签名 (参数、返回类型、async / static 等标记)
+    #[serde(skip_serializing_if = "Option::is_none", default)]
+    pub signature: Option<Signature>,
+    /// 父类 / 父接口 (接口继承、Rust supertrait、Swift 协议继承)
+    #[serde(skip_serializing_if = "Vec::is_empty", default)]
+    pub extends: Vec<String>,
+    /// 实现的接口 / 遵循的协议 / 实现的 trait
+    #[serde(skip_serializing_if = "Vec::is_empty", default)]
+    pub implements: Vec<String>,
+    /// 注解 / 装饰器 / 属性 (去掉 `@`、`#[...]` 等语法符号)，按源码顺序
+    #[serde(skip_serializing_if = "Vec::is_empty", default)]
+    pub attributes: Vec<String>,
+    /// 测试引用到的被测符号 (`Type.method` 或顶层名称)，按首次出现顺序
+    #[serde(skip_serializing_if = "Vec::is_empty", default)]
+    pub covers: Vec<String>,
+    /// 所属类型 (定义在类型体之外的成员，如 Go 方法的 receiver)
+    #[serde(skip_serializing_if = "Option::is_none", default)]
+    pub owner: Option<String>,
+    /// 定义所在文件 (仅当被归并到其他文件的类型下时设置)
+    #[serde(skip_serializing_if = "Option::is_none", default)]
+    pub path: Option<String>,
     pub range: Range,
+    /// 声明与定义分离时，定义所在位置
+    #[serde(skip_serializing_if = "Option::is_none", default)]
+    pub definition: Option<Location>,
+    /// 带函数体 (已是定义，聚合时不再与其他定义配对)，不输出
+    #[serde(skip)]
+    pub has_body: bool,
+    /// 重载签名的位置 (如 TypeScript 函数重载，实现或首个签名作为符号本身)
+    #[serde(skip_serializing_if = "Vec::is_empty", default)]
+    pub overloads: Vec<Range>,
     #[serde(skip_serializing_if = "Vec::is_empty", default)]
     pub children: Vec<Symbol>,
 }
 
+impl Symbol {
+    pub fn new(name: String, kind: SymbolKind, range: Range) -> Self {
+        Self {
+            name,
+            kind,
+            detail: None,
+            doc: None,
+            visibility: None,
+            type_params: Vec::new(),
+            signature: None,
+            extends: Vec::new(),
+            implements: Vec::new(),
+            attributes: Vec::new(),
+            covers: Vec::new(),
+            owner: None,
+            path: None,
+            range,
+            definition: None,
+            has_body: false,
+            overloads: Vec::new(),
+            children: Vec::new(),
+        }
+    }
+
+    /// 所有父类型 (extends 在前，implements 在后)
+    pub fn supertypes(&self) -> impl Iterator<Item = &String> {
+        self.extends.iter().chain(&self.implements)
+    }
+
+    /// 是否带有指定注解: 注解名 (参数之前的部分) 或其末段与 name 相同，不区分大小写
+    ///
+    /// `test` 匹配 `#[test]`、`#[tokio::test]`、`@Test`；`derive` 匹配 `#[derive(Debug)]`
+    pub fn has_attribute(&self, name: &str) -> bool {
+        self.attributes.iter().any(|attr| {
+            let full = attr
+                .split(|c: char| c == '(' || c == '=' || c.is_whitespace())
+                .next()
+                .unwrap_or("");
+            let last = full.rsplit([':', '.']).next().unwrap_or(full);
+            full.eq_ignore_ascii_case(name) || last.eq_ignore_ascii_case(name)
+        })
+    }
+}
+
+// ------------------------------------------------------------
+// 入口点
+// ------------------------------------------------------------
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum EntryKind {
+    /// 程序入口 (`main`、`@main`、`if __name__ == "__main__"`)
+    Main,
+    /// CLI 命令 (clap、click / typer、cobra)
+    Command,
+    /// HTTP 路由
+    Route,
+}
+
+impl EntryKind {
+    pub const ALL: &'static [EntryKind] = &[Self::Main, Self::Command, Self::Route];
+
+    /// 由 as_str() 名称反查
+    pub fn from_name(name: &str) -> Option<Self> {
+        Self::ALL.iter().copied().find(|kind| kind.as_str() == name)
+    }
+
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Self::Main => "main",
+            Self::Command => "command",
+            Self::Route => "route",
+        }
+    }
+}
+
+/// 入口点: 名称为函数名、命令名或 `GET /users/{id}` 形式的路由
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct EntryPoint {
+    pub kind: EntryKind,
+    pub name: String,
+    /// 处理函数 (路由 handler、命令对应的函数)
+    #[serde(skip_serializing_if = "Option::is_none", default)]
+    pub handler: Option<String>,
+    pub line: usize,
+}
+
+// ------------------------------------------------------------
+// 语法错误
+// ------------------------------------------------------------
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum DiagnosticKind {
+    /// 无法解析的源码 (ERROR 节点)
+    Error,
+    /// 解析器补出的缺失节点 (MISSING 节点)
+    Missing,
+}
+
+/// 语法错误位置: text 为 ERROR 节点源码的首行 (截断) 或缺失的节点类型
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Diagnostic {
+    pub kind: DiagnosticKind,
+    pub line: usize,
+    pub column: usize,
+    pub text: String,
+}
+
+impl Diagnostic {
+    /// 如 ``line 3:8 unexpected `}` ``、``line 5:0 missing `;` ``
+    pub fn message(&self) -> String {
+        let what = match self.kind {
+            DiagnosticKind::Error => "unexpected",
+            DiagnosticKind::Missing => "missing",
+        };
+        format!(
+            "line {}:{} {} `{}`",
+            self.line, self.column, what, self.text
+        )
+    }
+}
+
 // ------------------------------------------------------------
 // 文件 Map
 // ------------------------------------------------------------
@@ -83,7 +441,112 @@ pub struct Symbol {
 pub struct FileMap {
     pub path: String,
     pub language: String,
+    /// 源码编码，非 UTF-8 或带 BOM 时记录 (如 `Shift_JIS`、`UTF-16LE`)
+    #[serde(skip_serializing_if = "Option::is_none", default)]
+    pub encoding: Option<String>,
     pub symbols: Vec<Symbol>,
+    /// 入口点，按行号排序
+    #[serde(skip_serializing_if = "Vec::is_empty", default)]
+    pub entry_points: Vec<EntryPoint>,
+    /// 语法错误总数 (ERROR 与 MISSING 节点)
+    #[serde(skip_serializing_if = "is_zero", default)]
+    pub parse_errors: usize,
+    /// 语法错误位置，最多记录前 20 个
+    #[serde(skip_serializing_if = "Vec::is_empty", default)]
+    pub diagnostics: Vec<Diagnostic>,
+}
+
+impl FileMap {
+    /// 空的文件符号表
+    pub fn new(path: &str, language: &str) -> Self {
+        Self {
+            path: path.to_string(),
+            language: language.to_string(),
+            encoding: None,
+            symbols: Vec::new(),
+            entry_points: Vec::new(),
+            parse_errors: 0,
+            diagnostics: Vec::new(),
+        }
+    }
+
+    /// 递归过滤符号，被移除的符号连同其子树一起丢弃
+    pub fn retain_symbols(&mut self, keep: &impl Fn(&Symbol) -> bool) {
+        retain_tree(&mut self.symbols, keep);
+    }
+
+    /// 只保留匹配的符号 (连同子树) 以及包含匹配符号的祖先
+    pub fn keep_matching(&mut self, matches: &impl Fn(&Symbol) -> bool) {
+        keep_matching_tree(&mut self.symbols, matches);
+    }
+}
+
+fn retain_tree(symbols: &mut Vec<Symbol>, keep: &impl Fn(&Symbol) -> bool) {
+    symbols.retain(keep);
+    for sym in symbols.iter_mut() {
+        retain_tree(&mut sym.children, keep);
+    }
+}
+
+fn keep_matching_tree(symbols: &mut Vec<Symbol>, matches: &impl Fn(&Symbol) -> bool) {
+    symbols.retain_mut(|sym| {
+        if matches(sym) {
+            return true;
+        }
+        keep_matching_tree(&mut sym.children, matches);
+        !sym.children.is_empty()
+    });
+}
+
+// ------------------------------------------------------------
+// 测试用构造 (各模块单元测试共用)
+// ------------------------------------------------------------
+#[cfg(test)]
+impl Symbol {
+    /// 位于第 line 行的符号
+    pub fn at(name: &str, kind: SymbolKind, line: usize) -> Self {
+        let pos = Position { line, column: 0 };
+        let range = Range {
+            start: pos.clone(),
+            end: pos,
+        };
+        Self::new(name.to_string(), kind, range)
+    }
+
+    /// 范围结束于第 line 行
+    pub fn until(mut self, line: usize) -> Self {
+        self.range.end.line = line;
+        self
+    }
+
+    pub fn with_children(mut self, children: Vec<Symbol>) -> Self {
+        self.children = children;
+        self
+    }
+
+    pub fn with_attributes(mut self, attributes: &[&str]) -> Self {
+        self.attributes = attributes.iter().map(|a| a.to_string()).collect();
+        self
+    }
+
+    pub fn with_owner(mut self, owner: &str) -> Self {
+        self.owner = Some(owner.to_string());
+        self
+    }
+
+    pub fn with_supertypes(mut self, extends: &[&str], implements: &[&str]) -> Self {
+        self.extends = extends.iter().map(|s| s.to_string()).collect();
+        self.implements = implements.iter().map(|s| s.to_string()).collect();
+        self
+    }
+}
+
+#[cfg(test)]
+impl FileMap {
+    pub fn with_symbols(mut self, symbols: Vec<Symbol>) -> Self {
+        self.symbols = symbols;
+        self
+    }
 }
 
 // ============================================================
@@ -109,5 +572,114 @@ mod tests {
         assert_eq!(SymbolKind::EnumMember.as_str(), "enum_member");
         assert_eq!(SymbolKind::Type.as_str(), "type");
         assert_eq!(SymbolKind::Namespace.as_str(), "namespace");
+        assert_eq!(SymbolKind::Struct.as_str(), "struct");
+        assert_eq!(SymbolKind::Trait.as_str(), "trait");
+        assert_eq!(SymbolKind::Impl.as_str(), "impl");
+        assert_eq!(SymbolKind::Macro.as_str(), "macro");
+        assert_eq!(SymbolKind::Record.as_str(), "record");
+        assert_eq!(SymbolKind::Protocol.as_str(), "protocol");
+        assert_eq!(SymbolKind::Extension.as_str(), "extension");
+        assert_eq!(SymbolKind::Heading.as_str(), "heading");
+        assert_eq!(SymbolKind::CodeBlock.as_str(), "code_block");
+        assert_eq!(SymbolKind::Test.as_str(), "test");
+        assert_eq!(SymbolKind::Event.as_str(), "event");
+        assert_eq!(SymbolKind::Validation.as_str(), "validation");
+    }
+
+    #[test]
+    fn test_symbol_kind_serde_matches_as_str() {
+        for kind in SymbolKind::ALL {
+            let json = serde_json::to_string(kind).unwrap();
+            assert_eq!(json, format!("\"{}\"", kind.as_str()));
+        }
+    }
+
+    #[test]
+    fn test_lsp_kind() {
+        assert_eq!(SymbolKind::Class.lsp_kind(), 5);
+        assert_eq!(SymbolKind::Method.lsp_kind(), 6);
+        assert_eq!(SymbolKind::Function.lsp_kind(), 12);
+        assert_eq!(SymbolKind::Trait.lsp_kind(), 11);
+        assert_eq!(SymbolKind::Struct.lsp_kind(), 23);
+        assert_eq!(SymbolKind::EnumMember.lsp_kind(), 22);
+        assert_eq!(SymbolKind::Event.lsp_kind(), 24);
+        assert_eq!(SymbolKind::Type.lsp_kind(), 26);
+        assert_eq!(SymbolKind::Association.lsp_kind(), 8);
+        assert_eq!(SymbolKind::Callback.lsp_kind(), 24);
+    }
+
+    #[test]
+    fn test_symbol_json_roundtrip() {
+        let range = Range {
+            start: Position { line: 1, column: 0 },
+            end: Position { line: 3, column: 1 },
+        };
+        let symbol = Symbol::new("Foo".to_string(), SymbolKind::Struct, range);
+
+        let json = serde_json::to_value(&symbol).unwrap();
+        assert_eq!(json["kind"], "struct");
+        assert_eq!(json["lsp_kind"], 23);
+
+        let back: Symbol = serde_json::from_value(json).unwrap();
+        assert_eq!(back.kind, SymbolKind::Struct);
+    }
+
+    #[test]
+    fn test_signature_modifiers() {
+        let mut signature = Signature::default();
+        signature.apply_modifier("pub const unsafe");
+        signature.apply_modifier("suspend");
+        assert!(signature.is_const && signature.is_unsafe && signature.is_async);
+        assert!(!signature.is_static);
+
+        // 为 false 的标记不输出
+        let json = serde_json::to_value(&signature).unwrap();
+        assert_eq!(json["const"], true);
+        assert!(json.get("static").is_none());
+    }
+
+    #[test]
+    fn test_has_attribute() {
+        let symbol = Symbol::at("f", SymbolKind::Function, 1).with_attributes(&[
+            "tokio::test",
+            "derive(Debug, Clone)",
+            "get:JvmName(\"x\")",
+        ]);
+        assert!(symbol.has_attribute("test"));
+        assert!(symbol.has_attribute("tokio::test"));
+        assert!(symbol.has_attribute("derive"));
+        assert!(symbol.has_attribute("jvmname"));
+        assert!(!symbol.has_attribute("Debug"));
+        let fixture = Symbol::at("g", SymbolKind::Function, 1).with_attributes(&["pytest.fixture"]);
+        assert!(!fixture.has_attribute("test"));
+    }
+
+    #[test]
+    fn test_keep_matching_keeps_ancestors() {
+        let function = |name| Symbol::at(name, SymbolKind::Function, 1);
+        let tests = function("tests")
+            .with_attributes(&["cfg(test)"])
+            .with_children(vec![
+                function("it_works").with_attributes(&["test"]),
+                function("helper"),
+            ]);
+        let mut map = FileMap::new("lib.rs", "rust").with_symbols(vec![tests, function("main")]);
+        map.keep_matching(&|s| s.has_attribute("test"));
+
+        assert_eq!(map.symbols.len(), 1);
+        let names: Vec<_> = map.symbols[0]
+            .children
+            .iter()
+            .map(|s| s.name.as_str())
+            .collect();
+        assert_eq!(names, vec!["it_works"]);
+    }
+
+    #[test]
+    fn test_symbol_kind_from_name() {
+        for kind in SymbolKind::ALL {
+            assert_eq!(SymbolKind::from_name(kind.as_str()), Some(*kind));
+        }
+        assert_eq!(SymbolKind::from_name("type_class"), None);
     }
 }
@@ -0,0 +1,341 @@
+// ============================================================
+// TestMap: 测试识别与测试 → 被测代码映射
+// ============================================================
+
+use crate::detector::Language;
+use crate::symbol::{FileMap, Symbol, SymbolKind};
+use std::collections::{HashMap, HashSet};
+
+/// 标记测试函数的注解 (按名称或末段匹配，不区分大小写)
+/// - Rust `#[test]` / `#[tokio::test]`、JUnit / Kotlin / Swift Testing `@Test`、NUnit `[Test]`
+/// - JUnit 5 参数化测试、xUnit `[Fact]` / `[Theory]`、MSTest `[TestMethod]`、NUnit `[TestCase]`
+const TEST_ATTRIBUTES: &[&str] = &[
+    "test",
+    "ParameterizedTest",
+    "RepeatedTest",
+    "TestFactory",
+    "TestTemplate",
+    "TestCase",
+    "Fact",
+    "Theory",
+    "TestMethod",
+];
+
+/// 测试目录名 (不区分大小写)
+const TEST_DIRS: &[&str] = &["test", "tests", "spec", "specs", "__tests__"];
+
+/// 可作为被测目标的符号类型
+const TARGET_KINDS: &[SymbolKind] = &[
+    SymbolKind::Class,
+    SymbolKind::Function,
+    SymbolKind::Method,
+    SymbolKind::Constructor,
+    SymbolKind::Interface,
+    SymbolKind::Enum,
+    SymbolKind::Struct,
+    SymbolKind::Trait,
+    SymbolKind::Record,
+    SymbolKind::Protocol,
+    SymbolKind::Type,
+    SymbolKind::Macro,
+];
+
+/// 成员的所属类型 (目标名记为 `Type.member`)
+const TYPE_KINDS: &[SymbolKind] = &[
+    SymbolKind::Class,
+    SymbolKind::Interface,
+    SymbolKind::Enum,
+    SymbolKind::Struct,
+    SymbolKind::Trait,
+    SymbolKind::Impl,
+    SymbolKind::Record,
+    SymbolKind::Protocol,
+    SymbolKind::Extension,
+    SymbolKind::Module,
+];
+
+// ------------------------------------------------------------
+// 测试识别
+// ------------------------------------------------------------
+
+/// 按路径判断测试文件: 位于 test / tests / spec / __tests__ 目录下，
+/// 或文件名形如 `test_*`、`*_test`、`*_spec`、`*.test.*`、`*.spec.*`、`*Test(s)`、`*Spec`、`conftest`
+pub fn is_test_file(path: &str) -> bool {
+    let mut components: Vec<&str> = path.split(['/', '\\']).collect();
+    let file = components.pop().unwrap_or("");
+    if components
+        .iter()
+        .any(|dir| TEST_DIRS.iter().any(|t| dir.eq_ignore_ascii_case(t)))
+    {
+        return true;
+    }
+
+    let stem = file.split('.').next().unwrap_or(file);
+    let dotted = file
+        .split('.')
+        .skip(1)
+        .any(|part| part == "test" || part == "spec");
+    dotted
+        || stem.starts_with("test_")
+        || stem.ends_with("_test")
+        || stem.ends_with("_spec")
+        || stem.ends_with("Test")
+        || stem.ends_with("Tests")
+        || stem.ends_with("Spec")
+        || stem == "conftest"
+}
+
+/// 测试代码: 测试符号，以及 Rust `#[cfg(test)]` 条目
+pub fn is_test_code(sym: &Symbol) -> bool {
+    sym.kind == SymbolKind::Test || sym.attributes.iter().any(|attr| attr == "cfg(test)")
+}
+
+/// 将识别为测试的函数 / 方法标记为 test，并记录测试体中出现的标识符 (由 link_tests 解析)
+/// - 注解: TEST_ATTRIBUTES
+/// - Go: `_test.go` 中的 `TestXxx`、`BenchmarkXxx`、`FuzzXxx`、`ExampleXxx`
+/// - Python / Ruby / Swift / Objective-C: 测试文件或 `*TestCase` 子类中以 `test` 开头的函数与方法
+/// - JavaScript / TypeScript 的 describe / it 与 RSpec 块由查询直接标为 test
+pub fn mark_tests(map: &mut FileMap, lang: Language, code: &str) {
+    let test_file = is_test_file(&map.path);
+    let lines: Vec<&str> = code.lines().collect();
+    mark_tree(&mut map.symbols, lang, test_file, false, &lines);
+}
+
+fn mark_tree(
+    symbols: &mut [Symbol],
+    lang: Language,
+    test_file: bool,
+    in_test_case: bool,
+    lines: &[&str],
+) {
+    for sym in symbols {
+        let test_case = sym.supertypes().any(|name| is_test_case(name));
+        mark_tree(&mut sym.children, lang, test_file, test_case, lines);
+
+        if is_test(sym, lang, test_file || in_test_case) {
+            sym.kind = SymbolKind::Test;
+        }
+        // 只有最内层的测试记录引用，describe 等分组块不重复记录
+        if sym.kind == SymbolKind::Test && !sym.children.iter().any(|c| c.kind == SymbolKind::Test)
+        {
+            let body = lines
+                .get(sym.range.start.line - 1..sym.range.end.line.min(lines.len()))
+                .unwrap_or_default();
+            sym.covers = identifiers(body);
+        }
+    }
+}
+
+fn is_test(sym: &Symbol, lang: Language, test_scope: bool) -> bool {
+    if !matches!(sym.kind, SymbolKind::Function | SymbolKind::Method) {
+        return false;
+    }
+    if TEST_ATTRIBUTES.iter().any(|name| sym.has_attribute(name)) {
+        return true;
+    }
+    match lang {
+        Language::Go => test_scope && is_go_test_name(&sym.name),
+        Language::Python | Language::Ruby | Language::Swift | Language::ObjC => {
+            test_scope && sym.name.starts_with("test")
+        }
+        _ => false,
+    }
+}
+
+/// unittest / XCTest / Minitest 的测试基类
+fn is_test_case(name: &str) -> bool {
+    name.ends_with("TestCase") || name == "Minitest::Test" || name == "Test::Unit::TestCase"
+}
+
+/// Go 测试函数: 前缀之后为空或不以小写字母开头 (`TestParse`、`Test_parse`，不含 `Testify`)
+fn is_go_test_name(name: &str) -> bool {
+    ["Test", "Benchmark", "Fuzz", "Example"]
+        .iter()
+        .any(|prefix| {
+            name.strip_prefix(prefix)
+                .is_some_and(|rest| !rest.starts_with(|c: char| c.is_ascii_lowercase()))
+        })
+}
+
+/// 源码中的标识符，去重并保持首次出现顺序
+fn identifiers(lines: &[&str]) -> Vec<String> {
+    let mut seen = HashSet::new();
+    lines
+        .iter()
+        .flat_map(|line| line.split(|c: char| !(c.is_alphanumeric() || c == '_' || c == '$')))
+        .filter(|word| word.starts_with(|c: char| !c.is_ascii_digit()))
+        .filter(|word| seen.insert(*word))
+        .map(str::to_string)
+        .collect()
+}
+
+// ------------------------------------------------------------
+// 测试 → 被测代码映射
+// ------------------------------------------------------------
+
+/// 被测目标: 显示名与所属类型名
+struct Target {
+    display: String,
+    owner: Option<String>,
+}
+
+/// 将测试引用的标识符解析为同一语言族中的被测符号 (非测试文件中的非测试代码)
+/// - 顶层函数与类型按名称匹配
+/// - 成员要求所属类型名也出现在测试中 (`User::new` → `User.new`)
+/// - 解析不到的标识符丢弃
+pub fn link_tests(maps: &mut [FileMap]) {
+    // 语言族 → 名称 → 目标
+    let mut index: HashMap<String, HashMap<String, Vec<Target>>> = HashMap::new();
+    for map in maps.iter().filter(|m| !is_test_file(&m.path)) {
+        let targets = index.entry(family(&map.language).to_string()).or_default();
+        index_targets(&map.symbols, None, &mut |name, target| {
+            targets.entry(name).or_default().push(target);
+        });
+    }
+
+    let empty = HashMap::new();
+    for map in maps.iter_mut() {
+        let targets = index.get(family(&map.language)).unwrap_or(&empty);
+        link_tree(&mut map.symbols, targets);
+    }
+}
+
+fn index_targets(symbols: &[Symbol], owner: Option<&str>, add: &mut impl FnMut(String, Target)) {
+    for sym in symbols.iter().filter(|s| !is_test_code(s)) {
+        if TARGET_KINDS.contains(&sym.kind) {
+            add(
+                sym.name.clone(),
+                Target {
+                    display: match owner {
+                        Some(owner) => format!("{}.{}", owner, sym.name),
+                        None => sym.name.clone(),
+                    },
+                    owner: owner.map(str::to_string),
+                },
+            );
+        }
+        let owner = TYPE_KINDS.contains(&sym.kind).then_some(sym.name.as_str());
+        index_targets(&sym.children, owner, add);
+    }
+}
+
+fn link_tree(symbols: &mut [Symbol], targets: &HashMap<String, Vec<Target>>) {
+    for sym in symbols {
+        link_tree(&mut sym.children, targets);
+        if sym.covers.is_empty() {
+            continue;
+        }
+
+        let words: HashSet<&str> = sym.covers.iter().map(String::as_str).collect();
+        let mut covers: Vec<String> = Vec::new();
+        for word in &sym.covers {
+            for target in targets.get(word).into_iter().flatten() {
+                let owner_seen = target.owner.as_deref().is_none_or(|o| words.contains(o));
+                if owner_seen && !covers.contains(&target.display) {
+                    covers.push(target.display.clone());
+                }
+            }
+        }
+        sym.covers = covers;
+    }
+}
+
+/// 可互相引用的语言归为一族
+fn family(language: &str) -> &str {
+    match language {
+        "tsx" | "javascript" | "jsx" => "typescript",
+        "c" | "objc" => "cpp",
+        other => other,
+    }
+}
+
+// ============================================================
+// 单元测试
+// ============================================================
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_is_test_file() {
+        for path in [
+            "tests/cli.rs",
+            "src/test/java/FooTest.java",
+            "pkg/parse_test.go",
+            "test_parse.py",
+            "src/app.test.ts",
+            "spec/models/user_spec.rb",
+            "src/__tests__/app.js",
+            "Tests/AppTests/AppTests.swift",
+            "conftest.py",
+        ] {
+            assert!(is_test_file(path), "{}", path);
+        }
+        for path in [
+            "src/main.rs",
+            "src/testing.py",
+            "lib/contest.rb",
+            "latest.go",
+        ] {
+            assert!(!is_test_file(path), "{}", path);
+        }
+    }
+
+    #[test]
+    fn test_go_test_names() {
+        assert!(is_go_test_name("TestParse"));
+        assert!(is_go_test_name("Test_parse"));
+        assert!(is_go_test_name("BenchmarkParse"));
+        assert!(!is_go_test_name("Testify"));
+        assert!(!is_go_test_name("Parse"));
+    }
+
+    #[test]
+    fn test_mark_tests_by_language_rules() {
+        let code = "def test_parse():\n    parse()\n\ndef helper():\n    pass\n";
+        let mut file = FileMap::new("test_parse.py", "python").with_symbols(vec![
+            Symbol::at("test_parse", SymbolKind::Function, 1).until(2),
+            Symbol::at("helper", SymbolKind::Function, 4).until(5),
+        ]);
+        mark_tests(&mut file, Language::Python, code);
+        assert_eq!(file.symbols[0].kind, SymbolKind::Test);
+        assert_eq!(file.symbols[0].covers, vec!["def", "test_parse", "parse"]);
+        assert_eq!(file.symbols[1].kind, SymbolKind::Function);
+
+        // 非测试文件中只按注解识别
+        let test_parse = Symbol::at("test_parse", SymbolKind::Function, 1);
+        let mut file = FileMap::new("src/lib.rs", "rust").with_symbols(vec![test_parse]);
+        mark_tests(&mut file, Language::Rust, "fn test_parse() {}\n");
+        assert_eq!(file.symbols[0].kind, SymbolKind::Function);
+        file.symbols[0].attributes = vec!["tokio::test".to_string()];
+        mark_tests(&mut file, Language::Rust, "fn test_parse() {}\n");
+        assert_eq!(file.symbols[0].kind, SymbolKind::Test);
+    }
+
+    #[test]
+    fn test_link_tests_resolves_targets() {
+        let mut user = Symbol::at("User", SymbolKind::Class, 1).until(5);
+        user.children = vec![Symbol::at("new", SymbolKind::Method, 2)];
+        let mut test = Symbol::at("test_user", SymbolKind::Test, 1).until(3);
+        test.covers = ["let", "u", "User", "new", "parse", "assert"]
+            .iter()
+            .map(|w| w.to_string())
+            .collect();
+        let mut other = Symbol::at("test_other", SymbolKind::Test, 4).until(5);
+        other.covers = vec!["new".to_string(), "parse".to_string()];
+
+        let mut maps = vec![
+            FileMap::new("src/user.rs", "rust").with_symbols(vec![
+                user,
+                Symbol::at("parse", SymbolKind::Function, 7).until(9),
+            ]),
+            FileMap::new("tests/user.rs", "rust").with_symbols(vec![test, other]),
+        ];
+        link_tests(&mut maps);
+
+        let tests = &maps[1].symbols;
+        assert_eq!(tests[0].covers, vec!["User", "User.new", "parse"]);
+        // 所属类型未出现时成员不算引用
+        assert_eq!(tests[1].covers, vec!["parse"]);
+    }
+}
@@ -25,6 +25,12 @@ public:
         return email_.find('@') != std::string::npos;
     }
 
+    bool isAdmin() const;
+    bool operator==(const User& other) const;
+
+    template<typename T>
+    T get(const std::string& key) const;
+
 private:
     std::string name_;
     std::string email_;
@@ -92,12 +98,36 @@ using UserId = uint64_t;
 
 } // namespace app
 
+// 类外成员定义
+bool app::User::isAdmin() const {
+    return name_ == "admin";
+}
+
+bool app::User::operator==(const User& other) const {
+    return name_ == other.name_;
+}
+
+template<>
+void app::Repository<int>::add(int item) {
+    items_.push_back(item * 2);
+}
+
+template<>
+std::string app::User::get<std::string>(const std::string& key) const {
+    return key == "name" ? name_ : email_;
+}
+
 // 模板函数
 template<typename T>
 T max(T a, T b) {
     return (a > b) ? a : b;
 }
 
+template<>
+int max<int>(int a, int b) {
+    return a > b ? a : b;
+}
+
 int main() {
     app::UserService service;
     service.addUser(std::make_unique<app::User>("Alice", "alice@example.com"));
@@ -29,6 +29,7 @@ namespace Example.Test
     {
         public string Id { get; private set; }
         public string Name { get; set; }
+        [Obsolete("Use Name")]
         public string Email { get; set; }
 
         private readonly DateTime _createdAt;
@@ -70,10 +71,15 @@ namespace Example.Test
 
         public double Distance(Point other)
         {
-            var dx = X - other.X;
-            var dy = Y - other.Y;
-            return Math.Sqrt(dx * dx + dy * dy);
+            double Square(double v) => v * v;
+            return Math.Sqrt(Square(X - other.X) + Square(Y - other.Y));
         }
+
+        public double this[int axis] => axis == 0 ? X : Y;
+
+        public static Point operator +(Point a, Point b) => new Point(a.X + b.X, a.Y + b.Y);
+
+        public static explicit operator string(Point p) => $"({p.X}, {p.Y})";
     }
 
     public struct Address
@@ -131,6 +137,8 @@ namespace Example.Test
         {
             // Default implementation
         }
+
+        public event EventHandler Saved;
     }
 
     // --------------------------------------------------------
@@ -63,3 +63,13 @@ func main() {
 	service.AddUser(User{Name: "Alice", Email: "alice@example.com"})
 	fmt.Println(service.GetUser("Alice"))
 }
+
+// Map 泛型函数
+func Map[K comparable, V any](m map[K]V, f func(V) V) map[K]V {
+	return m
+}
+
+// Set 泛型类型
+type Set[T comparable] struct {
+	items map[T]struct{}
+}
@@ -86,3 +86,10 @@ enum UserRole {
  * 记录类 (Java 16+)
  */
 record UserRecord(String name, String email) {}
+
+/**
+ * 泛型容器
+ */
+class Box<T extends Comparable<T>> {
+    <R> R map(java.util.function.Function<T, R> f) { return null; }
+}
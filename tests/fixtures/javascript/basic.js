@@ -73,6 +73,12 @@ const utils = {
   },
 };
 
+// CommonJS 导出
+exports.formatName = function (name) {
+  return name.trim();
+};
+module.exports.VERSION = "1.0.0";
+
 // 导出
 export function exportedFunction() {
   return "exported";
@@ -94,6 +94,12 @@ sealed class Result<out T> {
     data class Error(val message: String) : Result<Nothing>()
 }
 
+// ------------------------------------------------------------
+// Value Class
+// ------------------------------------------------------------
+@JvmInline
+value class UserId(val value: String)
+
 // ------------------------------------------------------------
 // Extension Function
 // ------------------------------------------------------------
@@ -114,8 +120,15 @@ fun greet(user: User) {
 
 private fun internalHelper(): Boolean = true
 
+suspend fun fetchUser(id: String): User? = null
+
 // ------------------------------------------------------------
 // Top-level Properties
 // ------------------------------------------------------------
 val DEFAULT_NAME = "Guest"
 const val MAX_USERS = 100
+
+// ------------------------------------------------------------
+// Type Alias
+// ------------------------------------------------------------
+typealias UserMap = Map<String, User>
@@ -2,6 +2,13 @@
 Python 测试用例: 基础类和函数
 """
 
+from dataclasses import dataclass, field
+from functools import cached_property
+from typing import TypeAlias
+
+__all__ = ["User", "login", "Point"]
+__all__ += ["UserId"]
+
 
 class User:
     """用户类"""
@@ -16,6 +23,10 @@ class User:
     def to_dict(self) -> dict:
         return {"name": self.name, "email": self.email}
 
+    @property
+    def domain(self) -> str:
+        return self.email.split("@")[1]
+
 
 class Session:
     """会话类"""
@@ -34,6 +45,10 @@ class Session:
 
 def login(username: str, password: str) -> Session:
     """登录函数"""
+
+    def check(value: str) -> bool:
+        return bool(value)
+
     return Session()
 
 
@@ -49,3 +64,24 @@ async def fetch_user(user_id: int) -> User:
 
 DEFAULT_TIMEOUT = 30
 MAX_RETRIES = 3
+
+
+@dataclass(frozen=True)
+class Point:
+    """数据类"""
+
+    x: float
+    y: float = 0.0
+    tags: list[str] = field(default_factory=list)
+    ORIGIN = None
+
+    @cached_property
+    def norm(self) -> float:
+        return (self.x**2 + self.y**2) ** 0.5
+
+    class Meta:
+        ordering = "x"
+
+
+UserId: TypeAlias = int
+type Pair[T] = tuple[T, T]
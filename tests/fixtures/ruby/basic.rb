@@ -72,6 +72,42 @@ class User
   alias_method :valid_email?, :validate_email
 end
 
+# Rails 模型
+class Post < ApplicationRecord
+  include Publishable
+
+  belongs_to :author, class_name: 'User'
+  has_many :comments, dependent: :destroy
+
+  scope :published, -> { where(published: true) }
+
+  validates :title, presence: true
+  validates :body, :title, length: { maximum: 500 }
+  validate :author_must_be_active
+  before_save :normalize_title
+
+  delegate :name, to: :author, prefix: true
+
+  define_method(:summary) do
+    title.truncate(20)
+  end
+end
+
+# Concern
+module Publishable
+  extend ActiveSupport::Concern
+
+  included do
+    scope :drafts, -> { where(published: false) }
+  end
+
+  class_methods do
+    def publish_all
+      update_all(published: true)
+    end
+  end
+end
+
 # 使用示例
 if __FILE__ == $0
   service = UserService.new
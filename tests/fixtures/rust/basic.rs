@@ -3,6 +3,7 @@
 use std::collections::HashMap;
 
 /// 用户结构体
+#[derive(Debug, Clone)]
 pub struct User {
     pub name: String,
     pub email: String,
@@ -18,6 +19,9 @@ pub struct UserService {
     users: HashMap<String, User>,
 }
 
+/// 元组结构体
+pub struct Wrapper<T>(Vec<T>);
+
 impl UserService {
     /// 创建新服务
     pub fn new() -> Self {
@@ -43,6 +47,18 @@ impl Authenticatable for UserService {
     }
 }
 
+impl std::fmt::Display for User {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        write!(f, "{} <{}>", self.name, self.email)
+    }
+}
+
+impl<T> From<Vec<T>> for Wrapper<T> {
+    fn from(items: Vec<T>) -> Self {
+        Wrapper(items)
+    }
+}
+
 /// 常量
 pub const MAX_USERS: usize = 100;
 pub const DEFAULT_ROLE: &str = "user";
@@ -83,3 +99,14 @@ fn main() {
     let user = create_user!("Alice", "alice@example.com");
     service.add_user(user);
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_create_user() {
+        let user = create_user!("Bob", "bob@example.com");
+        assert_eq!(user.name, "Bob");
+    }
+}
@@ -71,4 +71,33 @@ export class AuthService {
   }
 }
 
+function formatUser(user: User): string;
+function formatUser(users: User[]): string;
+function formatUser(input: User | User[]): string {
+  return Array.isArray(input) ? input.map((u) => u.name).join(", ") : input.name;
+}
+
+namespace Validation {
+  export function isEmail(value: string): boolean {
+    return value.includes("@");
+  }
+}
+
+declare module "session-store" {
+  export function get(id: string): User | undefined;
+  export function get(id: number): User | undefined;
+}
+
+declare global {
+  interface Window {
+    currentUser?: User;
+  }
+}
+
 export default UserService;
+
+export class Cache<K extends string, V = unknown> {
+  get<T extends V>(key: K): T | undefined {
+    return undefined;
+  }
+}
@@ -3,8 +3,9 @@
 // ============================================================
 
 use std::fs;
+use std::io::Write;
 use std::path::PathBuf;
-use std::process::Command;
+use std::process::{Command, Stdio};
 use tempfile::TempDir;
 
 // ------------------------------------------------------------
@@ -23,6 +24,30 @@ fn run_cli(args: &[&str]) -> (String, String) {
     )
 }
 
+/// 在 dir 中运行，源码经 stdin 传入
+fn run_cli_stdin(dir: &std::path::Path, args: &[&str], input: &str) -> (String, String) {
+    let mut child = Command::new(env!("CARGO_BIN_EXE_agent-codemap"))
+        .args(args)
+        .current_dir(dir)
+        .stdin(Stdio::piped())
+        .stdout(Stdio::piped())
+        .stderr(Stdio::piped())
+        .spawn()
+        .expect("Failed to execute command");
+    child
+        .stdin
+        .take()
+        .unwrap()
+        .write_all(input.as_bytes())
+        .unwrap();
+    let output = child.wait_with_output().unwrap();
+
+    (
+        String::from_utf8_lossy(&output.stdout).to_string(),
+        String::from_utf8_lossy(&output.stderr).to_string(),
+    )
+}
+
 fn fixtures_path(lang: &str, file: &str) -> PathBuf {
     PathBuf::from(env!("CARGO_MANIFEST_DIR"))
         .join("tests/fixtures")
@@ -64,7 +89,10 @@ fn test_single_file_input() {
 
 #[test]
 fn test_directory_input() {
-    let dir = fixtures_path("python", "basic.py").parent().unwrap().to_path_buf();
+    let dir = fixtures_path("python", "basic.py")
+        .parent()
+        .unwrap()
+        .to_path_buf();
     let (stdout, _) = run_cli(&[dir.to_str().unwrap()]);
 
     assert_stdout_matches(&stdout, &expected_content("python"));
@@ -76,8 +104,8 @@ fn test_json_format() {
     let (stdout, _) = run_cli(&[file.to_str().unwrap(), "-f", "json"]);
 
     // 验证是有效 JSON
-    let parsed: serde_json::Value = serde_json::from_str(&stdout)
-        .expect("Output should be valid JSON");
+    let parsed: serde_json::Value =
+        serde_json::from_str(&stdout).expect("Output should be valid JSON");
 
     assert!(parsed.is_array());
     let arr = parsed.as_array().unwrap();
@@ -151,7 +179,10 @@ fn test_sorted_output() {
     let z_pos = stdout.find("# z.rs").unwrap();
     let m_pos = stdout.find("# sub/m.rs").unwrap();
 
-    assert!(m_pos < a_pos, "sub/m.rs should come before a.rs (dirs first)");
+    assert!(
+        m_pos < a_pos,
+        "sub/m.rs should come before a.rs (dirs first)"
+    );
     assert!(a_pos < z_pos, "a.rs should come before z.rs");
 }
 
@@ -174,6 +205,845 @@ fn test_multiple_files_concatenated() {
     assert!(stdout.contains("`b`"));
 }
 
+// ------------------------------------------------------------
+// 配置文件测试 (.codemap.toml)
+// ------------------------------------------------------------
+
+#[test]
+fn test_config_file_applied() {
+    let tmp = TempDir::new().unwrap();
+    let input = tmp.path();
+    let sub = input.join("sub");
+    fs::create_dir(&sub).unwrap();
+
+    fs::write(
+        input.join(".codemap.toml"),
+        "exclude = [\"skip.rs\"]\n\n[kinds.rust]\nconstant = false\n",
+    )
+    .unwrap();
+    fs::write(sub.join("keep.rs"), "const LIMIT: u32 = 1;\nfn keep() {}").unwrap();
+    fs::write(sub.join("skip.rs"), "fn skip() {}").unwrap();
+
+    // 从子目录运行，配置应向上查找
+    let (stdout, _) = run_cli(&[sub.to_str().unwrap()]);
+
+    assert!(stdout.contains("`keep`"));
+    assert!(!stdout.contains("LIMIT"));
+    assert!(!stdout.contains("skip.rs"));
+}
+
+#[test]
+fn test_cli_overrides_config() {
+    let tmp = TempDir::new().unwrap();
+    let input = tmp.path();
+
+    fs::write(input.join(".codemap.toml"), "format = \"json\"\n").unwrap();
+    fs::write(input.join("a.rs"), "fn a() {}").unwrap();
+
+    let (stdout, _) = run_cli(&[input.to_str().unwrap()]);
+    assert!(serde_json::from_str::<serde_json::Value>(&stdout).is_ok());
+
+    let (stdout, _) = run_cli(&[input.to_str().unwrap(), "-f", "markdown"]);
+    assert!(stdout.contains("# a.rs"));
+
+    let (stdout, _) = run_cli(&[input.to_str().unwrap(), "--no-config"]);
+    assert!(stdout.contains("# a.rs"));
+}
+
+#[test]
+fn test_config_token_budget() {
+    let tmp = TempDir::new().unwrap();
+    let input = tmp.path();
+
+    fs::write(input.join(".codemap.toml"), "budget = 30\n").unwrap();
+    for name in ["a", "b", "c", "d"] {
+        fs::write(
+            input.join(format!("{}.rs", name)),
+            "fn first() {}\nfn second() {}\n",
+        )
+        .unwrap();
+    }
+
+    let (stdout, _) = run_cli(&[input.to_str().unwrap()]);
+    assert!(stdout.starts_with("# a.rs\n"), "{}", stdout);
+    assert!(!stdout.contains("# d.rs"));
+    assert!(stdout.ends_with("more lines omitted\n"), "{}", stdout);
+
+    // 命令行优先
+    let (stdout, _) = run_cli(&[input.to_str().unwrap(), "--budget", "10000"]);
+    assert!(stdout.contains("# d.rs"));
+    assert!(!stdout.contains("Truncated"));
+
+    // JSON 保留完整的文件
+    let (stdout, stderr) = run_cli(&[input.to_str().unwrap(), "-f", "json"]);
+    let parsed: Vec<serde_json::Value> = serde_json::from_str(&stdout).unwrap();
+    assert!(parsed.len() < 4);
+    assert!(stderr.contains("Warning: output truncated to the 30 token budget"));
+}
+
+#[test]
+fn test_config_language_override() {
+    let tmp = TempDir::new().unwrap();
+    let input = tmp.path();
+
+    fs::write(input.join(".codemap.toml"), "[languages]\ninc = \"c\"\n").unwrap();
+    fs::write(input.join("defs.inc"), "int helper(void) { return 0; }").unwrap();
+
+    let (stdout, _) = run_cli(&[input.to_str().unwrap()]);
+    assert!(stdout.contains("# defs.inc"));
+    assert!(stdout.contains("`helper`"));
+}
+
+#[test]
+fn test_config_dsl_catalog() {
+    let tmp = TempDir::new().unwrap();
+    let input = tmp.path();
+
+    fs::write(
+        input.join(".codemap.toml"),
+        "[dsl.ruby]\nhas_paper_trail = \"callback\"\nacts_as_list = \"scope\"\n",
+    )
+    .unwrap();
+    fs::write(
+        input.join("post.rb"),
+        "class Post\n  has_many :comments\n  acts_as_list :position\n  audited :title\nend\n",
+    )
+    .unwrap();
+
+    let (stdout, _) = run_cli(&[input.to_str().unwrap()]);
+    assert!(stdout.contains("- [association] `comments`: has_many (line 2)"));
+    assert!(stdout.contains("- [scope] `position`: acts_as_list (line 3)"));
+    // 不在 catalog 中的调用不生成符号
+    assert!(!stdout.contains("`title`"), "{}", stdout);
+}
+
+// ------------------------------------------------------------
+// 用户查询测试 (--queries-dir)
+// ------------------------------------------------------------
+
+#[test]
+fn test_queries_dir_extends_builtin() {
+    let tmp = TempDir::new().unwrap();
+    let queries = tmp.path().join("queries");
+    let src = tmp.path().join("src");
+    fs::create_dir(&queries).unwrap();
+    fs::create_dir(&src).unwrap();
+
+    fs::write(
+        queries.join("rust.scm"),
+        "; extends\n(macro_invocation macro: (identifier) @name) @definition.function\n",
+    )
+    .unwrap();
+    fs::write(src.join("lib.rs"), "fn real() {}\nregister!(Plugin);\n").unwrap();
+
+    let (stdout, _) = run_cli(&[
+        src.to_str().unwrap(),
+        "--queries-dir",
+        queries.to_str().unwrap(),
+    ]);
+
+    assert!(stdout.contains("`real`"));
+    assert!(stdout.contains("`register`"));
+}
+
+#[test]
+fn test_queries_dir_from_config() {
+    let tmp = TempDir::new().unwrap();
+    let queries = tmp.path().join("q");
+    fs::create_dir(&queries).unwrap();
+
+    fs::write(tmp.path().join(".codemap.toml"), "queries_dir = \"q\"\n").unwrap();
+    fs::write(
+        queries.join("python.scm"),
+        "(class_definition name: (identifier) @name) @definition.class\n",
+    )
+    .unwrap();
+    fs::write(
+        tmp.path().join("m.py"),
+        "class A:\n    pass\n\ndef f():\n    pass\n",
+    )
+    .unwrap();
+
+    let (stdout, _) = run_cli(&[tmp.path().to_str().unwrap()]);
+
+    // 替换内置查询: 只剩类
+    assert!(stdout.contains("`A`"));
+    assert!(!stdout.contains("`f`"));
+}
+
+#[test]
+fn test_queries_dir_error_reported() {
+    let tmp = TempDir::new().unwrap();
+    fs::write(
+        tmp.path().join("go.scm"),
+        "(function_declaration\n  nme: (identifier) @name)\n",
+    )
+    .unwrap();
+    fs::write(tmp.path().join("main.go"), "package main\n").unwrap();
+
+    let (stdout, stderr) = run_cli(&[
+        tmp.path().to_str().unwrap(),
+        "--queries-dir",
+        tmp.path().to_str().unwrap(),
+    ]);
+
+    assert!(stdout.is_empty());
+    assert!(stderr.contains("go.scm:2:"), "{}", stderr);
+}
+
+// ------------------------------------------------------------
+// 跨文件聚合测试
+// ------------------------------------------------------------
+
+#[test]
+fn test_go_methods_grouped_across_package_files() {
+    let tmp = TempDir::new().unwrap();
+    let pkg = tmp.path().join("store");
+    fs::create_dir(&pkg).unwrap();
+
+    fs::write(
+        pkg.join("list.go"),
+        "package store\n\ntype List[T any] struct {\n\titems []T\n}\n",
+    )
+    .unwrap();
+    fs::write(
+        pkg.join("ops.go"),
+        "package store\n\nfunc (l *List[T]) Push(v T) {\n\tl.items = append(l.items, v)\n}\n",
+    )
+    .unwrap();
+
+    let (stdout, _) = run_cli(&[tmp.path().to_str().unwrap()]);
+
+    assert!(
+        stdout.contains("- [struct] `List`[T any] (line 3)\n  - [field] `items` (line 4)\n  - [method] `Push` (store/ops.go line 3)"),
+        "{}",
+        stdout
+    );
+}
+
+#[test]
+fn test_cpp_out_of_class_definitions_linked() {
+    let tmp = TempDir::new().unwrap();
+    fs::write(
+        tmp.path().join("user.hpp"),
+        "namespace app {\nclass User {\npublic:\n    void save();\n    bool operator==(const User& o) const;\n};\n}\n",
+    )
+    .unwrap();
+    fs::write(
+        tmp.path().join("user.cpp"),
+        "#include \"user.hpp\"\n\nnamespace app {\nvoid User::save() {}\n}\n\nbool app::User::operator==(const User& o) const { return true; }\n",
+    )
+    .unwrap();
+
+    let (stdout, _) = run_cli(&[tmp.path().to_str().unwrap()]);
+
+    assert!(
+        stdout.contains("  - [method] `save` (line 4, defined at user.cpp line 4)"),
+        "{}",
+        stdout
+    );
+    assert!(
+        stdout.contains("  - [method] `operator==` (line 5, defined at user.cpp line 7)"),
+        "{}",
+        stdout
+    );
+}
+
+#[test]
+fn test_c_pointer_prototypes_paired() {
+    let tmp = TempDir::new().unwrap();
+    fs::write(
+        tmp.path().join("user.h"),
+        "typedef struct User User;\n\nUser *create_user(const char *name);\nvoid free_user(User *user);\n",
+    )
+    .unwrap();
+    fs::write(
+        tmp.path().join("user.c"),
+        "#include \"user.h\"\n\nUser *create_user(const char *name) {\n    return 0;\n}\n\nvoid free_user(User *user) {}\n",
+    )
+    .unwrap();
+
+    let (stdout, _) = run_cli(&[tmp.path().to_str().unwrap()]);
+
+    // 返回指针的原型同样列出并与定义配对
+    assert!(
+        stdout.contains("- [function] `create_user` (line 3, defined at user.c line 3)"),
+        "{}",
+        stdout
+    );
+    assert!(
+        stdout.contains("- [function] `free_user` (line 4, defined at user.c line 7)"),
+        "{}",
+        stdout
+    );
+}
+
+#[test]
+fn test_objc_header_paired_with_implementation() {
+    let tmp = TempDir::new().unwrap();
+    fs::write(
+        tmp.path().join("User.h"),
+        "#import <Foundation/Foundation.h>\n\n@interface User : NSObject\n- (void)save;\n@end\n",
+    )
+    .unwrap();
+    fs::write(
+        tmp.path().join("User.m"),
+        "#import \"User.h\"\n\n@implementation User\n- (void)save {}\n@end\n",
+    )
+    .unwrap();
+
+    let (stdout, _) = run_cli(&[tmp.path().to_str().unwrap(), "-f", "json"]);
+    let json: serde_json::Value = serde_json::from_str(&stdout).unwrap();
+
+    let header = &json[0];
+    assert_eq!(header["path"], "User.h");
+    assert_eq!(header["language"], "objc");
+    let save = &header["symbols"][0]["children"][0];
+    assert_eq!(save["name"], "save");
+    assert_eq!(save["definition"]["path"], "User.m");
+    assert_eq!(save["definition"]["range"]["start"]["line"], 4);
+    assert_eq!(json[1]["symbols"].as_array().unwrap().len(), 0);
+}
+
+#[test]
+fn test_objc_selector_segments_not_methods() {
+    let tmp = TempDir::new().unwrap();
+    fs::write(
+        tmp.path().join("User.m"),
+        "@interface User : NSObject\n- (instancetype)initWithName:(NSString *)name email:(NSString *)email;\n@end\n\n@implementation User\n- (instancetype)initWithName:(NSString *)name email:(NSString *)email {\n    return self;\n}\n@end\n",
+    )
+    .unwrap();
+
+    let (stdout, _) = run_cli(&[tmp.path().to_str().unwrap(), "-f", "json"]);
+    let json: serde_json::Value = serde_json::from_str(&stdout).unwrap();
+
+    // 选择子的后续段 `email:` 是参数标签，不作为嵌套方法
+    let methods = json[0]["symbols"][0]["children"].as_array().unwrap();
+    assert_eq!(methods.len(), 1, "{}", stdout);
+    assert_eq!(methods[0]["name"], "initWithName");
+    assert!(methods[0].get("children").is_none(), "{}", stdout);
+    let params = methods[0]["signature"]["parameters"].as_array().unwrap();
+    assert_eq!(params[1]["label"], "email");
+}
+
+#[test]
+fn test_csharp_partial_class_grouped_by_file() {
+    let tmp = TempDir::new().unwrap();
+    fs::write(
+        tmp.path().join("User.cs"),
+        "namespace App {\n    public partial class User {\n        public string Name { get; set; }\n    }\n}\n",
+    )
+    .unwrap();
+    fs::write(
+        tmp.path().join("UserValidation.cs"),
+        "namespace App {\n    public partial class User {\n        public bool Validate() { return true; }\n    }\n}\n",
+    )
+    .unwrap();
+
+    let (stdout, _) = run_cli(&[tmp.path().to_str().unwrap()]);
+
+    assert!(
+        stdout.contains(
+            "  - [class] `User`: partial (line 2)\n    - [property] `Name` (line 3)\n    - [class] `User`: partial (UserValidation.cs line 2)\n      - [method] `Validate` (UserValidation.cs line 3)"
+        ),
+        "{}",
+        stdout
+    );
+}
+
+#[test]
+fn test_typescript_declaration_file() {
+    let tmp = TempDir::new().unwrap();
+    fs::write(
+        tmp.path().join("index.d.ts"),
+        "declare function parse(x: string): number;\ndeclare function parse(x: number): string;\nexport = parse;\n",
+    )
+    .unwrap();
+
+    let (stdout, _) = run_cli(&[tmp.path().to_str().unwrap(), "-f", "json"]);
+    let json: serde_json::Value = serde_json::from_str(&stdout).unwrap();
+
+    assert_eq!(json[0]["language"], "typescript");
+    let symbols = json[0]["symbols"].as_array().unwrap();
+    assert_eq!(symbols.len(), 1);
+    assert_eq!(symbols[0]["name"], "parse");
+    assert_eq!(symbols[0]["visibility"], "export");
+    assert_eq!(symbols[0]["overloads"][0]["start"]["line"], 2);
+}
+
+#[test]
+fn test_commonjs_exports_marked() {
+    let tmp = TempDir::new().unwrap();
+    fs::write(
+        tmp.path().join("index.cjs"),
+        "function open() {}\nfunction helper() {}\nmodule.exports = { open, close: () => {} };\n",
+    )
+    .unwrap();
+
+    let (stdout, _) = run_cli(&[tmp.path().to_str().unwrap(), "-f", "json"]);
+    let json: serde_json::Value = serde_json::from_str(&stdout).unwrap();
+
+    let exported: Vec<_> = json[0]["symbols"]
+        .as_array()
+        .unwrap()
+        .iter()
+        .filter(|s| s["visibility"] == "export")
+        .map(|s| s["name"].as_str().unwrap())
+        .collect();
+    assert_eq!(exported, vec!["open", "close"]);
+}
+
+#[test]
+fn test_with_attr_filter() {
+    let tmp = TempDir::new().unwrap();
+    fs::write(
+        tmp.path().join("lib.rs"),
+        "#[derive(Debug)]\npub struct User;\n\n#[cfg(test)]\nmod tests {\n    #[test]\n    fn it_works() {}\n\n    fn helper() {}\n}\n",
+    )
+    .unwrap();
+    fs::write(tmp.path().join("main.rs"), "fn main() {}\n").unwrap();
+
+    let (stdout, _) = run_cli(&[tmp.path().to_str().unwrap(), "--with-attr", "test"]);
+
+    assert_eq!(
+        stdout,
+        "# lib.rs\n\n- [module] `tests` @cfg(test) (line 5)\n  - [test] `it_works` @test (line 7)\n"
+    );
+}
+
+#[test]
+fn test_hierarchy_subcommand() {
+    let tmp = TempDir::new().unwrap();
+    fs::write(
+        tmp.path().join("Animal.java"),
+        "interface Named {}\nclass Animal implements Named {}\n",
+    )
+    .unwrap();
+    fs::write(
+        tmp.path().join("Dog.java"),
+        "class Dog extends Animal {}\nclass Puppy extends Dog {}\n",
+    )
+    .unwrap();
+
+    let (stdout, _) = run_cli(&["hierarchy", "Dog", tmp.path().to_str().unwrap()]);
+    assert_eq!(
+        stdout,
+        "# Dog\n\n- [class] `Dog` : Animal (Dog.java line 1)\n\n## Ancestors\n\n- [class] `Animal` : Named (Animal.java line 2)\n  - [interface] `Named` (Animal.java line 1)\n\n## Descendants\n\n- [class] `Puppy` : Dog (Dog.java line 2)\n"
+    );
+
+    let (stdout, _) = run_cli(&[
+        "hierarchy",
+        "Named",
+        tmp.path().to_str().unwrap(),
+        "-f",
+        "json",
+    ]);
+    let json: serde_json::Value = serde_json::from_str(&stdout).unwrap();
+    assert_eq!(json["descendants"][0]["name"], "Animal");
+    assert_eq!(json["descendants"][0]["children"][0]["name"], "Dog");
+}
+
+#[test]
+fn test_tests_filters_and_mapping() {
+    let tmp = TempDir::new().unwrap();
+    fs::write(
+        tmp.path().join("math.ts"),
+        "export function add(a: number, b: number) { return a + b; }\n",
+    )
+    .unwrap();
+    fs::write(
+        tmp.path().join("math.test.ts"),
+        "import { add } from './math';\n\ndescribe('math', () => {\n  it('adds', () => {\n    expect(add(1, 2)).toBe(3);\n  });\n});\n",
+    )
+    .unwrap();
+    let dir = tmp.path().to_str().unwrap();
+
+    let (stdout, _) = run_cli(&[dir, "--tests-only"]);
+    assert_eq!(
+        stdout,
+        "# math.test.ts\n\n- [test] `math`: describe (line 3)\n  - [test] `adds`: it → add (line 4)\n"
+    );
+
+    let (stdout, _) = run_cli(&[dir, "--no-tests"]);
+    assert_eq!(stdout, "# math.ts\n\n- [function] `add` (line 1)\n");
+
+    let (stdout, _) = run_cli(&[dir, "-f", "json"]);
+    let json: serde_json::Value = serde_json::from_str(&stdout).unwrap();
+    assert_eq!(json[0]["symbols"][0]["children"][0]["covers"][0], "add");
+}
+
+#[test]
+fn test_entry_points_section() {
+    let tmp = TempDir::new().unwrap();
+    fs::write(
+        tmp.path().join("app.py"),
+        "@app.route(\"/users/<id>\")\ndef user(id):\n    pass\n\nif __name__ == \"__main__\":\n    app.run()\n",
+    )
+    .unwrap();
+    fs::create_dir(tmp.path().join("config")).unwrap();
+    fs::write(
+        tmp.path().join("config/routes.rb"),
+        "Rails.application.routes.draw do\n  get \"/health\", to: \"status#show\"\nend\n",
+    )
+    .unwrap();
+    let dir = tmp.path().to_str().unwrap();
+
+    let (stdout, _) = run_cli(&[dir]);
+    assert!(stdout.starts_with(
+        "# Entry points\n\n\
+         - [route] `GET /health` → status#show (config/routes.rb line 2)\n\
+         - [route] `GET /users/<id>` → user (app.py line 2)\n\
+         - [main] `__main__` (app.py line 5)\n\n# config/routes.rb\n"
+    ));
+
+    let (stdout, _) = run_cli(&[dir, "-f", "json"]);
+    let json: serde_json::Value = serde_json::from_str(&stdout).unwrap();
+    assert_eq!(json[0]["entry_points"][0]["name"], "GET /health");
+    assert_eq!(json[1]["entry_points"][0]["kind"], "route");
+    assert_eq!(json[1]["entry_points"][0]["handler"], "user");
+
+    // 过滤符号时不输出入口点
+    let (stdout, _) = run_cli(&[dir, "--with-attr", "app.route"]);
+    assert!(!stdout.contains("# Entry points"));
+}
+
+#[test]
+fn test_parse_errors_reported() {
+    let tmp = TempDir::new().unwrap();
+    fs::write(tmp.path().join("bad.c"), "int main() { return 0 }\n").unwrap();
+    fs::write(
+        tmp.path().join("good.c"),
+        "int add(int a, int b) { return a + b; }\n",
+    )
+    .unwrap();
+    let dir = tmp.path().to_str().unwrap();
+
+    let (stdout, stderr) = run_cli(&[dir]);
+    assert!(
+        stdout.contains("# bad.c\n\n> 1 parse error: line 1:21 missing `;`\n\n- [function] `main`")
+    );
+    assert!(
+        stderr.contains("Parsed 2 files: 1 clean, 1 with syntax errors"),
+        "{}",
+        stderr
+    );
+    assert!(stderr.contains("bad.c: 1 parse error, first at line 1:21 missing `;`"));
+
+    let (stdout, _) = run_cli(&[dir, "-f", "json"]);
+    let json: serde_json::Value = serde_json::from_str(&stdout).unwrap();
+    assert_eq!(json[0]["parse_errors"], 1);
+    assert_eq!(json[0]["diagnostics"][0]["kind"], "missing");
+    assert!(json[1].get("parse_errors").is_none());
+
+    // --strict: 有语法错误时失败
+    let output = Command::new(env!("CARGO_BIN_EXE_agent-codemap"))
+        .args([dir, "--strict"])
+        .output()
+        .unwrap();
+    assert!(!output.status.success());
+    assert!(
+        String::from_utf8_lossy(&output.stderr).contains("--strict: 1 of 2 files failed to parse")
+    );
+}
+
+#[test]
+fn test_non_utf8_sources() {
+    let tmp = TempDir::new().unwrap();
+    // Latin-1: 列号为原文件中的字节偏移
+    fs::write(
+        tmp.path().join("latin.py"),
+        b"x = '\xE9t\xE9'; y = 1\ndef caf\xE9():\n    pass\n",
+    )
+    .unwrap();
+    // UTF-16LE + BOM
+    let mut utf16 = vec![0xFF, 0xFE];
+    utf16.extend(
+        "class Greeter {}\n"
+            .encode_utf16()
+            .flat_map(|u| u.to_le_bytes()),
+    );
+    fs::write(tmp.path().join("Greeter.java"), utf16).unwrap();
+    let dir = tmp.path().to_str().unwrap();
+
+    let (stdout, stderr) = run_cli(&[dir]);
+    assert!(!stderr.contains("Warning"), "{}", stderr);
+    assert!(stdout.contains("- [class] `Greeter` (line 1)"));
+    assert!(stdout.contains("- [function] `café` (line 2)"));
+
+    let (stdout, _) = run_cli(&[dir, "-f", "json"]);
+    let json: serde_json::Value = serde_json::from_str(&stdout).unwrap();
+    assert_eq!(json[0]["path"], "Greeter.java");
+    assert_eq!(json[0]["encoding"], "UTF-16LE");
+    // BOM 2 字节 + `class ` 6 个 UTF-16 单元
+    assert_eq!(json[0]["symbols"][0]["range"]["end"]["column"], 34);
+    assert_eq!(json[1]["encoding"], "windows-1252");
+    // `y` 在原文件中位于第 11 字节 (UTF-8 中为 13)
+    let y = &json[1]["symbols"][1];
+    assert_eq!(y["name"], "y");
+    assert_eq!(y["range"]["start"]["column"], 11);
+
+    // 配置按 glob 指定编码
+    fs::write(
+        tmp.path().join(".codemap.toml"),
+        "[encodings]\n\"*.py\" = \"shift_jis\"\n",
+    )
+    .unwrap();
+    let (stdout, _) = run_cli(&[dir, "-f", "json"]);
+    let json: serde_json::Value = serde_json::from_str(&stdout).unwrap();
+    assert_eq!(json[1]["encoding"], "Shift_JIS");
+}
+
+#[test]
+fn test_skip_generated_and_large_files() {
+    let tmp = TempDir::new().unwrap();
+    fs::write(
+        tmp.path().join("main.go"),
+        "package main\n\nfunc main() {}\n",
+    )
+    .unwrap();
+    fs::write(
+        tmp.path().join("api.go"),
+        "// Code generated by protoc-gen-go. DO NOT EDIT.\npackage main\n\nfunc Api() {}\n",
+    )
+    .unwrap();
+    fs::write(tmp.path().join("user.pb.go"), "package main\n").unwrap();
+    // 手写文件的注释提到 DO NOT EDIT 不算生成标记
+    fs::write(
+        tmp.path().join("reg.go"),
+        "// DO NOT EDIT the order below without updating docs\npackage main\n\nfunc Reg() {}\n",
+    )
+    .unwrap();
+    fs::write(
+        tmp.path().join("big.py"),
+        format!("x = 1\n{}", "# pad\n".repeat(400)),
+    )
+    .unwrap();
+    let dir = tmp.path().to_str().unwrap();
+
+    let (stdout, stderr) = run_cli(&[dir, "--max-file-size", "1K"]);
+    assert!(stdout.contains("# main.go") && stdout.contains("# reg.go"));
+    assert!(
+        !stdout.contains("api.go") && !stdout.contains("user.pb.go") && !stdout.contains("big.py")
+    );
+    assert!(
+        stderr.contains("Parsed 2 files: 2 clean; skipped 3 (2 generated, 1 too large)"),
+        "{}",
+        stderr
+    );
+    assert!(
+        stderr.contains("skipped api.go: generated (\"Code generated ... DO NOT EDIT.\" header)"),
+        "{}",
+        stderr
+    );
+    assert!(
+        stderr.contains("skipped user.pb.go: generated (*.pb.go)"),
+        "{}",
+        stderr
+    );
+    assert!(
+        stderr.contains("skipped big.py: too large (2406 bytes)"),
+        "{}",
+        stderr
+    );
+
+    // --no-skip 索引全部文件
+    let (stdout, _) = run_cli(&[dir, "--no-skip"]);
+    assert!(stdout.contains("# api.go") && stdout.contains("# big.py"));
+
+    // 直接给出的文件不跳过
+    let (stdout, _) = run_cli(&[tmp.path().join("api.go").to_str().unwrap()]);
+    assert!(stdout.contains("`Api`"));
+}
+
+#[test]
+fn test_content_based_language_detection() {
+    let tmp = TempDir::new().unwrap();
+    fs::create_dir_all(tmp.path().join("bin")).unwrap();
+    fs::create_dir_all(tmp.path().join("include")).unwrap();
+    fs::write(
+        tmp.path().join(".gitattributes"),
+        "include/*.h linguist-language=C++\n",
+    )
+    .unwrap();
+    fs::write(
+        tmp.path().join("bin/deploy"),
+        "#!/usr/bin/env python3\n\ndef deploy():\n    pass\n",
+    )
+    .unwrap();
+    fs::write(tmp.path().join("Rakefile"), "def build\nend\n").unwrap();
+    fs::write(tmp.path().join("Dockerfile"), "FROM rust\n").unwrap();
+    fs::write(
+        tmp.path().join("include/shape.h"),
+        "struct Shape {\n    int area() const;\n};\n",
+    )
+    .unwrap();
+    fs::write(
+        tmp.path().join("util.h"),
+        "/* vim: set ft=cpp : */\nstruct Util {\n    int size() const;\n};\n",
+    )
+    .unwrap();
+    let dir = tmp.path().to_str().unwrap();
+
+    let (stdout, stderr) = run_cli(&[dir]);
+    assert!(
+        stdout.contains("# bin/deploy") && stdout.contains("`deploy`"),
+        "{}",
+        stdout
+    );
+    assert!(
+        stdout.contains("# Rakefile") && stdout.contains("`build`"),
+        "{}",
+        stdout
+    );
+    assert!(!stdout.contains("Dockerfile"));
+    assert!(
+        stdout.contains("`area`") && stdout.contains("`size`"),
+        "{}",
+        stdout
+    );
+    assert!(stderr.contains("Parsed 4 files: 4 clean"), "{}", stderr);
+
+    // 单独给出的无扩展名脚本
+    let (stdout, _) = run_cli(&[tmp.path().join("bin/deploy").to_str().unwrap()]);
+    assert!(stdout.contains("`deploy`"));
+}
+
+#[test]
+fn test_stdin_buffer() {
+    let tmp = TempDir::new().unwrap();
+    let source = "class Greeter:\n    def greet(self):\n        pass\n";
+
+    // 路径不必存在，输出使用 --path-hint
+    let (stdout, stderr) = run_cli_stdin(
+        tmp.path(),
+        &["-", "--lang", "python", "--path-hint", "src/greeter.py"],
+        source,
+    );
+    assert!(
+        stdout.contains("# src/greeter.py") && stdout.contains("`greet`"),
+        "{}",
+        stdout
+    );
+    assert!(stderr.contains("Parsed 1 file: 1 clean"), "{}", stderr);
+
+    // 未给出 --lang 时按 --path-hint 检测
+    let (stdout, _) = run_cli_stdin(
+        tmp.path(),
+        &["-", "--path-hint", "greeter.py", "-f", "json"],
+        source,
+    );
+    let json: serde_json::Value = serde_json::from_str(&stdout).unwrap();
+    assert_eq!(json[0]["path"], "greeter.py");
+    assert_eq!(json[0]["language"], "python");
+
+    // 两者都没有时按内容检测，路径为 <stdin>
+    let (stdout, _) = run_cli_stdin(tmp.path(), &["-"], "#!/usr/bin/env ruby\ndef run\nend\n");
+    assert!(
+        stdout.contains("# <stdin>") && stdout.contains("`run`"),
+        "{}",
+        stdout
+    );
+
+    let (stdout, stderr) = run_cli_stdin(tmp.path(), &["-"], "hello\n");
+    assert!(stdout.is_empty());
+    assert!(
+        stderr.contains("Cannot detect the language of stdin; pass --lang"),
+        "{}",
+        stderr
+    );
+
+    let (_, stderr) = run_cli(&[tmp.path().to_str().unwrap(), "--lang", "python"]);
+    assert!(
+        stderr.contains("--lang and --path-hint only apply to stdin input"),
+        "{}",
+        stderr
+    );
+}
+
+#[test]
+fn test_multiple_roots_and_file_list() {
+    let tmp = TempDir::new().unwrap();
+    fs::create_dir_all(tmp.path().join("backend/src")).unwrap();
+    fs::create_dir_all(tmp.path().join("web")).unwrap();
+    fs::write(tmp.path().join("backend/src/main.rs"), "fn main() {}\n").unwrap();
+    fs::write(tmp.path().join("web/app.py"), "def app():\n    pass\n").unwrap();
+    fs::write(tmp.path().join("web/util.py"), "def util():\n    pass\n").unwrap();
+    let backend = tmp.path().join("backend");
+    let web = tmp.path().join("web/app.py");
+
+    // 多个输入: 目录下的文件加根标签，直接给出的文件用给出的路径
+    let (stdout, stderr) = run_cli_stdin(tmp.path(), &["./backend/", "web/app.py"], "");
+    assert!(
+        stdout.contains("# backend/src/main.rs") && stdout.contains("# web/app.py"),
+        "{}",
+        stdout
+    );
+    assert!(
+        stdout.contains("- [main] `main` (backend/src/main.rs line 1)"),
+        "{}",
+        stdout
+    );
+    assert!(stderr.contains("Parsed 2 files: 2 clean"), "{}", stderr);
+    let (stdout, _) = run_cli(&[
+        backend.to_str().unwrap(),
+        web.to_str().unwrap(),
+        "-f",
+        "json",
+    ]);
+    let json: serde_json::Value = serde_json::from_str(&stdout).unwrap();
+    assert!(json[0]["path"]
+        .as_str()
+        .unwrap()
+        .ends_with("backend/src/main.rs"));
+
+    // --files-from: NUL 分隔 (stdin) 或按行 (文件)，路径相对当前目录；列表中的目录忽略
+    let (stdout, stderr) = run_cli_stdin(
+        tmp.path(),
+        &["--files-from", "-"],
+        "web/util.py\0backend\0backend/src/main.rs\0",
+    );
+    assert!(
+        stdout.contains("# backend/src/main.rs") && stdout.contains("# web/util.py"),
+        "{}",
+        stdout
+    );
+    assert!(!stdout.contains("app.py"));
+    assert!(stderr.contains("Parsed 2 files: 2 clean"), "{}", stderr);
+    fs::write(tmp.path().join("files.txt"), "web/app.py\n").unwrap();
+    let (stdout, _) = run_cli_stdin(tmp.path(), &["--files-from", "files.txt"], "");
+    assert!(
+        stdout.contains("# web/app.py") && !stdout.contains("main.rs"),
+        "{}",
+        stdout
+    );
+
+    // 跨文件聚合在合并所有根之后进行: 测试映射到另一个根中的符号
+    fs::create_dir_all(tmp.path().join("tests")).unwrap();
+    fs::write(
+        tmp.path().join("web/config.py"),
+        "def parse_config():\n    pass\n",
+    )
+    .unwrap();
+    fs::write(
+        tmp.path().join("tests/test_config.py"),
+        "def test_parse():\n    parse_config()\n",
+    )
+    .unwrap();
+    let (stdout, _) = run_cli_stdin(tmp.path(), &["web", "tests"], "");
+    assert!(
+        stdout.contains("- [test] `test_parse` → parse_config (line 1)"),
+        "{}",
+        stdout
+    );
+
+    let (_, stderr) = run_cli_stdin(tmp.path(), &["backend", "-"], "");
+    assert!(
+        stderr.contains("stdin input (`-`) cannot be combined with other inputs"),
+        "{}",
+        stderr
+    );
+}
+
 // ------------------------------------------------------------
 // 语言支持测试 (精确比对)
 // ------------------------------------------------------------